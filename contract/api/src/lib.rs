@@ -0,0 +1,143 @@
+pub mod queries;
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path as UrlPath, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use rusqlite::Connection;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::queries::{
+    BountyFilter, BountySummary, NetworkStats, NodeFilter, NodeSummary, Page, Pagination, Payout,
+};
+
+// How often WebSocket streams check the database for newly indexed events
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone)]
+pub struct AppState {
+    pub db: Arc<Mutex<Connection>>,
+    pub poll_interval: Duration,
+}
+
+impl AppState {
+    // Opens the indexer's database, creating the schema if the indexer hasn't run yet
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let store = indexer::store::Store::open(path)?;
+        return Ok(AppState { db: Arc::new(Mutex::new(store.conn)), poll_interval: DEFAULT_POLL_INTERVAL });
+    }
+
+    fn query<T>(&self, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, ApiError> {
+        let conn = self.db.lock().map_err(|_| ApiError::Internal("database lock poisoned".to_string()))?;
+        return f(&conn).map_err(|e| ApiError::Internal(e.to_string()));
+    }
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound(String),
+    Internal(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            ApiError::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
+        };
+        return (status, Json(json!({ "error": message }))).into_response();
+    }
+}
+
+pub fn router(state: AppState) -> Router {
+    return Router::new()
+        .route("/bounties", get(list_bounties))
+        .route("/bounties/:bounty_id", get(get_bounty))
+        .route("/nodes", get(list_nodes))
+        .route("/nodes/:node_id", get(get_node))
+        .route("/nodes/:node_id/payouts", get(list_node_payouts))
+        .route("/stats", get(network_stats))
+        .route("/events/ws", get(stream_events))
+        .with_state(state);
+}
+
+async fn list_bounties(State(state): State<AppState>, Query(filter): Query<BountyFilter>) -> Result<Json<Page<BountySummary>>, ApiError> {
+    return state.query(|conn| queries::list_bounties(conn, &filter)).map(Json);
+}
+
+async fn get_bounty(State(state): State<AppState>, UrlPath(bounty_id): UrlPath<String>) -> Result<Json<BountySummary>, ApiError> {
+    return state
+        .query(|conn| queries::get_bounty(conn, &bounty_id))?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Bounty {} does not exist", bounty_id)));
+}
+
+async fn list_nodes(State(state): State<AppState>, Query(filter): Query<NodeFilter>) -> Result<Json<Page<NodeSummary>>, ApiError> {
+    return state.query(|conn| queries::list_nodes(conn, &filter)).map(Json);
+}
+
+async fn get_node(State(state): State<AppState>, UrlPath(node_id): UrlPath<String>) -> Result<Json<NodeSummary>, ApiError> {
+    return state
+        .query(|conn| queries::get_node(conn, &node_id))?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Node {} is not registered", node_id)));
+}
+
+async fn list_node_payouts(
+    State(state): State<AppState>,
+    UrlPath(node_id): UrlPath<String>,
+    Query(page): Query<Pagination>,
+) -> Result<Json<Page<Payout>>, ApiError> {
+    return state.query(|conn| queries::list_payouts_for_node(conn, &node_id, &page)).map(Json);
+}
+
+async fn network_stats(State(state): State<AppState>) -> Result<Json<NetworkStats>, ApiError> {
+    return state.query(queries::network_stats).map(Json);
+}
+
+#[derive(Deserialize)]
+struct StreamFilter {
+    node_id: Option<String>,
+}
+
+// Streams events indexed after the socket connects, e.g. /events/ws?node_id=node0.node.bob
+async fn stream_events(
+    State(state): State<AppState>,
+    Query(filter): Query<StreamFilter>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let after_id = state.query(queries::latest_event_id)?;
+    return Ok(ws.on_upgrade(move |socket| send_events(socket, state, filter.node_id, after_id)));
+}
+
+async fn send_events(mut socket: WebSocket, state: AppState, node_id: Option<String>, mut after_id: i64) {
+    let mut interval = tokio::time::interval(state.poll_interval);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            message = socket.recv() => match message {
+                // Clients don't send us anything, so anything but a ping means they're gone
+                Some(Ok(Message::Ping(_))) | Some(Ok(Message::Pong(_))) => continue,
+                _ => return,
+            },
+        }
+        let events = match state.query(|conn| queries::events_after(conn, after_id, node_id.as_deref())) {
+            Ok(events) => events,
+            Err(_) => return,
+        };
+        for event in events {
+            after_id = event.id;
+            let text = serde_json::to_string(&event).unwrap_or_default();
+            if socket.send(Message::Text(text)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
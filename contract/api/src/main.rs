@@ -0,0 +1,29 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+
+/// Serves the indexer's SQLite database over HTTP
+#[derive(Parser)]
+struct Args {
+    /// SQLite database written by the indexer
+    #[arg(long, default_value = "indexer.sqlite")]
+    db: PathBuf,
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+    /// How often WebSocket streams check for new events, in milliseconds
+    #[arg(long, default_value_t = 500)]
+    poll_interval_ms: u64,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let mut state = api::AppState::open(&args.db)?;
+    state.poll_interval = Duration::from_millis(args.poll_interval_ms);
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    println!("Serving {} on http://{}", args.db.display(), listener.local_addr()?);
+    axum::serve(listener, api::router(state)).await?;
+    return Ok(());
+}
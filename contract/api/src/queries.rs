@@ -0,0 +1,391 @@
+use rusqlite::types::ToSql;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const DEFAULT_LIMIT: u64 = 50;
+pub const MAX_LIMIT: u64 = 200;
+
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+pub struct Pagination {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+impl Pagination {
+    pub fn limit(&self) -> u64 {
+        return self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    }
+
+    pub fn offset(&self) -> u64 {
+        return self.offset.unwrap_or(0);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+// Amounts are strings since javascript doesn't support u128, same as the contract
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BountySummary {
+    pub bounty_id: String,
+    pub owner_id: String,
+    pub file_location: String,
+    pub file_download_protocol: String,
+    pub min_nodes: u64,
+    pub timeout_seconds: u64,
+    pub network_required: bool,
+    pub gpu_required: bool,
+    pub amt_storage: String,
+    pub amt_node_reward: String,
+    pub reward_policy: Value,
+    pub elected_nodes: Vec<String>,
+    pub status: String,
+    pub event_seq: u64,
+    pub created_height: u64,
+    pub closed_height: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct BountyFilter {
+    pub owner_id: Option<String>,
+    pub status: Option<String>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+impl BountyFilter {
+    pub fn page(&self) -> Pagination {
+        return Pagination { limit: self.limit, offset: self.offset };
+    }
+}
+
+// reputation is the share of a node's answers that succeeded, or None if it hasn't answered anything yet
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NodeSummary {
+    pub node_id: String,
+    pub owner_id: String,
+    pub allow_network: bool,
+    pub allow_gpu: bool,
+    pub absolute_timeout: u64,
+    pub deposit: String,
+    pub online: bool,
+    pub removed: bool,
+    pub successful_runs: u64,
+    pub failed_runs: u64,
+    pub rejected_runs: u64,
+    pub reputation: Option<f64>,
+    pub registered_height: u64,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct NodeFilter {
+    pub owner_id: Option<String>,
+    pub allow_network: Option<bool>,
+    pub allow_gpu: Option<bool>,
+    pub online: Option<bool>,
+    // Removed nodes are left out unless this is set
+    pub include_removed: Option<bool>,
+    // Only nodes that can run bounties at least this long
+    pub min_timeout: Option<u64>,
+    pub min_successful_runs: Option<u64>,
+    pub min_reputation: Option<f64>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+// Query strings can't be flattened into a nested struct, so the pagination fields are repeated on each filter
+impl NodeFilter {
+    pub fn page(&self) -> Pagination {
+        return Pagination { limit: self.limit, offset: self.offset };
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Payout {
+    pub bounty_id: String,
+    pub node_id: String,
+    pub owner_id: String,
+    pub amount: String,
+    pub fee: String,
+    pub audit_levy: String,
+    pub block_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NetworkStats {
+    pub nodes: u64,
+    pub online_nodes: u64,
+    pub removed_nodes: u64,
+    pub bounties: u64,
+    pub pending_bounties: u64,
+    pub successful_bounties: u64,
+    pub failed_bounties: u64,
+    pub cancelled_bounties: u64,
+    pub answers: u64,
+    pub payouts: u64,
+    pub total_paid_out: String,
+    pub total_fees: String,
+    pub indexed_height: Option<u64>,
+    pub event_nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexedEvent {
+    pub id: i64,
+    pub receipt_id: String,
+    pub block_height: u64,
+    pub event_nonce: Option<u64>,
+    pub event_seq: Option<u64>,
+    pub event: String,
+    pub data: Value,
+}
+
+// Builds up a WHERE clause and its parameters from optional filters
+#[derive(Default)]
+struct Conditions {
+    clauses: Vec<String>,
+    params: Vec<Box<dyn ToSql>>,
+}
+
+impl Conditions {
+    fn add<T: ToSql + 'static>(&mut self, clause: &str, value: Option<T>) {
+        if let Some(value) = value {
+            self.params.push(Box::new(value));
+            self.clauses.push(clause.replace('?', &format!("?{}", self.params.len())));
+        }
+    }
+
+    fn to_sql(&self) -> String {
+        if self.clauses.is_empty() {
+            return String::new();
+        }
+        return format!("WHERE {}", self.clauses.join(" AND "));
+    }
+
+    fn params(&self) -> Vec<&dyn ToSql> {
+        return self.params.iter().map(|param| param.as_ref()).collect();
+    }
+}
+
+fn json_column(row: &Row, index: usize) -> rusqlite::Result<Value> {
+    let text: String = row.get(index)?;
+    return Ok(serde_json::from_str(&text).unwrap_or(Value::Null));
+}
+
+const BOUNTY_COLUMNS: &str = "bounty_id, owner_id, file_location, file_download_protocol, min_nodes, timeout_seconds, network_required, gpu_required,
+    amt_storage, amt_node_reward, reward_policy, elected_nodes, status, event_seq, created_height, closed_height";
+
+fn bounty_from_row(row: &Row) -> rusqlite::Result<BountySummary> {
+    return Ok(BountySummary {
+        bounty_id: row.get(0)?,
+        owner_id: row.get(1)?,
+        file_location: row.get(2)?,
+        file_download_protocol: row.get(3)?,
+        min_nodes: row.get(4)?,
+        timeout_seconds: row.get(5)?,
+        network_required: row.get(6)?,
+        gpu_required: row.get(7)?,
+        amt_storage: row.get(8)?,
+        amt_node_reward: row.get(9)?,
+        reward_policy: json_column(row, 10)?,
+        elected_nodes: serde_json::from_value(json_column(row, 11)?).unwrap_or_default(),
+        status: row.get(12)?,
+        event_seq: row.get(13)?,
+        created_height: row.get(14)?,
+        closed_height: row.get(15)?,
+    });
+}
+
+pub fn list_bounties(conn: &Connection, filter: &BountyFilter) -> rusqlite::Result<Page<BountySummary>> {
+    let mut conditions = Conditions::default();
+    conditions.add("owner_id = ?", filter.owner_id.clone());
+    conditions.add("status = ?", filter.status.clone());
+    let total: u64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM bounties {}", conditions.to_sql()),
+        conditions.params().as_slice(),
+        |row| row.get(0),
+    )?;
+    let mut statement = conn.prepare(&format!(
+        "SELECT {} FROM bounties {} ORDER BY created_height DESC, bounty_id LIMIT {} OFFSET {}",
+        BOUNTY_COLUMNS,
+        conditions.to_sql(),
+        filter.page().limit(),
+        filter.page().offset()
+    ))?;
+    let items = statement
+        .query_map(conditions.params().as_slice(), bounty_from_row)?
+        .collect::<rusqlite::Result<Vec<BountySummary>>>()?;
+    return Ok(Page { items, total, limit: filter.page().limit(), offset: filter.page().offset() });
+}
+
+pub fn get_bounty(conn: &Connection, bounty_id: &str) -> rusqlite::Result<Option<BountySummary>> {
+    return conn
+        .query_row(&format!("SELECT {} FROM bounties WHERE bounty_id = ?1", BOUNTY_COLUMNS), params![bounty_id], bounty_from_row)
+        .optional();
+}
+
+// Answer counts are joined in so nodes can be filtered and sorted by reputation
+const NODE_QUERY: &str = "
+    SELECT n.node_id, n.owner_id, n.allow_network, n.allow_gpu, n.absolute_timeout, n.deposit, n.online, n.removed, n.registered_height,
+        successful_runs, failed_runs, rejected_runs,
+        CASE WHEN successful_runs + failed_runs + rejected_runs = 0 THEN NULL
+            ELSE CAST(successful_runs AS REAL) / (successful_runs + failed_runs + rejected_runs) END AS reputation
+    FROM nodes n
+    JOIN (
+        SELECT n.node_id,
+            COUNT(CASE WHEN a.status = 'SUCCESS' THEN 1 END) AS successful_runs,
+            COUNT(CASE WHEN a.status = 'FAILURE' THEN 1 END) AS failed_runs,
+            COUNT(CASE WHEN a.status = 'REJECT' THEN 1 END) AS rejected_runs
+        FROM nodes n LEFT JOIN answers a ON a.node_id = n.node_id
+        GROUP BY n.node_id
+    ) runs ON runs.node_id = n.node_id";
+
+fn node_from_row(row: &Row) -> rusqlite::Result<NodeSummary> {
+    return Ok(NodeSummary {
+        node_id: row.get(0)?,
+        owner_id: row.get(1)?,
+        allow_network: row.get(2)?,
+        allow_gpu: row.get(3)?,
+        absolute_timeout: row.get(4)?,
+        deposit: row.get(5)?,
+        online: row.get(6)?,
+        removed: row.get(7)?,
+        registered_height: row.get(8)?,
+        successful_runs: row.get(9)?,
+        failed_runs: row.get(10)?,
+        rejected_runs: row.get(11)?,
+        reputation: row.get(12)?,
+    });
+}
+
+pub fn list_nodes(conn: &Connection, filter: &NodeFilter) -> rusqlite::Result<Page<NodeSummary>> {
+    let mut conditions = Conditions::default();
+    conditions.add("owner_id = ?", filter.owner_id.clone());
+    conditions.add("allow_network = ?", filter.allow_network);
+    conditions.add("allow_gpu = ?", filter.allow_gpu);
+    conditions.add("online = ?", filter.online);
+    if !filter.include_removed.unwrap_or(false) {
+        conditions.add("removed = ?", Some(false));
+    }
+    conditions.add("absolute_timeout >= ?", filter.min_timeout);
+    conditions.add("successful_runs >= ?", filter.min_successful_runs);
+    conditions.add("reputation >= ?", filter.min_reputation);
+    let filtered = format!("SELECT * FROM ({}) {}", NODE_QUERY, conditions.to_sql());
+    let total: u64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM ({})", filtered),
+        conditions.params().as_slice(),
+        |row| row.get(0),
+    )?;
+    let mut statement = conn.prepare(&format!(
+        "{} ORDER BY reputation DESC NULLS LAST, successful_runs DESC, node_id LIMIT {} OFFSET {}",
+        filtered,
+        filter.page().limit(),
+        filter.page().offset()
+    ))?;
+    let items = statement
+        .query_map(conditions.params().as_slice(), node_from_row)?
+        .collect::<rusqlite::Result<Vec<NodeSummary>>>()?;
+    return Ok(Page { items, total, limit: filter.page().limit(), offset: filter.page().offset() });
+}
+
+pub fn get_node(conn: &Connection, node_id: &str) -> rusqlite::Result<Option<NodeSummary>> {
+    return conn
+        .query_row(&format!("SELECT * FROM ({}) WHERE node_id = ?1", NODE_QUERY), params![node_id], node_from_row)
+        .optional();
+}
+
+pub fn list_payouts_for_node(conn: &Connection, node_id: &str, page: &Pagination) -> rusqlite::Result<Page<Payout>> {
+    let total: u64 = conn.query_row("SELECT COUNT(*) FROM payouts WHERE node_id = ?1", params![node_id], |row| row.get(0))?;
+    let mut statement = conn.prepare(&format!(
+        "SELECT bounty_id, node_id, owner_id, amount, fee, audit_levy, block_height FROM payouts WHERE node_id = ?1
+         ORDER BY block_height DESC, bounty_id LIMIT {} OFFSET {}",
+        page.limit(),
+        page.offset()
+    ))?;
+    let items = statement
+        .query_map(params![node_id], |row| {
+            Ok(Payout {
+                bounty_id: row.get(0)?,
+                node_id: row.get(1)?,
+                owner_id: row.get(2)?,
+                amount: row.get(3)?,
+                fee: row.get(4)?,
+                audit_levy: row.get(5)?,
+                block_height: row.get(6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<Payout>>>()?;
+    return Ok(Page { items, total, limit: page.limit(), offset: page.offset() });
+}
+
+fn count(conn: &Connection, sql: &str) -> rusqlite::Result<u64> {
+    return conn.query_row(sql, [], |row| row.get(0));
+}
+
+// u128 amounts are stored as text, so they're summed here rather than in SQL
+fn sum_amounts(conn: &Connection, column: &str) -> rusqlite::Result<String> {
+    let mut statement = conn.prepare(&format!("SELECT {} FROM payouts", column))?;
+    let total: u128 = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .map(|amount| amount.map(|amount| amount.parse::<u128>().unwrap_or(0)))
+        .sum::<rusqlite::Result<u128>>()?;
+    return Ok(total.to_string());
+}
+
+pub fn network_stats(conn: &Connection) -> rusqlite::Result<NetworkStats> {
+    let checkpoint: Option<(u64, u64)> = conn
+        .query_row("SELECT height, event_nonce FROM checkpoint WHERE id = 1", [], |row| Ok((row.get(0)?, row.get(1)?)))
+        .optional()?;
+    return Ok(NetworkStats {
+        nodes: count(conn, "SELECT COUNT(*) FROM nodes WHERE removed = 0")?,
+        online_nodes: count(conn, "SELECT COUNT(*) FROM nodes WHERE removed = 0 AND online = 1")?,
+        removed_nodes: count(conn, "SELECT COUNT(*) FROM nodes WHERE removed = 1")?,
+        bounties: count(conn, "SELECT COUNT(*) FROM bounties")?,
+        pending_bounties: count(conn, "SELECT COUNT(*) FROM bounties WHERE status = 'Pending'")?,
+        successful_bounties: count(conn, "SELECT COUNT(*) FROM bounties WHERE status = 'Success'")?,
+        failed_bounties: count(conn, "SELECT COUNT(*) FROM bounties WHERE status = 'Failed'")?,
+        cancelled_bounties: count(conn, "SELECT COUNT(*) FROM bounties WHERE status = 'Cancelled'")?,
+        answers: count(conn, "SELECT COUNT(*) FROM answers")?,
+        payouts: count(conn, "SELECT COUNT(*) FROM payouts")?,
+        total_paid_out: sum_amounts(conn, "amount")?,
+        total_fees: sum_amounts(conn, "fee")?,
+        indexed_height: checkpoint.map(|(height, _)| height),
+        event_nonce: checkpoint.map(|(_, nonce)| nonce).unwrap_or(0),
+    });
+}
+
+pub fn latest_event_id(conn: &Connection) -> rusqlite::Result<i64> {
+    return conn.query_row("SELECT COALESCE(MAX(rowid), 0) FROM events", [], |row| row.get(0));
+}
+
+// Events indexed after after_id, optionally only those that mention node_id in node_id, node_ids or reward_recipients
+pub fn events_after(conn: &Connection, after_id: i64, node_id: Option<&str>) -> rusqlite::Result<Vec<IndexedEvent>> {
+    let mut statement = conn.prepare(
+        "SELECT rowid, receipt_id, block_height, event_nonce, event_seq, name, data FROM events
+         WHERE rowid > ?1 AND (?2 IS NULL
+            OR json_extract(data, '$.node_id') = ?2
+            OR EXISTS (SELECT 1 FROM json_each(data, '$.node_ids') WHERE value = ?2)
+            OR EXISTS (SELECT 1 FROM json_each(data, '$.reward_recipients') WHERE value = ?2))
+         ORDER BY rowid",
+    )?;
+    let events = statement
+        .query_map(params![after_id, node_id], |row| {
+            Ok(IndexedEvent {
+                id: row.get(0)?,
+                receipt_id: row.get(1)?,
+                block_height: row.get(2)?,
+                event_nonce: row.get(3)?,
+                event_seq: row.get(4)?,
+                event: row.get(5)?,
+                data: json_column(row, 6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<IndexedEvent>>>()?;
+    return Ok(events);
+}
@@ -0,0 +1,132 @@
+// Serves the indexer's fixture history and queries it over HTTP and WebSocket
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use api::queries::{BountySummary, NetworkStats, NodeSummary, Page, Payout};
+use futures_util::StreamExt;
+use indexer::source::DirectorySource;
+use indexer::store::Store;
+use serde_json::Value;
+
+fn index_history(db: &Path) {
+    let blocks_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../indexer/tests/fixtures/history");
+    let mut store = Store::open(db).unwrap();
+    indexer::index(&mut DirectorySource::new(blocks_dir), &mut store, "coordinator.near", 0, None).unwrap();
+}
+
+async fn serve(db: &Path) -> String {
+    let mut state = api::AppState::open(db).unwrap();
+    state.poll_interval = Duration::from_millis(20);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, api::router(state)).await.unwrap() });
+    return addr.to_string();
+}
+
+async fn get<T: serde::de::DeserializeOwned>(addr: &str, path: &str) -> T {
+    return reqwest::get(format!("http://{}{}", addr, path)).await.unwrap().json().await.unwrap();
+}
+
+#[tokio::test]
+async fn filters_and_paginates_bounties() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("indexer.sqlite");
+    index_history(&db);
+    let addr = serve(&db).await;
+
+    // The disputed bounty, its re-run, the audited bounty and its audit all succeeded
+    let page: Page<BountySummary> = get(&addr, "/bounties?owner_id=alice.near&status=Success").await;
+    assert_eq!(page.total, 4);
+    assert!(page.items.iter().all(|bounty| bounty.elected_nodes.len() == 3));
+    // Only the auction is still running
+    let page: Page<BountySummary> = get(&addr, "/bounties?status=Pending").await;
+    assert_eq!(page.items.iter().map(|bounty| bounty.bounty_id.as_str()).collect::<Vec<&str>>(), vec!["4-0.bounty.alice.near"]);
+    let page: Page<BountySummary> = get(&addr, "/bounties?limit=1&offset=5").await;
+    assert_eq!((page.total, page.items.len(), page.limit, page.offset), (5, 0, 1, 5));
+
+    let response = reqwest::get(format!("http://{}/bounties/missing.bounty.alice.near", addr)).await.unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn filters_nodes_by_capability_and_reputation() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("indexer.sqlite");
+    index_history(&db);
+    let addr = serve(&db).await;
+
+    // frank's node was removed, so only the other six are listed by default
+    let page: Page<NodeSummary> = get(&addr, "/nodes").await;
+    assert_eq!(page.total, 6);
+    let page: Page<NodeSummary> = get(&addr, "/nodes?include_removed=true").await;
+    assert_eq!(page.total, 7);
+    let page: Page<NodeSummary> = get(&addr, "/nodes?allow_gpu=true").await;
+    assert_eq!(page.items.iter().map(|node| node.node_id.as_str()).collect::<Vec<&str>>(), vec!["worker.node.bob.near"]);
+    // dave's node rejected a bounty, frank's was removed
+    let page: Page<NodeSummary> = get(&addr, "/nodes?min_reputation=0.9").await;
+    assert_eq!(
+        page.items.iter().map(|node| node.node_id.as_str()).collect::<Vec<&str>>(),
+        vec!["worker.node.heidi.near", "worker.node.bob.near", "worker.node.carol.near", "worker.node.erin.near", "worker.node.grace.near"]
+    );
+    assert_eq!(page.items[0].successful_runs, 2);
+    let page: Page<NodeSummary> = get(&addr, "/nodes?min_timeout=90000").await;
+    assert_eq!(page.items.iter().map(|node| node.node_id.as_str()).collect::<Vec<&str>>(), vec!["worker.node.bob.near"]);
+
+    let node: NodeSummary = get(&addr, "/nodes/worker.node.dave.near").await;
+    assert_eq!(node.reputation, Some(0.5));
+    assert_eq!(node.rejected_runs, 1);
+    let node: NodeSummary = get(&addr, "/nodes/worker.node.carol.near").await;
+    assert_eq!(node.reputation, Some(1.0));
+}
+
+#[tokio::test]
+async fn reports_payouts_and_stats() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("indexer.sqlite");
+    index_history(&db);
+    let addr = serve(&db).await;
+
+    let page: Page<Payout> = get(&addr, "/nodes/worker.node.erin.near/payouts").await;
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].bounty_id, "1-0.bounty.alice.near");
+    let grace: Page<Payout> = get(&addr, "/nodes/worker.node.grace.near/payouts").await;
+    assert_eq!(grace.total, 1);
+    let dave: Page<Payout> = get(&addr, "/nodes/worker.node.dave.near/payouts").await;
+    assert_eq!(dave.total, 1);
+    let stats: NetworkStats = get(&addr, "/stats").await;
+    assert_eq!((stats.nodes, stats.online_nodes, stats.removed_nodes), (6, 6, 1));
+    assert_eq!((stats.bounties, stats.pending_bounties, stats.successful_bounties), (5, 1, 4));
+    assert_eq!(stats.answers, 9);
+    let total: u128 = page.items.iter().chain(grace.items.iter()).chain(dave.items.iter()).map(|payout| payout.amount.parse::<u128>().unwrap()).sum();
+    assert_eq!(stats.total_paid_out, total.to_string());
+    assert_eq!(stats.indexed_height, Some(42));
+    assert_eq!(stats.event_nonce, 47);
+}
+
+#[tokio::test]
+async fn streams_new_events_for_a_node() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("indexer.sqlite");
+    index_history(&db);
+    let addr = serve(&db).await;
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/events/ws?node_id=worker.node.carol.near", addr)).await.unwrap();
+    // Give the server a moment to note the latest event id before new events are indexed
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let store = Store::open(&db).unwrap();
+    for (receipt_id, node_id) in [("r48", "worker.node.bob.near"), ("r49", "worker.node.carol.near")] {
+        store
+            .conn
+            .execute(
+                "INSERT INTO events (receipt_id, log_index, block_height, block_timestamp, event_nonce, event_seq, name, data)
+                 VALUES (?1, 0, 43, 0, NULL, NULL, 'node_status_changed', ?2)",
+                rusqlite::params![receipt_id, format!("{{\"node_id\":\"{}\",\"online\":false}}", node_id)],
+            )
+            .unwrap();
+    }
+
+    let message = tokio::time::timeout(Duration::from_secs(5), socket.next()).await.unwrap().unwrap().unwrap();
+    let event: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+    assert_eq!(event["receipt_id"], "r49");
+    assert_eq!(event["data"]["node_id"], "worker.node.carol.near");
+}
@@ -3,4 +3,4 @@
 echo ">> Building contract"
 
 rustup target add wasm32-unknown-unknown
-cargo build --workspace --target wasm32-unknown-unknown --release
+cargo build -p coordinator --target wasm32-unknown-unknown --release
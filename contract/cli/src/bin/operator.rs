@@ -0,0 +1,325 @@
+use clap::{Parser, Subcommand};
+use cli::ConnectionArgs;
+use coordinator_client::{
+    format_amount, parse_amount, AcceptanceRules, BountyStatus, CoordinatorClient, Node, PendingPayout,
+    SupportedDownloadProtocols,
+};
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+use serde::Serialize;
+
+/// Manage your nodes and collect their rewards
+#[derive(Parser)]
+struct Args {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List every node of an owner, online and offline, with its counters and earnings
+    Nodes {
+        /// Defaults to the signing account
+        #[arg(long)]
+        owner: Option<AccountId>,
+    },
+    /// Bring an offline node back online, making it electable again
+    Online {
+        node_id: AccountId,
+    },
+    /// Take a node offline, it won't be elected until it's brought back online
+    Offline {
+        node_id: AccountId,
+    },
+    /// Set the least a node will run a bounty for, in NEAR ("0.1N") or yoctoNEAR. It's only elected to bounties whose
+    /// reward per elected node covers min + per_second * timeout_seconds
+    Price {
+        node_id: AccountId,
+        #[arg(long, value_parser = parse_amount, default_value = "0")]
+        min: u128,
+        #[arg(long, value_parser = parse_amount, default_value = "0")]
+        per_second: u128,
+    },
+    /// Replace the rules for which bounties a node takes on, it's never elected to bounties they turn down. Run without
+    /// options to take any bounty again
+    Rules {
+        node_id: AccountId,
+        /// Only take bounties from this owner, can be repeated
+        #[arg(long = "allow-owner")]
+        allowed_owners: Vec<AccountId>,
+        /// Never take bounties from this owner, can be repeated
+        #[arg(long = "deny-owner")]
+        denied_owners: Vec<AccountId>,
+        /// Only download packages over this protocol (ipfs, https or git), can be repeated
+        #[arg(long = "protocol", value_parser = parse_protocol)]
+        allowed_protocols: Vec<SupportedDownloadProtocols>,
+        /// Longest bounty timeout_seconds to take, 0 for no limit
+        #[arg(long, default_value = "0")]
+        max_timeout: u64,
+        /// Only run packages pinned to one version: an ipfs cid, a git commit or an https sha256 digest
+        #[arg(long)]
+        require_pinned: bool,
+    },
+    /// Bid on an auctioned bounty for one of your nodes, in NEAR ("0.1N") or yoctoNEAR. Bidding again replaces the bid
+    Bid {
+        bounty_id: AccountId,
+        node_id: AccountId,
+        #[arg(value_parser = parse_amount)]
+        price: u128,
+    },
+    /// Accept an invite to a private pool for one of your nodes
+    Join {
+        pool_id: String,
+        node_id: AccountId,
+    },
+    /// Take one of your nodes out of a pool, or turn down its invite
+    Leave {
+        pool_id: String,
+        node_id: AccountId,
+    },
+    /// List the pools a node is a member of or invited to
+    Pools {
+        node_id: AccountId,
+    },
+    /// Collect every reward your nodes are owed for closed bounties
+    Collect {
+        /// Only print the payouts that would be claimed
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct NodeListing {
+    online: bool,
+    #[serde(flatten)]
+    node: Node,
+}
+
+#[derive(Serialize)]
+struct OwnerNodes {
+    owner_id: AccountId,
+    lifetime_earnings: U128,
+    nodes: Vec<NodeListing>,
+}
+
+#[derive(Serialize)]
+struct CollectReport {
+    dry_run: bool,
+    payouts: Vec<PendingPayout>,
+    total: U128,
+    // Payouts that couldn't be collected, with the reason
+    failed: Vec<(PendingPayout, String)>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let output = args.connection.output();
+    let client = args.connection.connect().await?;
+    match args.command {
+        Command::Nodes { owner } => {
+            let owner_id = owner.unwrap_or_else(|| client.signer_id());
+            let mut nodes: Vec<NodeListing> = vec![];
+            for node in client.get_nodes_for_owner(&owner_id).await? {
+                nodes.push(NodeListing { online: true, node });
+            }
+            for node in client.get_offline_nodes_for_owner(&owner_id).await? {
+                nodes.push(NodeListing { online: false, node });
+            }
+            nodes.sort_by(|a, b| a.node.id.cmp(&b.node.id));
+            let listing = OwnerNodes {
+                lifetime_earnings: client.get_lifetime_earnings_for_owner(&owner_id).await?.into(),
+                owner_id,
+                nodes,
+            };
+            output.print(&listing, describe_nodes);
+        }
+        Command::Online { node_id } => {
+            let node = client.set_node_offline(&node_id, false).await?;
+            output.print(&NodeListing { online: true, node }, |listing| format!("{} is online", listing.node.id));
+        }
+        Command::Offline { node_id } => {
+            let node = client.set_node_offline(&node_id, true).await?;
+            output.print(&NodeListing { online: false, node }, |listing| format!("{} is offline", listing.node.id));
+        }
+        Command::Price { node_id, min, per_second } => {
+            let node = client.set_node_price(&node_id, min, per_second).await?;
+            output.print(&NodeListing { online: true, node }, |listing| {
+                format!(
+                    "{} charges at least {} + {} per second",
+                    listing.node.id,
+                    format_amount(listing.node.min_reward),
+                    format_amount(listing.node.min_reward_per_second)
+                )
+            });
+        }
+        Command::Rules { node_id, allowed_owners, denied_owners, allowed_protocols, max_timeout, require_pinned } => {
+            let rules = AcceptanceRules {
+                allowed_owners,
+                denied_owners,
+                allowed_protocols,
+                max_timeout_seconds: max_timeout,
+                require_pinned_package: require_pinned,
+            };
+            let node = client.set_node_rules(&node_id, &rules).await?;
+            output.print(&node.acceptance_rules, |rules| describe_rules(&node_id, rules));
+        }
+        Command::Bid { bounty_id, node_id, price } => {
+            let bid = client.bid_on_bounty(&bounty_id, &node_id, price).await?;
+            output.print(&bid, |bid| format!("{} bid {} on {}", bid.node_id, format_amount(bid.price.0), bounty_id));
+        }
+        Command::Join { pool_id, node_id } => {
+            let pool = client.join_pool(&pool_id, &node_id).await?;
+            output.print(&pool, |pool| format!("{} joined pool {}", node_id, pool.id));
+        }
+        Command::Leave { pool_id, node_id } => {
+            let pool = client.leave_pool(&pool_id, &node_id).await?;
+            output.print(&pool, |pool| format!("{} left pool {}", node_id, pool.id));
+        }
+        Command::Pools { node_id } => {
+            let pools = client.get_pools_for_node(&node_id).await?;
+            output.print(&pools, |pools| {
+                let mut lines = vec![format!("{} is in {} pools", node_id, pools.len())];
+                for pool in pools {
+                    let status = if pool.is_member(&node_id) { "member" } else { "invited" };
+                    lines.push(format!("  {} ({}, owned by {})", pool.id, status, pool.owner_id));
+                }
+                return lines.join("\n");
+            });
+        }
+        Command::Collect { dry_run } => {
+            let payouts = find_claimable_payouts(&client).await?;
+            let mut failed = vec![];
+            let mut collected = vec![];
+            for payout in payouts {
+                if dry_run {
+                    collected.push(payout);
+                    continue;
+                }
+                match client.collect_reward(&payout.node_id, &payout.bounty_id).await {
+                    Ok(()) => collected.push(payout),
+                    Err(e) => failed.push((payout, e.to_string())),
+                }
+            }
+            let report = CollectReport {
+                dry_run,
+                total: collected.iter().map(|payout| payout.net.0).sum::<u128>().into(),
+                payouts: collected,
+                failed,
+            };
+            output.print(&report, describe_collect_report);
+            anyhow::ensure!(report.failed.is_empty(), "{} rewards could not be collected", report.failed.len());
+        }
+    }
+    return Ok(());
+}
+
+// Every closed bounty one of the signer's nodes was elected for, where should_collect_reward is true
+async fn find_claimable_payouts(client: &CoordinatorClient) -> anyhow::Result<Vec<PendingPayout>> {
+    let node_ids: Vec<AccountId> = client
+        .get_nodes_for_owner(&client.signer_id())
+        .await?
+        .into_iter()
+        .map(|node| node.id)
+        .collect();
+    let mut payouts = vec![];
+    for bounty in client.get_bounties().await? {
+        if bounty.status == BountyStatus::Pending {
+            continue;
+        }
+        for node_id in bounty.elected_nodes.iter().filter(|node_id| node_ids.contains(node_id)) {
+            match client.should_collect_reward(node_id, &bounty.id).await {
+                Ok(true) => payouts.push(client.get_pending_payout(node_id, &bounty.id).await?),
+                Ok(false) => {}
+                // should_collect_reward panics when the node didn't answer, already claimed, or payouts are locked
+                Err(e) if e.panic_message().is_some() => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    return Ok(payouts);
+}
+
+fn describe_nodes(listing: &OwnerNodes) -> String {
+    let mut lines = vec![format!(
+        "{} has {} nodes, lifetime earnings {}",
+        listing.owner_id,
+        listing.nodes.len(),
+        format_amount(listing.lifetime_earnings.0)
+    )];
+    for NodeListing { online, node } in &listing.nodes {
+        lines.push(format!(
+            "  {} {}: {} successful, {} failed, {} unanswered, {} rejected, timeout {}ms, network={} gpu={}, price {} + {}/s, earned {}",
+            if *online { "online " } else { "offline" },
+            node.id,
+            node.successful_runs,
+            node.failed_runs,
+            node.unanswered_runs,
+            node.rejected_runs,
+            node.absolute_timeout,
+            node.allow_network,
+            node.allow_gpu,
+            format_amount(node.min_reward),
+            format_amount(node.min_reward_per_second),
+            format_amount(node.lifetime_earnings)
+        ));
+    }
+    return lines.join("\n");
+}
+
+fn parse_protocol(s: &str) -> Result<SupportedDownloadProtocols, String> {
+    return match s.to_lowercase().as_str() {
+        "ipfs" => Ok(SupportedDownloadProtocols::IPFS),
+        "https" => Ok(SupportedDownloadProtocols::HTTPS),
+        "git" => Ok(SupportedDownloadProtocols::GIT),
+        _ => Err(format!("Unknown download protocol {}, expected ipfs, https or git", s)),
+    };
+}
+
+fn describe_rules(node_id: &AccountId, rules: &AcceptanceRules) -> String {
+    if rules == &AcceptanceRules::default() {
+        return format!("{} takes any bounty", node_id);
+    }
+    let mut lines = vec![format!("{} takes bounties", node_id)];
+    if !rules.allowed_owners.is_empty() {
+        lines.push(format!("  only from {:?}", rules.allowed_owners));
+    }
+    if !rules.denied_owners.is_empty() {
+        lines.push(format!("  never from {:?}", rules.denied_owners));
+    }
+    if !rules.allowed_protocols.is_empty() {
+        let protocols: Vec<String> = rules.allowed_protocols.iter().map(|protocol| protocol.to_string()).collect();
+        lines.push(format!("  downloaded over {}", protocols.join(", ")));
+    }
+    if rules.max_timeout_seconds > 0 {
+        lines.push(format!("  with a timeout of at most {} seconds", rules.max_timeout_seconds));
+    }
+    if rules.require_pinned_package {
+        lines.push("  whose package is pinned to one version".to_string());
+    }
+    return lines.join("
+");
+}
+
+fn describe_collect_report(report: &CollectReport) -> String {
+    let verb = if report.dry_run { "Would collect" } else { "Collected" };
+    let mut lines = vec![format!("{} {} rewards, {} in total", verb, report.payouts.len(), format_amount(report.total.0))];
+    for payout in &report.payouts {
+        lines.push(format!(
+            "  {} for {}: {} (fee {}, audit levy {})",
+            payout.node_id,
+            payout.bounty_id,
+            format_amount(payout.net.0),
+            format_amount(payout.fee.0),
+            format_amount(payout.audit_levy.0)
+        ));
+    }
+    for (payout, error) in &report.failed {
+        lines.push(format!("  failed {} for {}: {}", payout.node_id, payout.bounty_id, error));
+    }
+    return lines.join("\n");
+}
+
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand};
+use cli::output::{describe_bounty, Output};
+use cli::spec::BountySpec;
+use cli::ConnectionArgs;
+use coordinator_client::{format_amount, parse_amount, Blocklist, BountyStatus, CoordinatorClient, Pool};
+use near_sdk::{AccountId, Balance};
+use serde::Serialize;
+
+/// Create and manage bounties on a coordinator
+#[derive(Parser)]
+struct Args {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a bounty from a TOML spec, attaching amt_storage + amt_node_reward as the deposit
+    Create {
+        spec: PathBuf,
+    },
+    /// Poll a bounty until it closes, then print its result
+    Watch {
+        bounty_id: AccountId,
+        #[arg(long, default_value_t = 5)]
+        interval_secs: u64,
+        /// Give up after this long, waits forever if not set
+        #[arg(long)]
+        max_wait_secs: Option<u64>,
+    },
+    /// Print the result of a closed bounty
+    Result {
+        bounty_id: AccountId,
+    },
+    /// Add to a bounty's storage and/or node reward deposits, amounts are in NEAR ("0.5N") or yoctoNEAR
+    TopUp {
+        bounty_id: AccountId,
+        #[arg(long, value_parser = parse_amount)]
+        storage: Option<Balance>,
+        #[arg(long, value_parser = parse_amount)]
+        reward: Option<Balance>,
+    },
+    /// Replace the bounty's elected nodes that haven't answered, once they've had the bounty's timeout to answer
+    Reelect {
+        bounty_id: AccountId,
+    },
+    /// Close an auction once bidding is over, electing the cheapest bids
+    Award {
+        bounty_id: AccountId,
+    },
+    /// Cancel a pending bounty, the unused deposit is refunded
+    Cancel {
+        bounty_id: AccountId,
+    },
+    /// Keep nodes, or every node of an operator, from being elected for your bounties
+    Block {
+        #[arg(long = "node")]
+        node_ids: Vec<AccountId>,
+        #[arg(long = "owner")]
+        owner_ids: Vec<AccountId>,
+    },
+    /// Take nodes or operators off your blocklist
+    Unblock {
+        #[arg(long = "node")]
+        node_ids: Vec<AccountId>,
+        #[arg(long = "owner")]
+        owner_ids: Vec<AccountId>,
+    },
+    /// Print your blocklist
+    Blocklist,
+    /// Create a private node pool, bounties with its pool_id only elect its members
+    PoolCreate {
+        pool_id: String,
+    },
+    /// Invite a node to one of your pools, it joins once its operator accepts
+    PoolInvite {
+        pool_id: String,
+        node_id: AccountId,
+    },
+    /// Take a node out of one of your pools, or withdraw its invite
+    PoolRemove {
+        pool_id: String,
+        node_id: AccountId,
+    },
+    /// Print a pool's members and stats
+    Pool {
+        pool_id: String,
+    },
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct Progress {
+    bounty_id: AccountId,
+    status: BountyStatus,
+    successful_nodes: usize,
+    failed_nodes: usize,
+    unanswered_nodes: usize,
+}
+
+#[derive(Serialize)]
+struct BountyResult {
+    bounty_id: AccountId,
+    status: BountyStatus,
+    result: HashMap<String, u8>,
+    canonical_result: Option<String>,
+    agreeing_nodes: Vec<AccountId>,
+}
+
+#[derive(Serialize)]
+struct Deposits {
+    bounty_id: AccountId,
+    amt_storage: String,
+    amt_node_reward: String,
+}
+
+#[derive(Serialize)]
+struct ElectedNodes {
+    bounty_id: AccountId,
+    elected_nodes: Vec<AccountId>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let output = args.connection.output();
+    let client = args.connection.connect().await?;
+    match args.command {
+        Command::Create { spec } => {
+            let spec = BountySpec::from_toml(&std::fs::read_to_string(&spec)?)?;
+            let bounty = client.create_bounty(&spec.to_args()?).await?;
+            output.print(&bounty, |bounty| format!("Created {}", describe_bounty(bounty)));
+        }
+        Command::Watch { bounty_id, interval_secs, max_wait_secs } => {
+            watch(&client, output, &bounty_id, Duration::from_secs(interval_secs), max_wait_secs.map(Duration::from_secs)).await?;
+            print_result(&client, output, &bounty_id).await?;
+        }
+        Command::Result { bounty_id } => print_result(&client, output, &bounty_id).await?,
+        Command::TopUp { bounty_id, storage, reward } => {
+            anyhow::ensure!(storage.is_some() || reward.is_some(), "pass --storage and/or --reward");
+            if let Some(amount) = storage {
+                client.add_storage_deposit(&bounty_id, amount).await?;
+            }
+            if let Some(amount) = reward {
+                client.add_node_reward_deposit(&bounty_id, amount).await?;
+            }
+            let bounty = client.get_bounty(&bounty_id).await?;
+            let deposits = Deposits {
+                bounty_id,
+                amt_storage: bounty.amt_storage.to_string(),
+                amt_node_reward: bounty.amt_node_reward.to_string(),
+            };
+            output.print(&deposits, |_| {
+                format!(
+                    "Topped up {}, storage is now {} and node reward is now {}",
+                    bounty.id,
+                    format_amount(bounty.amt_storage),
+                    format_amount(bounty.amt_node_reward)
+                )
+            });
+        }
+        Command::Reelect { bounty_id } => {
+            client.reelect_unanswered_nodes(&bounty_id).await?;
+            let elected_nodes = client.get_bounty_elected_nodes(&bounty_id).await?;
+            output.print(&ElectedNodes { bounty_id, elected_nodes }, |elected| {
+                format!("Reelected {}, elected nodes: {}", elected.bounty_id, join(&elected.elected_nodes))
+            });
+        }
+        Command::Award { bounty_id } => {
+            client.award_bounty(&bounty_id).await?;
+            let bounty = client.get_bounty(&bounty_id).await?;
+            output.print(&bounty, |bounty| {
+                if bounty.elected_nodes.is_empty() {
+                    return format!("Not enough bids, cancelled {}", describe_bounty(bounty));
+                }
+                return format!("Awarded {}\n  elected:     {}", describe_bounty(bounty), join(&bounty.elected_nodes));
+            });
+        }
+        Command::Cancel { bounty_id } => {
+            client.cancel_bounty(&bounty_id).await?;
+            let bounty = client.get_bounty(&bounty_id).await?;
+            output.print(&bounty, |bounty| format!("Cancelled {}", describe_bounty(bounty)));
+        }
+        Command::Block { node_ids, owner_ids } => {
+            anyhow::ensure!(!node_ids.is_empty() || !owner_ids.is_empty(), "pass --node and/or --owner");
+            let blocklist = client.add_to_blocklist(&node_ids, &owner_ids).await?;
+            output.print(&blocklist, describe_blocklist);
+        }
+        Command::Unblock { node_ids, owner_ids } => {
+            anyhow::ensure!(!node_ids.is_empty() || !owner_ids.is_empty(), "pass --node and/or --owner");
+            let blocklist = client.remove_from_blocklist(&node_ids, &owner_ids).await?;
+            output.print(&blocklist, describe_blocklist);
+        }
+        Command::Blocklist => {
+            let blocklist = client.get_blocklist(&client.signer_id()).await?;
+            output.print(&blocklist, describe_blocklist);
+        }
+        Command::PoolCreate { pool_id } => {
+            let pool = client.create_pool(&pool_id).await?;
+            output.print(&pool, |pool| format!("Created pool {}", pool.id));
+        }
+        Command::PoolInvite { pool_id, node_id } => {
+            let pool = client.invite_to_pool(&pool_id, &node_id).await?;
+            output.print(&pool, |pool| format!("Invited {} to {}", node_id, describe_pool(pool)));
+        }
+        Command::PoolRemove { pool_id, node_id } => {
+            let pool = client.leave_pool(&pool_id, &node_id).await?;
+            output.print(&pool, |pool| format!("Removed {} from {}", node_id, describe_pool(pool)));
+        }
+        Command::Pool { pool_id } => {
+            let stats = client.get_pool_stats(&pool_id).await?;
+            let pool = client.get_pool(&pool_id).await?;
+            output.print(&stats, |stats| {
+                format!(
+                    "{}\n  online:      {} of {}\n  bounties:    {} ({} active)",
+                    describe_pool(&pool),
+                    stats.online_members,
+                    stats.members,
+                    stats.total_bounties,
+                    stats.active_bounties
+                )
+            });
+        }
+    }
+    return Ok(());
+}
+
+async fn progress(client: &CoordinatorClient, bounty_id: &AccountId) -> anyhow::Result<Progress> {
+    let bounty = client.get_bounty(bounty_id).await?;
+    return Ok(Progress {
+        bounty_id: bounty_id.clone(),
+        status: bounty.status,
+        successful_nodes: client.get_successful_nodes(bounty_id).await?.len(),
+        failed_nodes: client.get_failed_nodes(bounty_id).await?.len(),
+        unanswered_nodes: client.get_unanswered_nodes(bounty_id).await?.len(),
+    });
+}
+
+// Prints a line every time the bounty's answers change, until it's no longer pending
+async fn watch(
+    client: &CoordinatorClient,
+    output: Output,
+    bounty_id: &AccountId,
+    interval: Duration,
+    max_wait: Option<Duration>,
+) -> anyhow::Result<()> {
+    let started = Instant::now();
+    let mut last: Option<Progress> = None;
+    loop {
+        let current = progress(client, bounty_id).await?;
+        if last.as_ref() != Some(&current) {
+            output.print(&current, |p| {
+                format!(
+                    "{}: {}, {} successful, {} failed, {} unanswered",
+                    p.bounty_id, p.status, p.successful_nodes, p.failed_nodes, p.unanswered_nodes
+                )
+            });
+        }
+        if current.status != BountyStatus::Pending {
+            return Ok(());
+        }
+        if max_wait.is_some_and(|max_wait| started.elapsed() >= max_wait) {
+            anyhow::bail!("{} is still pending after {}s", bounty_id, started.elapsed().as_secs());
+        }
+        last = Some(current);
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn print_result(client: &CoordinatorClient, output: Output, bounty_id: &AccountId) -> anyhow::Result<()> {
+    let bounty = client.get_bounty(bounty_id).await?;
+    let aggregate = client.get_bounty_aggregate_result(bounty_id).await?;
+    let result = BountyResult {
+        bounty_id: bounty_id.clone(),
+        status: bounty.status,
+        result: client.get_bounty_result(bounty_id).await?,
+        canonical_result: aggregate.canonical_result,
+        agreeing_nodes: aggregate.agreeing_nodes,
+    };
+    output.print(&result, |result| {
+        let mut lines = vec![format!("{}: {}", result.bounty_id, result.status)];
+        if let Some(canonical_result) = &result.canonical_result {
+            lines.push(format!("  result: {} (agreed by {})", canonical_result, join(&result.agreeing_nodes)));
+        }
+        let mut answers: Vec<_> = result.result.iter().collect();
+        answers.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (answer, count) in answers {
+            lines.push(format!("  {} x {}", count, answer));
+        }
+        return lines.join("\n");
+    });
+    return Ok(());
+}
+
+fn join(ids: &[AccountId]) -> String {
+    return ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+}
+
+fn describe_pool(pool: &Pool) -> String {
+    return format!("pool {}\n  members:     {}\n  invited:     {}", pool.id, join(&pool.members), join(&pool.invited));
+}
+
+fn describe_blocklist(blocklist: &Blocklist) -> String {
+    return format!("blocked nodes:  {}\nblocked owners: {}", join(&blocklist.node_ids), join(&blocklist.owner_ids));
+}
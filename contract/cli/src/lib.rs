@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use coordinator_client::CoordinatorClient;
+use near_sdk::AccountId;
+
+pub mod output;
+pub mod spec;
+
+/// Where the coordinator lives and who signs the transactions, shared by every command
+#[derive(Args, Debug)]
+pub struct ConnectionArgs {
+    /// JSON RPC url, e.g. a local sandbox at http://localhost:3030 or https://rpc.testnet.near.org
+    #[arg(long, env = "NEAR_RPC_URL", default_value = "http://localhost:3030", global = true)]
+    pub rpc_url: String,
+    /// Account id of the coordinator contract
+    #[arg(long, env = "CONTRACT_NAME", global = true)]
+    pub coordinator: Option<AccountId>,
+    /// near-cli credentials file of the signing account, e.g. ~/.near-credentials/testnet/alice.testnet.json
+    #[arg(long, env = "NEAR_CREDENTIALS", global = true)]
+    pub credentials: Option<PathBuf>,
+    /// Print JSON instead of human readable output
+    #[arg(long, global = true)]
+    pub json: bool,
+}
+
+impl ConnectionArgs {
+    pub async fn connect(&self) -> anyhow::Result<CoordinatorClient> {
+        let coordinator = self
+            .coordinator
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("--coordinator or CONTRACT_NAME is required"))?;
+        let credentials = self
+            .credentials
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("--credentials or NEAR_CREDENTIALS is required"))?;
+        return Ok(CoordinatorClient::connect(&self.rpc_url, credentials, coordinator).await?);
+    }
+
+    pub fn output(&self) -> output::Output {
+        return if self.json { output::Output::Json } else { output::Output::Human };
+    }
+}
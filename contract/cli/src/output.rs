@@ -0,0 +1,33 @@
+use coordinator_client::{format_amount, Bounty};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    Human,
+    Json,
+}
+
+impl Output {
+    // Prints value as a single line of JSON, or the human readable version of it
+    pub fn print<T: Serialize>(&self, value: &T, human: impl FnOnce(&T) -> String) {
+        match self {
+            Output::Json => println!("{}", serde_json::to_string(value).expect("outputs always serialize")),
+            Output::Human => println!("{}", human(value)),
+        }
+    }
+}
+
+pub fn describe_bounty(bounty: &Bounty) -> String {
+    let mut lines = vec![
+        format!("bounty {} ({})", bounty.id, bounty.status),
+        format!("  owner:       {}", bounty.owner_id),
+        format!("  package:     {} ({})", bounty.file_location, bounty.file_download_protocol),
+        format!("  min nodes:   {}, timeout {}s", bounty.min_nodes, bounty.timeout_seconds),
+        format!("  storage:     {} ({} used)", format_amount(bounty.amt_storage), format_amount(bounty.get_amt_storage_used())),
+        format!("  node reward: {} ({})", format_amount(bounty.amt_node_reward), bounty.reward_policy),
+    ];
+    if bounty.network_required || bounty.gpu_required {
+        lines.push(format!("  requires:    network={} gpu={}", bounty.network_required, bounty.gpu_required));
+    }
+    return lines.join("\n");
+}
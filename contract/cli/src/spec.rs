@@ -0,0 +1,64 @@
+use anyhow::Context;
+use coordinator_client::{
+    parse_amount, AggregationFunction, BountyOptions, CreateBountyArgs, ResultType, RewardPolicy, SupportedDownloadProtocols,
+};
+use near_sdk::AccountId;
+use serde::Deserialize;
+
+/// A bounty described in TOML, see bounty.example.toml
+///
+/// Amounts are strings, either in NEAR ("1.5N") or yoctoNEAR, since TOML integers can't hold a yoctoNEAR amount.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BountySpec {
+    pub file_location: String,
+    pub file_download_protocol: SupportedDownloadProtocols,
+    pub min_nodes: u64,
+    pub timeout_seconds: u64,
+    #[serde(default)]
+    pub network_required: bool,
+    #[serde(default)]
+    pub gpu_required: bool,
+    pub amt_storage: String,
+    pub amt_node_reward: String,
+    pub reward_policy: Option<RewardPolicy>,
+    pub dispute_window_seconds: Option<u64>,
+    pub result_type: Option<ResultType>,
+    pub aggregation: Option<AggregationFunction>,
+    pub bidding_window_seconds: Option<u64>,
+    pub max_nodes_per_owner: Option<u64>,
+    pub pool_id: Option<String>,
+    pub excluded_nodes: Option<Vec<AccountId>>,
+}
+
+impl BountySpec {
+    pub fn from_toml(input: &str) -> anyhow::Result<Self> {
+        return toml::from_str(input).context("invalid bounty spec");
+    }
+
+    pub fn to_args(&self) -> anyhow::Result<CreateBountyArgs> {
+        let amt_storage = parse_amount(&self.amt_storage).map_err(anyhow::Error::msg).context("invalid amt_storage")?;
+        let amt_node_reward =
+            parse_amount(&self.amt_node_reward).map_err(anyhow::Error::msg).context("invalid amt_node_reward")?;
+        return Ok(CreateBountyArgs {
+            file_location: self.file_location.clone(),
+            file_download_protocol: self.file_download_protocol.clone(),
+            min_nodes: self.min_nodes,
+            timeout_seconds: self.timeout_seconds,
+            network_required: self.network_required,
+            gpu_required: self.gpu_required,
+            amt_storage,
+            amt_node_reward,
+            options: BountyOptions {
+                reward_policy: self.reward_policy.clone(),
+                dispute_window_seconds: self.dispute_window_seconds,
+                result_type: self.result_type.clone(),
+                aggregation: self.aggregation.clone(),
+                bidding_window_seconds: self.bidding_window_seconds,
+                max_nodes_per_owner: self.max_nodes_per_owner,
+                pool_id: self.pool_id.clone(),
+                excluded_nodes: self.excluded_nodes.clone(),
+            },
+        });
+    }
+}
@@ -0,0 +1,60 @@
+use cli::spec::BountySpec;
+use coordinator_client::{AggregationFunction, ResultType, RewardPolicy, SupportedDownloadProtocols};
+
+const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+#[test]
+fn parses_the_example_spec() {
+    let spec = BountySpec::from_toml(include_str!("../bounty.example.toml")).unwrap();
+    assert_eq!(spec.file_download_protocol, SupportedDownloadProtocols::GIT);
+    assert_eq!(spec.min_nodes, 2);
+    assert_eq!(spec.reward_policy, Some(RewardPolicy::EqualSplit));
+    assert_eq!(spec.result_type, Some(ResultType::String));
+    assert_eq!(spec.aggregation, Some(AggregationFunction::Mode));
+    let args = spec.to_args().unwrap();
+    assert_eq!(args.amt_storage, ONE_NEAR);
+    assert_eq!(args.amt_node_reward, ONE_NEAR);
+    assert_eq!(args.deposit(), 2 * ONE_NEAR);
+    assert!(args.network_required);
+    assert!(!args.gpu_required);
+}
+
+#[test]
+fn optional_fields_default() {
+    let spec = BountySpec::from_toml(
+        r#"
+        file_location = "https://example.com/Dockerfile"
+        file_download_protocol = "HTTPS"
+        min_nodes = 1
+        timeout_seconds = 30
+        amt_storage = "100000000000000000000000"
+        amt_node_reward = "0.25N"
+        reward_policy = { FirstN = { n = 1 } }
+        result_type = { Decimal = { tolerance = "0.01" } }
+        "#,
+    )
+    .unwrap();
+    let args = spec.to_args().unwrap();
+    assert!(!args.network_required && !args.gpu_required);
+    assert_eq!(args.amt_storage, ONE_NEAR / 10);
+    assert_eq!(args.amt_node_reward, ONE_NEAR / 4);
+    assert_eq!(args.options.reward_policy, Some(RewardPolicy::FirstN { n: 1 }));
+    assert_eq!(args.options.dispute_window_seconds, None);
+    assert_eq!(args.options.result_type, Some(ResultType::Decimal { tolerance: "0.01".to_string() }));
+    assert_eq!(args.options.aggregation, None);
+}
+
+#[test]
+fn rejects_unknown_fields_and_bad_amounts() {
+    let base = r#"
+        file_location = "https://example.com/Dockerfile"
+        file_download_protocol = "HTTPS"
+        min_nodes = 1
+        timeout_seconds = 30
+        amt_node_reward = "1N"
+    "#;
+    assert!(BountySpec::from_toml(&format!("{}\namt_storage = \"1N\"\nmin_node = 2", base)).is_err());
+    let spec = BountySpec::from_toml(&format!("{}\namt_storage = \"lots\"", base)).unwrap();
+    let error = spec.to_args().unwrap_err();
+    assert!(format!("{:#}", error).contains("invalid amt_storage"), "{:#}", error);
+}
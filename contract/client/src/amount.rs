@@ -0,0 +1,29 @@
+use near_sdk::Balance;
+
+const YOCTO_DIGITS: usize = 24;
+
+/// Parses an amount written either in NEAR ("1.5N", "2 NEAR", "0.1near") or in yoctoNEAR ("1000000000000000000000000")
+pub fn parse_amount(input: &str) -> Result<Balance, String> {
+    let trimmed = input.trim();
+    let lower = trimmed.to_lowercase();
+    let near = lower.strip_suffix("near").or_else(|| lower.strip_suffix('n'));
+    let Some(near) = near else {
+        return trimmed.parse::<Balance>().map_err(|_| format!("{} is not a yoctoNEAR amount or a NEAR amount like 1.5N", input));
+    };
+    let near = near.trim();
+    let (whole, fraction) = near.split_once('.').unwrap_or((near, ""));
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if whole.is_empty() && fraction.is_empty() || !digits(whole) || !digits(fraction) {
+        return Err(format!("{} is not a NEAR amount like 1.5N", input));
+    }
+    if fraction.len() > YOCTO_DIGITS {
+        return Err(format!("{} has more decimals than a yoctoNEAR", input));
+    }
+    let yocto = format!("{}{}{}", whole, fraction, "0".repeat(YOCTO_DIGITS - fraction.len()));
+    return yocto.parse::<Balance>().map_err(|_| format!("{} is too large", input));
+}
+
+/// Formats a yoctoNEAR amount for people, e.g. "1.5 N" or "100 mN"
+pub fn format_amount(amount: Balance) -> String {
+    return near_units::near::to_human(amount);
+}
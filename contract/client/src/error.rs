@@ -0,0 +1,173 @@
+use std::fmt;
+
+// Prefixes the chain puts in front of a contract's panic message. Calls report the first, views may report either.
+const CALL_PANIC_MARKER: &str = "Smart contract panicked: ";
+const VIEW_PANIC_MARKER: &str = "panic_msg: \"";
+
+/// Every way a call to the coordinator can fail
+///
+/// Contract panics are matched on the coordinator's require!/panic! messages, the original message is kept in each variant.
+/// Panics the client doesn't recognise (e.g. from a newer coordinator) are returned as `Panic`.
+#[derive(Debug)]
+pub enum ClientError {
+    BountyNotFound(String),
+    NodeNotFound(String),
+    NodeAlreadyRegistered(String),
+    // The signer isn't allowed to do this, e.g. a node owner cancelling someone else's bounty
+    Unauthorized(String),
+    // The attached deposit or an amount argument is wrong
+    InvalidDeposit(String),
+    NotEnoughNodes(String),
+    // The bounty isn't in the state the call needs, e.g. posting an answer to a closed bounty
+    InvalidBountyState(String),
+    NotElected(String),
+    AlreadyAnswered(String),
+    AlreadyClaimed(String),
+    NotEligible(String),
+    PayoutLocked(String),
+    StorageExhausted(String),
+    Panic(String),
+    // The transaction failed without a contract panic, e.g. it ran out of gas
+    Execution(String),
+    // Couldn't reach the chain, or couldn't decode what it sent back
+    Rpc(near_workspaces::error::Error),
+}
+
+impl ClientError {
+    /// Maps a contract panic message, e.g. "Bounty b1.coordinator.near does not exist", to a typed error
+    pub fn from_panic_message(message: &str) -> ClientError {
+        let message = message.to_string();
+        let contains = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+        if contains(&["Node already registered"]) {
+            return ClientError::NodeAlreadyRegistered(message);
+        }
+        if message.starts_with("Bounty ") && contains(&[" does not exist"])
+            || contains(&["Disputed bounty", "Audited bounty"]) && message.ends_with("does not exist")
+        {
+            return ClientError::BountyNotFound(message);
+        }
+        if message.starts_with("Node ") && (message.ends_with("does not exist") || message.ends_with("is not registered"))
+            || contains(&["Could not find node"])
+        {
+            return ClientError::NodeNotFound(message);
+        }
+        if message.starts_with("Only ") || contains(&["owner id must be the signer"]) {
+            return ClientError::Unauthorized(message);
+        }
+        if contains(&["Not enough nodes registered", "Not enough qualified nodes", "Total nodes cannot be greater"]) {
+            return ClientError::NotEnoughNodes(message);
+        }
+        if contains(&["Not enough storage"]) {
+            return ClientError::StorageExhausted(message);
+        }
+        if contains(&["Attached deposit", "Must include", "must be at least", "Node reward must", "Nothing to withdraw", "Cannot withdraw more"]) {
+            return ClientError::InvalidDeposit(message);
+        }
+        if contains(&["Payouts for this bounty are locked"]) {
+            return ClientError::PayoutLocked(message);
+        }
+        if contains(&["You are not an elected node", "You are not elected"]) {
+            return ClientError::NotElected(message);
+        }
+        if contains(&["already submitted an answer"]) {
+            return ClientError::AlreadyAnswered(message);
+        }
+        if contains(&["already claimed your payout"]) {
+            return ClientError::AlreadyClaimed(message);
+        }
+        if contains(&["not eligible to collect", "has not submitted an answer"]) {
+            return ClientError::NotEligible(message);
+        }
+        if contains(&[
+            "Bounty is complete",
+            "Bounty is already closed",
+            "Bounty must be",
+            "pending bounties",
+            "can only be disputed",
+            "cannot be cancelled",
+            "only be used on pending bounties",
+            "only view answers of bounties",
+        ]) {
+            return ClientError::InvalidBountyState(message);
+        }
+        return ClientError::Panic(message);
+    }
+
+    /// Maps the description of a failed transaction or view to a typed error
+    pub fn from_failure(description: &str) -> ClientError {
+        return match extract_panic_message(description) {
+            Some(message) => ClientError::from_panic_message(&message),
+            None => ClientError::Execution(description.to_string()),
+        };
+    }
+
+    /// The contract's panic message, if the contract panicked
+    pub fn panic_message(&self) -> Option<&str> {
+        return match self {
+            ClientError::BountyNotFound(m)
+            | ClientError::NodeNotFound(m)
+            | ClientError::NodeAlreadyRegistered(m)
+            | ClientError::Unauthorized(m)
+            | ClientError::InvalidDeposit(m)
+            | ClientError::NotEnoughNodes(m)
+            | ClientError::InvalidBountyState(m)
+            | ClientError::NotElected(m)
+            | ClientError::AlreadyAnswered(m)
+            | ClientError::AlreadyClaimed(m)
+            | ClientError::NotEligible(m)
+            | ClientError::PayoutLocked(m)
+            | ClientError::StorageExhausted(m)
+            | ClientError::Panic(m) => Some(m),
+            ClientError::Execution(_) | ClientError::Rpc(_) => None,
+        };
+    }
+}
+
+// Pulls the contract's own message out of the chain's error description.
+// Both markers are followed by the message inside a debug-printed string, so read up to the closing quote and unescape it.
+fn extract_panic_message(description: &str) -> Option<String> {
+    let start = description
+        .find(CALL_PANIC_MARKER)
+        .map(|i| i + CALL_PANIC_MARKER.len())
+        .or_else(|| description.find(VIEW_PANIC_MARKER).map(|i| i + VIEW_PANIC_MARKER.len()))?;
+    let mut message = String::new();
+    let mut chars = description[start..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => message.extend(chars.next()),
+            '"' => break,
+            _ => message.push(c),
+        }
+    }
+    return Some(message.trim_end().to_string());
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Execution(description) => write!(f, "transaction failed: {}", description),
+            ClientError::Rpc(e) => write!(f, "rpc error: {}", e),
+            _ => write!(f, "coordinator panicked: {}", self.panic_message().unwrap_or_default()),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            ClientError::Rpc(e) => Some(e),
+            _ => None,
+        };
+    }
+}
+
+impl From<near_workspaces::error::Error> for ClientError {
+    fn from(e: near_workspaces::error::Error) -> Self {
+        // View panics come back as rpc errors, surface them as the contract's error instead
+        let description = e.to_string();
+        return match extract_panic_message(&description) {
+            Some(message) => ClientError::from_panic_message(&message),
+            None => ClientError::Rpc(e),
+        };
+    }
+}
@@ -0,0 +1,648 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use near_sdk::{AccountId, Balance};
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::types::NearToken;
+use near_workspaces::Account;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+pub use coordinator::aggregation::{AggregateResult, AggregationFunction, ResultType};
+pub use coordinator::bounty::{
+    Bid, Bounty, BountyOptions, BountyStatus, DisputeStatus, NodeResponse, NodeResponseStatus, RewardPolicy, SupportedDownloadProtocols,
+};
+pub use coordinator::coordinator::{Blocklist, PendingPayout, QuotaUsage, Quotas, SolvencyReport};
+pub use coordinator::node::{AcceptanceRules, Node};
+pub use coordinator::pool::{Pool, PoolMembershipChange, PoolStats};
+pub use amount::{format_amount, parse_amount};
+pub use error::ClientError;
+
+pub mod amount;
+pub mod error;
+
+// register_node requires a refundable 1 NEAR deposit
+pub const NODE_REGISTRATION_DEPOSIT: Balance = 1_000_000_000_000_000_000_000_000;
+// Attached to create_pool, invite_to_pool and add_to_blocklist to pay for storage, the contract refunds what isn't used
+pub const STORAGE_DEPOSIT: Balance = 10_000_000_000_000_000_000_000;
+
+pub type Result<T, E = ClientError> = std::result::Result<T, E>;
+
+/// Arguments to create_bounty. Amounts are in yoctoNEAR, the client attaches amt_storage + amt_node_reward as the deposit.
+///
+/// Arguments
+/// * `file_location`: url of the package the nodes should run
+/// * `file_download_protocol`: how nodes should fetch file_location
+/// * `min_nodes`: how many answers are required before the bounty closes, the coordinator elects extra nodes on top of this
+/// * `timeout_seconds`: how long the bounty is expected to take
+/// * `network_required`, `gpu_required`: only nodes that allow these are elected
+/// * `amt_storage`: refundable deposit that pays for the bounty's storage
+/// * `amt_node_reward`: reward split between the nodes that get paid
+/// * `options`: optional settings, anything left as None takes the contract's default
+///   * `reward_policy`: how amt_node_reward is split, defaults to an equal split
+///   * `dispute_window_seconds`: how long after completion the bounty can be disputed, defaults to 0 so it can't be
+///   * `result_type`: how answers are parsed and compared, defaults to plain strings
+///   * `aggregation`: how the canonical result is picked from the answers, defaults to the most common answer
+///   * `bidding_window_seconds`: makes the bounty an auction, nodes bid for this long before the cheapest are elected
+///   * `max_nodes_per_owner`: most elected nodes that can share an owner, defaults to 1. 0 for no limit
+///   * `pool_id`: only elect members of this pool, which the signer must own
+///   * `excluded_nodes`: never elect these nodes, on top of the signer's blocklist
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateBountyArgs {
+    pub file_location: String,
+    pub file_download_protocol: SupportedDownloadProtocols,
+    pub min_nodes: u64,
+    pub timeout_seconds: u64,
+    pub network_required: bool,
+    pub gpu_required: bool,
+    pub amt_storage: Balance,
+    pub amt_node_reward: Balance,
+    pub options: BountyOptions,
+}
+
+impl CreateBountyArgs {
+    pub fn new(
+        file_location: String,
+        file_download_protocol: SupportedDownloadProtocols,
+        min_nodes: u64,
+        timeout_seconds: u64,
+        amt_storage: Balance,
+        amt_node_reward: Balance,
+    ) -> Self {
+        return CreateBountyArgs {
+            file_location,
+            file_download_protocol,
+            min_nodes,
+            timeout_seconds,
+            network_required: false,
+            gpu_required: false,
+            amt_storage,
+            amt_node_reward,
+            options: BountyOptions::default(),
+        };
+    }
+
+    pub fn deposit(&self) -> Balance {
+        return self.amt_storage + self.amt_node_reward;
+    }
+
+    // The contract takes amounts as strings since javascript doesn't support u128
+    fn to_json(&self) -> Value {
+        return json!({
+            "file_location": self.file_location,
+            "file_download_protocol": self.file_download_protocol,
+            "min_nodes": self.min_nodes,
+            "timeout_seconds": self.timeout_seconds,
+            "network_required": self.network_required,
+            "gpu_required": self.gpu_required,
+            "amt_storage": self.amt_storage.to_string(),
+            "amt_node_reward": self.amt_node_reward.to_string(),
+            "options": self.options,
+        });
+    }
+}
+
+/// Typed async client for a deployed coordinator. Every call is signed by `account`.
+#[derive(Clone)]
+pub struct CoordinatorClient {
+    pub account: Account,
+    pub contract_id: near_workspaces::AccountId,
+}
+
+impl CoordinatorClient {
+    pub fn new(account: Account, contract_id: &AccountId) -> Self {
+        return CoordinatorClient {
+            account,
+            contract_id: to_workspaces_id(contract_id),
+        };
+    }
+
+    /// Connects to any rpc endpoint, e.g. a local sandbox on http://localhost:3030, signing with a near-cli credentials file
+    pub async fn connect(rpc_url: &str, credentials_path: &Path, contract_id: &AccountId) -> Result<Self> {
+        let worker = near_workspaces::custom(rpc_url).await?;
+        let account = Account::from_file(credentials_path, &worker)?;
+        return Ok(CoordinatorClient::new(account, contract_id));
+    }
+
+    pub fn signer_id(&self) -> AccountId {
+        return self.account.id().as_str().parse().unwrap();
+    }
+
+    async fn view<T: DeserializeOwned>(&self, method: &str, args: Value) -> Result<T> {
+        let result = self.account.view(&self.contract_id, method).args_json(args).await?;
+        return Ok(result.json()?);
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, args: Value, deposit: Balance) -> Result<T> {
+        let result = self.transact(method, args, deposit).await?;
+        return Ok(result.json()?);
+    }
+
+    // For calls that return a Promise (i.e. a transfer) or nothing
+    async fn call_unit(&self, method: &str, args: Value, deposit: Balance) -> Result<()> {
+        self.transact(method, args, deposit).await?;
+        return Ok(());
+    }
+
+    async fn transact(&self, method: &str, args: Value, deposit: Balance) -> Result<near_workspaces::result::ExecutionSuccess> {
+        let outcome: ExecutionFinalResult = self
+            .account
+            .call(&self.contract_id, method)
+            .args_json(args)
+            .deposit(NearToken::from_yoctonear(deposit))
+            .max_gas()
+            .transact()
+            .await?;
+        return outcome.into_result().map_err(|failure| ClientError::from_failure(&failure.to_string()));
+    }
+
+    // Views
+
+    pub async fn get_node_count(&self) -> Result<u64> {
+        return self.view("get_node_count", json!({})).await;
+    }
+
+    pub async fn get_bounty_count(&self) -> Result<u64> {
+        return self.view("get_bounty_count", json!({})).await;
+    }
+
+    pub async fn get_offline_node_count(&self) -> Result<u64> {
+        return self.view("get_offline_node_count", json!({})).await;
+    }
+
+    pub async fn get_total_completed_bounties(&self) -> Result<u64> {
+        return self.view("get_total_completed_bounties", json!({})).await;
+    }
+
+    pub async fn get_nodes_for_owner_count(&self, owner_id: &AccountId) -> Result<u64> {
+        return self.view("get_nodes_for_owner_count", json!({ "owner_id": owner_id })).await;
+    }
+
+    pub async fn get_lifetime_earnings_for_owner(&self, owner_id: &AccountId) -> Result<Balance> {
+        return self.view("get_lifetime_earnings_for_owner", json!({ "owner_id": owner_id })).await;
+    }
+
+    pub async fn get_bounties_for_owner_count(&self, owner_id: &AccountId) -> Result<u64> {
+        return self.view("get_bounties_for_owner_count", json!({ "owner_id": owner_id })).await;
+    }
+
+    pub async fn get_total_payouts(&self) -> Result<Balance> {
+        return self.view("get_total_payouts", json!({})).await;
+    }
+
+    pub async fn get_gas_reimbursement(&self) -> Result<Balance> {
+        return self.view("get_gas_reimbursement", json!({})).await;
+    }
+
+    pub async fn get_total_fees(&self) -> Result<Balance> {
+        return self.view("get_total_fees", json!({})).await;
+    }
+
+    pub async fn get_treasury_balance(&self) -> Result<Balance> {
+        return self.view("get_treasury_balance", json!({})).await;
+    }
+
+    pub async fn get_treasury_account(&self) -> Result<AccountId> {
+        return self.view("get_treasury_account", json!({})).await;
+    }
+
+    pub async fn get_protocol_fee_bps(&self) -> Result<u16> {
+        return self.view("get_protocol_fee_bps", json!({})).await;
+    }
+
+    pub async fn get_admin(&self) -> Result<AccountId> {
+        return self.view("get_admin", json!({})).await;
+    }
+
+    pub async fn get_keepers(&self) -> Result<Vec<AccountId>> {
+        return self.view("get_keepers", json!({})).await;
+    }
+
+    pub async fn get_audit_rate_bps(&self) -> Result<u16> {
+        return self.view("get_audit_rate_bps", json!({})).await;
+    }
+
+    pub async fn get_audit_levy_bps(&self) -> Result<u16> {
+        return self.view("get_audit_levy_bps", json!({})).await;
+    }
+
+    pub async fn get_audit_pool(&self) -> Result<Balance> {
+        return self.view("get_audit_pool", json!({})).await;
+    }
+
+    /// Quotas that apply to account_id, or the coordinator's defaults if account_id is None
+    pub async fn get_quotas(&self, account_id: Option<&AccountId>) -> Result<Quotas> {
+        return self.view("get_quotas", json!({ "account_id": account_id })).await;
+    }
+
+    pub async fn get_active_bounty_count_for_owner(&self, owner_id: &AccountId) -> Result<u64> {
+        return self.view("get_active_bounty_count_for_owner", json!({ "owner_id": owner_id })).await;
+    }
+
+    pub async fn get_quota_usage(&self, account_id: &AccountId) -> Result<QuotaUsage> {
+        return self.view("get_quota_usage", json!({ "account_id": account_id })).await;
+    }
+
+    pub async fn get_event_nonce(&self) -> Result<u64> {
+        return self.view("get_event_nonce", json!({})).await;
+    }
+
+    pub async fn get_total_escrowed(&self) -> Result<Balance> {
+        return self.view("get_total_escrowed", json!({})).await;
+    }
+
+    pub async fn get_solvency_report(&self) -> Result<SolvencyReport> {
+        return self.view("get_solvency_report", json!({})).await;
+    }
+
+    pub async fn get_node(&self, node_id: &AccountId) -> Result<Node> {
+        return self.view("get_node", json!({ "node_id": node_id })).await;
+    }
+
+    pub async fn get_bounty(&self, bounty_id: &AccountId) -> Result<Bounty> {
+        return self.view("get_bounty", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_bounty_or_panic(&self, bounty_id: &AccountId) -> Result<Bounty> {
+        return self.view("get_bounty_or_panic", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_nodes(&self) -> Result<Vec<Node>> {
+        return self.view("get_nodes", json!({})).await;
+    }
+
+    pub async fn get_nodes2(&self) -> Result<Vec<(AccountId, Node)>> {
+        return self.view("get_nodes2", json!({})).await;
+    }
+
+    pub async fn get_nodes_for_owner(&self, owner_id: &AccountId) -> Result<Vec<Node>> {
+        return self.view("get_nodes_for_owner", json!({ "owner_id": owner_id })).await;
+    }
+
+    pub async fn get_offline_nodes_for_owner(&self, owner_id: &AccountId) -> Result<Vec<Node>> {
+        return self.view("get_offline_nodes_for_owner", json!({ "owner_id": owner_id })).await;
+    }
+
+    pub async fn get_bounties(&self) -> Result<Vec<Bounty>> {
+        return self.view("get_bounties", json!({})).await;
+    }
+
+    pub async fn get_bounties_for_owner(&self, owner_id: &AccountId) -> Result<Vec<Bounty>> {
+        return self.view("get_bounties_for_owner", json!({ "owner_id": owner_id })).await;
+    }
+
+    pub async fn get_bounty_elected_nodes(&self, bounty_id: &AccountId) -> Result<Vec<AccountId>> {
+        return self.view("get_bounty_elected_nodes", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_unanswered_nodes(&self, bounty_id: &AccountId) -> Result<Vec<AccountId>> {
+        return self.view("get_unanswered_nodes", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_successful_nodes(&self, bounty_id: &AccountId) -> Result<Vec<AccountId>> {
+        return self.view("get_successful_nodes", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_failed_nodes(&self, bounty_id: &AccountId) -> Result<Vec<AccountId>> {
+        return self.view("get_failed_nodes", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_bounty_answer_counts(&self, bounty_id: &AccountId) -> Result<HashMap<String, u64>> {
+        return self.view("get_bounty_answer_counts", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    /// How many online nodes would be elected to a bounty with this reward and these requirements
+    pub async fn get_accepting_node_count(
+        &self,
+        amt_node_reward: Balance,
+        min_nodes: u64,
+        timeout_seconds: u64,
+        network_required: bool,
+        gpu_required: bool,
+    ) -> Result<u64> {
+        let args = json!({
+            "amt_node_reward": amt_node_reward.to_string(),
+            "min_nodes": min_nodes,
+            "timeout_seconds": timeout_seconds,
+            "network_required": network_required,
+            "gpu_required": gpu_required,
+        });
+        return self.view("get_accepting_node_count", args).await;
+    }
+
+    pub async fn get_node_padding(&self, min_nodes: u64) -> Result<u64> {
+        return self.view("get_node_padding", json!({ "min_nodes": min_nodes })).await;
+    }
+
+    pub async fn get_answer(&self, bounty_id: &AccountId, node_id: &AccountId) -> Result<NodeResponse> {
+        return self.view("get_answer", json!({ "bounty_id": bounty_id, "node_id": node_id })).await;
+    }
+
+    pub async fn should_post_answer(&self, bounty_id: &AccountId, node_id: &AccountId) -> Result<bool> {
+        return self.view("should_post_answer", json!({ "bounty_id": bounty_id, "node_id": node_id })).await;
+    }
+
+    pub async fn should_collect_reward(&self, node_id: &AccountId, bounty_id: &AccountId) -> Result<bool> {
+        return self.view("should_collect_reward", json!({ "node_id": node_id, "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_pending_payout(&self, node_id: &AccountId, bounty_id: &AccountId) -> Result<PendingPayout> {
+        return self.view("get_pending_payout", json!({ "node_id": node_id, "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_bounty_aggregate_result(&self, bounty_id: &AccountId) -> Result<AggregateResult> {
+        return self.view("get_bounty_aggregate_result", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    pub async fn get_blocklist(&self, account_id: &AccountId) -> Result<Blocklist> {
+        return self.view("get_blocklist", json!({ "account_id": account_id })).await;
+    }
+
+    pub async fn get_pool(&self, pool_id: &str) -> Result<Pool> {
+        return self.view("get_pool", json!({ "pool_id": pool_id })).await;
+    }
+
+    /// Pools the node is a member of or invited to
+    pub async fn get_pools_for_node(&self, node_id: &AccountId) -> Result<Vec<Pool>> {
+        return self.view("get_pools_for_node", json!({ "node_id": node_id })).await;
+    }
+
+    pub async fn get_pool_stats(&self, pool_id: &str) -> Result<PoolStats> {
+        return self.view("get_pool_stats", json!({ "pool_id": pool_id })).await;
+    }
+
+    pub async fn get_bounty_result(&self, bounty_id: &AccountId) -> Result<HashMap<String, u8>> {
+        return self.view("get_bounty_result", json!({ "bounty_id": bounty_id })).await;
+    }
+
+    // Nodes
+
+    /// Registers a node owned by the signer, attaching the refundable registration deposit
+    pub async fn register_node(&self, name: &str, absolute_timeout: u64, allow_network: bool, allow_gpu: bool) -> Result<Node> {
+        return self
+            .call(
+                "register_node",
+                json!({
+                    "name": name,
+                    "absolute_timeout": absolute_timeout,
+                    "allow_network": allow_network,
+                    "allow_gpu": allow_gpu,
+                }),
+                NODE_REGISTRATION_DEPOSIT,
+            )
+            .await;
+    }
+
+    pub async fn update_node(&self, node_id: &AccountId, allow_network: bool, allow_gpu: bool, absolute_timeout: u64) -> Result<Node> {
+        return self
+            .call(
+                "update_node",
+                json!({
+                    "node_id": node_id,
+                    "allow_network": allow_network,
+                    "allow_gpu": allow_gpu,
+                    "absolute_timeout": absolute_timeout,
+                }),
+                0,
+            )
+            .await;
+    }
+
+    pub async fn remove_node(&self, node_id: &AccountId) -> Result<()> {
+        return self.call_unit("remove_node", json!({ "node_id": node_id }), 0).await;
+    }
+
+    pub async fn remove_all_nodes(&self) -> Result<()> {
+        return self.call_unit("remove_all_nodes", json!({}), 0).await;
+    }
+
+    pub async fn set_node_offline(&self, node_id: &AccountId, offline: bool) -> Result<Node> {
+        return self.call("set_node_offline", json!({ "node_id": node_id, "offline": offline }), 0).await;
+    }
+
+    /// Sets the least the node will run a bounty for, it isn't elected to bounties paying less per elected node
+    pub async fn set_node_price(&self, node_id: &AccountId, min_reward: Balance, min_reward_per_second: Balance) -> Result<Node> {
+        return self
+            .call(
+                "set_node_price",
+                json!({
+                    "node_id": node_id,
+                    "min_reward": min_reward.to_string(),
+                    "min_reward_per_second": min_reward_per_second.to_string(),
+                }),
+                0,
+            )
+            .await;
+    }
+
+    /// Replaces the rules for which bounties the node takes on, it isn't elected to bounties they turn down
+    pub async fn set_node_rules(&self, node_id: &AccountId, rules: &AcceptanceRules) -> Result<Node> {
+        return self.call("set_node_rules", json!({ "node_id": node_id, "rules": rules }), 0).await;
+    }
+
+    // Bounties
+
+    /// Creates a bounty, attaching amt_storage + amt_node_reward as the deposit
+    pub async fn create_bounty(&self, args: &CreateBountyArgs) -> Result<Bounty> {
+        return self.call("create_bounty", args.to_json(), args.deposit()).await;
+    }
+
+    pub async fn cancel_bounty(&self, bounty_id: &AccountId) -> Result<()> {
+        return self.call_unit("cancel_bounty", json!({ "bounty_id": bounty_id }), 0).await;
+    }
+
+    pub async fn cancel_all_my_bounties(&self) -> Result<()> {
+        return self.call_unit("cancel_all_my_bounties", json!({}), 0).await;
+    }
+
+    /// Same as get_answer, but works on pending bounties. Only the coordinator, the bounty owner or the node owner can call it.
+    pub async fn call_get_answer(&self, bounty_id: &AccountId, node_id: &AccountId) -> Result<NodeResponse> {
+        return self.call("call_get_answer", json!({ "bounty_id": bounty_id, "node_id": node_id }), 0).await;
+    }
+
+    /// Turns the bounty down for the node, the coordinator elects a replacement if a qualified node is left
+    pub async fn reject_bounty(&self, bounty_id: &AccountId, node_id: &AccountId, message: &str) -> Result<NodeResponse> {
+        return self
+            .call("reject_bounty", json!({ "bounty_id": bounty_id, "node_id": node_id, "message": message }), 0)
+            .await;
+    }
+
+    pub async fn post_answer(
+        &self,
+        bounty_id: &AccountId,
+        node_id: &AccountId,
+        answer: &str,
+        message: &str,
+        status: NodeResponseStatus,
+    ) -> Result<NodeResponse> {
+        return self
+            .call(
+                "post_answer",
+                json!({
+                    "bounty_id": bounty_id,
+                    "node_id": node_id,
+                    "answer": answer,
+                    "message": message,
+                    "status": status,
+                }),
+                0,
+            )
+            .await;
+    }
+
+    pub async fn collect_reward(&self, node_id: &AccountId, bounty_id: &AccountId) -> Result<()> {
+        return self.call_unit("collect_reward", json!({ "node_id": node_id, "bounty_id": bounty_id }), 0).await;
+    }
+
+    /// Disputes a successful bounty, attaching amt_storage + amt_node_reward as the re-run's deposit
+    pub async fn dispute_bounty(&self, bounty_id: &AccountId, amt_storage: Balance, amt_node_reward: Balance) -> Result<Bounty> {
+        return self
+            .call(
+                "dispute_bounty",
+                json!({
+                    "bounty_id": bounty_id,
+                    "amt_storage": amt_storage.to_string(),
+                    "amt_node_reward": amt_node_reward.to_string(),
+                }),
+                amt_storage + amt_node_reward,
+            )
+            .await;
+    }
+
+    /// Cancels a dispute re-run that didn't close in time, upholding the disputed bounty
+    pub async fn expire_rerun(&self, bounty_id: &AccountId) -> Result<()> {
+        return self.call_unit("expire_rerun", json!({ "bounty_id": bounty_id }), 0).await;
+    }
+
+    pub async fn reclaim_reward_from_dropped_nodes(&self, bounty_id: &AccountId) -> Result<()> {
+        return self.call_unit("reclaim_reward_from_dropped_nodes", json!({ "bounty_id": bounty_id }), 0).await;
+    }
+
+    pub async fn add_storage_deposit(&self, bounty_id: &AccountId, amount: Balance) -> Result<()> {
+        return self.call_unit("add_storage_deposit", json!({ "bounty_id": bounty_id }), amount).await;
+    }
+
+    pub async fn add_node_reward_deposit(&self, bounty_id: &AccountId, amount: Balance) -> Result<()> {
+        return self.call_unit("add_node_reward_deposit", json!({ "bounty_id": bounty_id }), amount).await;
+    }
+
+    /// Replaces the nodes that didn't answer in time. Only the bounty owner or a keeper can call it, once the timeout passed
+    pub async fn reelect_unanswered_nodes(&self, bounty_id: &AccountId) -> Result<()> {
+        return self.call_unit("reelect_unanswered_nodes", json!({ "bounty_id": bounty_id }), 0).await;
+    }
+
+    /// Bids price on an auctioned bounty for one of the signer's nodes, replacing its earlier bid
+    pub async fn bid_on_bounty(&self, bounty_id: &AccountId, node_id: &AccountId, price: Balance) -> Result<Bid> {
+        let args = json!({ "bounty_id": bounty_id, "node_id": node_id, "price": price.to_string() });
+        return self.call("bid_on_bounty", args, 0).await;
+    }
+
+    /// Closes an auction whose bidding window has passed, electing the cheapest bids or cancelling the bounty
+    pub async fn award_bounty(&self, bounty_id: &AccountId) -> Result<()> {
+        return self.call_unit("award_bounty", json!({ "bounty_id": bounty_id }), 0).await;
+    }
+
+    // Blocklists
+
+    /// Keeps nodes, and every node of the given owners, from being elected for the signer's bounties
+    pub async fn add_to_blocklist(&self, node_ids: &[AccountId], owner_ids: &[AccountId]) -> Result<Blocklist> {
+        return self
+            .call("add_to_blocklist", json!({ "node_ids": node_ids, "owner_ids": owner_ids }), STORAGE_DEPOSIT)
+            .await;
+    }
+
+    pub async fn remove_from_blocklist(&self, node_ids: &[AccountId], owner_ids: &[AccountId]) -> Result<Blocklist> {
+        return self.call("remove_from_blocklist", json!({ "node_ids": node_ids, "owner_ids": owner_ids }), 0).await;
+    }
+
+    // Pools
+
+    /// Creates a pool owned by the signer, attaching STORAGE_DEPOSIT for its storage
+    pub async fn create_pool(&self, pool_id: &str) -> Result<Pool> {
+        return self.call("create_pool", json!({ "pool_id": pool_id }), STORAGE_DEPOSIT).await;
+    }
+
+    /// Invites a node to one of the signer's pools, attaching STORAGE_DEPOSIT for the invite's storage
+    pub async fn invite_to_pool(&self, pool_id: &str, node_id: &AccountId) -> Result<Pool> {
+        return self
+            .call("invite_to_pool", json!({ "pool_id": pool_id, "node_id": node_id }), STORAGE_DEPOSIT)
+            .await;
+    }
+
+    /// Accepts an invite for one of the signer's nodes
+    pub async fn join_pool(&self, pool_id: &str, node_id: &AccountId) -> Result<Pool> {
+        return self.call("join_pool", json!({ "pool_id": pool_id, "node_id": node_id }), 0).await;
+    }
+
+    /// Takes a node out of a pool or withdraws its invite, as either the pool owner or the node owner
+    pub async fn leave_pool(&self, pool_id: &str, node_id: &AccountId) -> Result<Pool> {
+        return self.call("leave_pool", json!({ "pool_id": pool_id, "node_id": node_id }), 0).await;
+    }
+
+    // Admin
+
+    pub async fn set_gas_reimbursement(&self, amount: Balance) -> Result<Balance> {
+        return self.call("set_gas_reimbursement", json!({ "amount": amount.to_string() }), 0).await;
+    }
+
+    pub async fn set_admin(&self, admin_id: &AccountId) -> Result<AccountId> {
+        return self.call("set_admin", json!({ "admin_id": admin_id }), 0).await;
+    }
+
+    /// Lets account_id reelect timed out nodes on any bounty
+    pub async fn add_keeper(&self, account_id: &AccountId) -> Result<Vec<AccountId>> {
+        return self.call("add_keeper", json!({ "account_id": account_id }), 0).await;
+    }
+
+    pub async fn remove_keeper(&self, account_id: &AccountId) -> Result<Vec<AccountId>> {
+        return self.call("remove_keeper", json!({ "account_id": account_id }), 0).await;
+    }
+
+    pub async fn set_protocol_fee_bps(&self, fee_bps: u16) -> Result<u16> {
+        return self.call("set_protocol_fee_bps", json!({ "fee_bps": fee_bps }), 0).await;
+    }
+
+    pub async fn set_treasury_account(&self, treasury_account_id: &AccountId) -> Result<AccountId> {
+        return self.call("set_treasury_account", json!({ "treasury_account_id": treasury_account_id }), 0).await;
+    }
+
+    /// Withdraws `amount` from the treasury, or all of it if amount is None
+    pub async fn withdraw_treasury(&self, amount: Option<Balance>) -> Result<()> {
+        return self
+            .call_unit("withdraw_treasury", json!({ "amount": amount.map(|amount| amount.to_string()) }), 0)
+            .await;
+    }
+
+    pub async fn set_audit_rate_bps(&self, rate_bps: u16) -> Result<u16> {
+        return self.call("set_audit_rate_bps", json!({ "rate_bps": rate_bps }), 0).await;
+    }
+
+    pub async fn set_audit_levy_bps(&self, levy_bps: u16) -> Result<u16> {
+        return self.call("set_audit_levy_bps", json!({ "levy_bps": levy_bps }), 0).await;
+    }
+
+    pub async fn set_quotas(&self, quotas: &Quotas) -> Result<Quotas> {
+        return self
+            .call(
+                "set_quotas",
+                json!({
+                    "max_nodes_per_owner": quotas.max_nodes_per_owner,
+                    "max_active_bounties_per_owner": quotas.max_active_bounties_per_owner,
+                    "max_elected_share_bps": quotas.max_elected_share_bps,
+                }),
+                0,
+            )
+            .await;
+    }
+
+    /// Gives account_id its own quotas, or puts it back on the defaults if quotas is None
+    pub async fn set_owner_quotas(&self, account_id: &AccountId, quotas: Option<&Quotas>) -> Result<Quotas> {
+        return self.call("set_owner_quotas", json!({ "account_id": account_id, "quotas": quotas }), 0).await;
+    }
+}
+
+// near_workspaces and near_sdk each have their own AccountId, both are validated the same way
+pub fn to_workspaces_id(account_id: &AccountId) -> near_workspaces::AccountId {
+    return account_id.as_str().parse().expect("near_sdk account ids are always valid");
+}
+
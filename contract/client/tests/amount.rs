@@ -0,0 +1,25 @@
+use coordinator_client::{format_amount, parse_amount};
+
+const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+#[test]
+fn parses_near_and_yocto_amounts() {
+    assert_eq!(parse_amount("1N"), Ok(ONE_NEAR));
+    assert_eq!(parse_amount("1.5 NEAR"), Ok(ONE_NEAR + ONE_NEAR / 2));
+    assert_eq!(parse_amount(".1near"), Ok(ONE_NEAR / 10));
+    assert_eq!(parse_amount("0.000000000000000000000001N"), Ok(1));
+    assert_eq!(parse_amount("1000000000000000000000000"), Ok(ONE_NEAR));
+}
+
+#[test]
+fn rejects_invalid_amounts() {
+    for input in ["", "N", "1.5", "-1N", "1.2.3N", "0.0000000000000000000000001N", "abc"] {
+        assert!(parse_amount(input).is_err(), "{} should not parse", input);
+    }
+}
+
+#[test]
+fn formats_amounts_for_people() {
+    assert_eq!(format_amount(ONE_NEAR), "1 N");
+    assert_eq!(format_amount(ONE_NEAR / 10), "100 mN");
+}
@@ -0,0 +1,68 @@
+use coordinator_client::ClientError;
+
+type Matcher = fn(&ClientError) -> bool;
+
+#[test]
+fn maps_call_failures_to_typed_errors() {
+    // What ExecutionFailure's Display looks like for a require! in a call
+    let failure = "Action #0: ExecutionError(\"Smart contract panicked: You have already submitted an answer\")";
+    let error = ClientError::from_failure(failure);
+    assert!(matches!(error, ClientError::AlreadyAnswered(_)), "got {:?}", error);
+    assert_eq!(error.panic_message(), Some("You have already submitted an answer"));
+}
+
+#[test]
+fn maps_view_failures_to_typed_errors() {
+    let failure = "wasm execution failed with error: HostError(GuestPanic { panic_msg: \"Bounty b1.coordinator.test.near does not exist\" })";
+    let error = ClientError::from_failure(failure);
+    assert!(matches!(error, ClientError::BountyNotFound(_)), "got {:?}", error);
+    assert_eq!(error.panic_message(), Some("Bounty b1.coordinator.test.near does not exist"));
+}
+
+#[test]
+fn keeps_escaped_quotes_and_parentheses_in_messages() {
+    let failure = "Action #0: ExecutionError(\"Smart contract panicked: Node reward must be greater than the gas reimbursement reserved for every elected node (\\\"1\\\" x 2)\")";
+    let error = ClientError::from_failure(failure);
+    assert!(matches!(error, ClientError::InvalidDeposit(_)), "got {:?}", error);
+    assert_eq!(
+        error.panic_message(),
+        Some("Node reward must be greater than the gas reimbursement reserved for every elected node (\"1\" x 2)")
+    );
+}
+
+#[test]
+fn maps_every_known_panic() {
+    let cases: Vec<(&str, Matcher)> = vec![
+        ("Node n.node.alice.near is not registered", |e| matches!(e, ClientError::NodeNotFound(_))),
+        ("Node n.node.alice.near does not exist", |e| matches!(e, ClientError::NodeNotFound(_))),
+        ("Could not find node to set offline", |e| matches!(e, ClientError::NodeNotFound(_))),
+        ("Bounty b1.coordinator.near does not exist, cannot get elected nodes", |e| matches!(e, ClientError::BountyNotFound(_))),
+        ("Node already registered: n.node.alice.near", |e| matches!(e, ClientError::NodeAlreadyRegistered(_))),
+        ("Only the node owner can post an answer", |e| matches!(e, ClientError::Unauthorized(_))),
+        ("Must include a refundable deposit of 1 NEAR to register a node", |e| matches!(e, ClientError::InvalidDeposit(_))),
+        ("Attached deposit must be equal to the sum of the storage and node reward amounts", |e| matches!(e, ClientError::InvalidDeposit(_))),
+        ("Not enough nodes registered for bounty", |e| matches!(e, ClientError::NotEnoughNodes(_))),
+        ("Not enough qualified nodes to fill bounty", |e| matches!(e, ClientError::NotEnoughNodes(_))),
+        ("Not enough storage left to store answer", |e| matches!(e, ClientError::StorageExhausted(_))),
+        ("Bounty is complete, no more answers can be published", |e| matches!(e, ClientError::InvalidBountyState(_))),
+        ("Bounty must be pending to be cancelled", |e| matches!(e, ClientError::InvalidBountyState(_))),
+        ("You are not an elected node", |e| matches!(e, ClientError::NotElected(_))),
+        ("You have already claimed your payout", |e| matches!(e, ClientError::AlreadyClaimed(_))),
+        ("You are not eligible to collect a reward", |e| matches!(e, ClientError::NotEligible(_))),
+        ("Payouts for this bounty are locked until its dispute window has passed and any dispute is resolved", |e| {
+            matches!(e, ClientError::PayoutLocked(_))
+        }),
+        ("Something a newer coordinator says", |e| matches!(e, ClientError::Panic(_))),
+    ];
+    for (message, is_expected) in cases {
+        let error = ClientError::from_panic_message(message);
+        assert!(is_expected(&error), "{} mapped to {:?}", message, error);
+    }
+}
+
+#[test]
+fn failures_without_a_panic_are_execution_errors() {
+    let error = ClientError::from_failure("Exceeded the prepaid gas.");
+    assert!(matches!(error, ClientError::Execution(_)), "got {:?}", error);
+    assert_eq!(error.panic_message(), None);
+}
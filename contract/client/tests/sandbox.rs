@@ -0,0 +1,126 @@
+// These run against a local sandbox and the release wasm, build it with ./build.sh first, then run
+// cargo test -p coordinator-client -- --ignored
+use near_sdk::AccountId;
+use near_units::parse_near;
+use near_workspaces::network::Sandbox;
+use near_workspaces::Worker;
+use serde_json::json;
+
+use coordinator_client::{BountyStatus, ClientError, CoordinatorClient, CreateBountyArgs, NodeResponseStatus, SupportedDownloadProtocols};
+
+const COORDINATOR_WASM_FILEPATH: &str = "../target/wasm32-unknown-unknown/release/coordinator.wasm";
+
+async fn setup_coordinator(worker: &Worker<Sandbox>) -> anyhow::Result<AccountId> {
+    let coordinator_wasm = std::fs::read(COORDINATOR_WASM_FILEPATH)?;
+    let coordinator_contract = worker.dev_deploy(&coordinator_wasm).await?;
+    coordinator_contract
+        .call("init")
+        .max_gas()
+        .args_json(json!({}))
+        .transact()
+        .await?
+        .into_result()?;
+    return Ok(coordinator_contract.id().as_str().parse()?);
+}
+
+async fn create_client(worker: &Worker<Sandbox>, coordinator_id: &AccountId) -> anyhow::Result<CoordinatorClient> {
+    let account = worker.dev_create_account().await?;
+    return Ok(CoordinatorClient::new(account, coordinator_id));
+}
+
+fn hello_world_bounty(min_nodes: u64) -> CreateBountyArgs {
+    return CreateBountyArgs::new(
+        "https://github.com/ad0ll/docker-hello-world.git".to_string(),
+        SupportedDownloadProtocols::GIT,
+        min_nodes,
+        60,
+        parse_near!("1N"),
+        parse_near!("1N"),
+    );
+}
+
+#[tokio::test]
+#[ignore = "needs a local sandbox and the release wasm"]
+async fn test_bounty_full_lifecycle() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let coordinator_id = setup_coordinator(&worker).await?;
+    let owner = create_client(&worker, &coordinator_id).await?;
+    // Each elected node needs a different owner
+    let operators = [
+        create_client(&worker, &coordinator_id).await?,
+        create_client(&worker, &coordinator_id).await?,
+        create_client(&worker, &coordinator_id).await?,
+    ];
+    for (i, operator) in operators.iter().enumerate() {
+        for j in 0..2 {
+            let node = operator.register_node(&format!("node{}{}", i, j), 600000, true, false).await?;
+            assert_eq!(node.owner_id, operator.signer_id());
+        }
+    }
+    assert_eq!(owner.get_node_count().await?, 6);
+
+    let bounty = owner.create_bounty(&hello_world_bounty(2)).await?;
+    assert_eq!(bounty.amt_storage, parse_near!("1N"));
+    assert_eq!(bounty.amt_node_reward, parse_near!("1N"));
+    assert_eq!(owner.get_bounty_count().await?, 1);
+
+    let elected = owner.get_bounty_elected_nodes(&bounty.id).await?;
+    assert!(elected.len() >= 2, "expected at least min_nodes to be elected");
+    for node_id in elected.iter() {
+        let node = owner.get_node(node_id).await?;
+        let operator = operators.iter().find(|o| o.signer_id() == node.owner_id).unwrap();
+        if !operator.should_post_answer(&bounty.id, node_id).await? {
+            continue;
+        }
+        let response = operator
+            .post_answer(&bounty.id, node_id, "hello world", "", NodeResponseStatus::SUCCESS)
+            .await?;
+        assert_eq!(response.node_id, *node_id);
+    }
+    let bounty = owner.get_bounty(&bounty.id).await?;
+    assert_eq!(bounty.status, BountyStatus::Success);
+
+    let report = owner.get_solvency_report().await?;
+    assert!(report.solvent && report.ledger_consistent);
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a local sandbox and the release wasm"]
+async fn test_contract_panics_map_to_typed_errors() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let coordinator_id = setup_coordinator(&worker).await?;
+    let operator = create_client(&worker, &coordinator_id).await?;
+    let owner = create_client(&worker, &coordinator_id).await?;
+
+    operator.register_node("node0", 600000, true, false).await?;
+    let error = operator.register_node("node0", 600000, true, false).await.unwrap_err();
+    assert!(matches!(error, ClientError::NodeAlreadyRegistered(_)), "got {:?}", error);
+
+    let missing: AccountId = format!("missing.{}", coordinator_id).parse()?;
+    let error = owner.get_bounty_or_panic(&missing).await.unwrap_err();
+    assert!(matches!(error, ClientError::BountyNotFound(_)), "got {:?}", error);
+
+    let error = owner.create_bounty(&hello_world_bounty(5)).await.unwrap_err();
+    assert!(matches!(error, ClientError::NotEnoughNodes(_)), "got {:?}", error);
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a local sandbox and the release wasm"]
+async fn test_offline_nodes_are_listed_separately() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let coordinator_id = setup_coordinator(&worker).await?;
+    let operator = create_client(&worker, &coordinator_id).await?;
+    let node = operator.register_node("node0", 600000, true, false).await?;
+
+    operator.set_node_offline(&node.id, true).await?;
+    assert!(operator.get_nodes_for_owner(&operator.signer_id()).await?.is_empty());
+    let offline = operator.get_offline_nodes_for_owner(&operator.signer_id()).await?;
+    assert_eq!(offline.iter().map(|n| &n.id).collect::<Vec<_>>(), vec![&node.id]);
+
+    operator.set_node_offline(&node.id, false).await?;
+    assert!(operator.get_offline_nodes_for_owner(&operator.signer_id()).await?.is_empty());
+    assert_eq!(operator.get_nodes_for_owner(&operator.signer_id()).await?, vec![node]);
+    Ok(())
+}
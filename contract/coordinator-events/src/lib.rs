@@ -0,0 +1,161 @@
+use std::fmt;
+
+use serde_json::Value;
+
+pub use coordinator::coordinator::{EVENT_STANDARD_NAME, EVENT_STANDARD_SPEC};
+pub use coordinator::events::*;
+
+// Every log line the coordinator emits for an event starts with this, anything else is a plain log! line
+pub const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+// Names of every EventLogVariant this crate was built against.
+// An event that isn't listed here came from a newer coordinator, and is returned as DecodedEvent::Unknown rather than an error.
+pub const KNOWN_EVENTS: &[&str] = &[
+    "bounty_created",
+    "bounty_retry",
+    "bounty_completed",
+    "bounty_disputed",
+    "bounty_dispute_resolved",
+    "bounty_audit_mismatch",
+    "bounty_rejected",
+    "bounty_deposit_added",
+    "bounty_reelected",
+    "bounty_bid_placed",
+    "bounty_awarded",
+    "answer_posted",
+    "reward_collected",
+    "node_registered",
+    "node_updated",
+    "node_removed",
+    "node_status_changed",
+    "pool_created",
+    "pool_membership_changed",
+];
+
+/// An event emitted by a coordinator with a newer version of the standard than this crate knows about
+///
+/// Arguments
+/// * `version`: the version of the standard the event was emitted with
+/// * `event_nonce`: the coordinator-wide nonce, if the event has one
+/// * `event_seq`: the per-bounty sequence number, if the event is about a bounty
+/// * `event`: name of the event, e.g. "bounty_created"
+/// * `data`: the untyped event data
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownEvent {
+    pub version: String,
+    pub event_nonce: Option<u64>,
+    pub event_seq: Option<u64>,
+    pub event: String,
+    pub data: Value,
+}
+
+#[derive(Debug)]
+pub enum DecodedEvent {
+    Known(Box<EventLog>),
+    Unknown(UnknownEvent),
+}
+
+impl DecodedEvent {
+    pub fn name(&self) -> String {
+        return match self {
+            DecodedEvent::Known(log) => serde_json::to_value(&log.event)
+                .ok()
+                .and_then(|value| value["event"].as_str().map(|name| name.to_string()))
+                .unwrap_or_default(),
+            DecodedEvent::Unknown(event) => event.event.clone(),
+        };
+    }
+
+    pub fn event_nonce(&self) -> Option<u64> {
+        return match self {
+            DecodedEvent::Known(log) => Some(log.event_nonce),
+            DecodedEvent::Unknown(event) => event.event_nonce,
+        };
+    }
+
+    pub fn event_seq(&self) -> Option<u64> {
+        return match self {
+            DecodedEvent::Known(log) => log.event_seq,
+            DecodedEvent::Unknown(event) => event.event_seq,
+        };
+    }
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    // The line doesn't start with EVENT_JSON:, this is expected for regular log! lines
+    NotAnEvent,
+    InvalidJson(serde_json::Error),
+    MissingField(&'static str),
+    UnsupportedStandard(String),
+    UnsupportedVersion(String),
+    // The event is one we know, but its data doesn't match the type we expect
+    InvalidEvent { event: String, source: serde_json::Error },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::NotAnEvent => write!(f, "log line does not start with {}", EVENT_JSON_PREFIX),
+            DecodeError::InvalidJson(e) => write!(f, "event is not valid json: {}", e),
+            DecodeError::MissingField(field) => write!(f, "event is missing the {} field", field),
+            DecodeError::UnsupportedStandard(standard) => {
+                write!(f, "unsupported event standard {}, expected {}", standard, EVENT_STANDARD_NAME)
+            }
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported event version {}, expected {}", version, EVENT_STANDARD_SPEC)
+            }
+            DecodeError::InvalidEvent { event, source } => write!(f, "could not decode {} event: {}", event, source),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// Versions are compatible as long as the major version matches, minor versions only add events and fields
+pub fn is_supported_version(version: &str) -> bool {
+    let major = |v: &str| v.split('.').next().map(|major| major.to_string());
+    return major(version).is_some() && major(version) == major(EVENT_STANDARD_SPEC);
+}
+
+// Decodes a single receipt log line into a typed event
+pub fn decode_log(line: &str) -> Result<DecodedEvent, DecodeError> {
+    let json = line.trim().strip_prefix(EVENT_JSON_PREFIX).ok_or(DecodeError::NotAnEvent)?;
+    let value: Value = serde_json::from_str(json).map_err(DecodeError::InvalidJson)?;
+
+    let standard = value["standard"].as_str().ok_or(DecodeError::MissingField("standard"))?;
+    if standard != EVENT_STANDARD_NAME {
+        return Err(DecodeError::UnsupportedStandard(standard.to_string()));
+    }
+    let version = value["version"].as_str().ok_or(DecodeError::MissingField("version"))?;
+    if !is_supported_version(version) {
+        return Err(DecodeError::UnsupportedVersion(version.to_string()));
+    }
+    let event = value["event"].as_str().ok_or(DecodeError::MissingField("event"))?.to_string();
+
+    if !KNOWN_EVENTS.contains(&event.as_str()) {
+        return Ok(DecodedEvent::Unknown(UnknownEvent {
+            version: version.to_string(),
+            event_nonce: value["event_nonce"].as_u64(),
+            event_seq: value["event_seq"].as_u64(),
+            event,
+            data: value["data"].clone(),
+        }));
+    }
+    return serde_json::from_value::<EventLog>(value)
+        .map(|log| DecodedEvent::Known(Box::new(log)))
+        .map_err(|source| DecodeError::InvalidEvent { event, source });
+}
+
+// Decodes every event in a receipt's logs, skipping plain log! lines
+pub fn decode_logs<I, S>(logs: I) -> Vec<Result<DecodedEvent, DecodeError>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    return logs
+        .into_iter()
+        .map(|line| decode_log(line.as_ref()))
+        .filter(|result| !matches!(result, Err(DecodeError::NotAnEvent)))
+        .collect();
+}
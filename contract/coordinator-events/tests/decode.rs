@@ -0,0 +1,125 @@
+// lifecycle.log is recorded by tests/lifecycle.rs from the coordinator's own code running a full node and bounty
+// lifecycle, so its EVENT_JSON lines are interleaved with the plain log! lines that share a receipt with them.
+use coordinator::bounty::NodeResponseStatus;
+use coordinator_events::{
+    decode_log, decode_logs, BountyDepositKind, DecodeError, DecodedEvent, EventLogVariant, KNOWN_EVENTS,
+};
+
+const LIFECYCLE: &str = include_str!("fixtures/lifecycle.log");
+const UNKNOWN: &str = include_str!("fixtures/unknown.log");
+const INVALID: &str = include_str!("fixtures/invalid.log");
+
+fn decode_all(fixture: &str) -> Vec<DecodedEvent> {
+    return decode_logs(fixture.lines()).into_iter().map(|result| result.unwrap()).collect();
+}
+
+#[test]
+fn decodes_every_event_in_a_lifecycle() {
+    let events = decode_all(LIFECYCLE);
+    let names: Vec<String> = events.iter().map(|event| event.name()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "node_registered",
+            "node_registered",
+            "node_registered",
+            "node_registered",
+            "node_updated",
+            "node_status_changed",
+            "node_status_changed",
+            "bounty_created",
+            "bounty_deposit_added",
+            "bounty_retry",
+            "bounty_rejected",
+            "bounty_retry",
+            "answer_posted",
+            "answer_posted",
+            "bounty_completed",
+            "reward_collected",
+            "node_removed",
+        ]
+    );
+    for name in names {
+        assert!(KNOWN_EVENTS.contains(&name.as_str()), "{} is missing from KNOWN_EVENTS", name);
+    }
+
+    let nonces: Vec<u64> = events.iter().map(|event| event.event_nonce().unwrap()).collect();
+    assert_eq!(nonces, (1..=17).collect::<Vec<u64>>());
+    let seqs: Vec<u64> = events.iter().filter_map(|event| event.event_seq()).collect();
+    assert_eq!(seqs, (1..=9).collect::<Vec<u64>>());
+}
+
+#[test]
+fn decoded_events_have_typed_data() {
+    let events = decode_all(LIFECYCLE);
+    for event in events.iter() {
+        let log = match event {
+            DecodedEvent::Known(log) => log,
+            DecodedEvent::Unknown(unknown) => panic!("{} should be a known event", unknown.event),
+        };
+        match &log.event {
+            EventLogVariant::BountyCreated(created) => {
+                assert_eq!(created.bounty_id.as_str(), "0-0.bounty.alice.near");
+                assert_eq!(created.min_nodes, 2);
+                assert_eq!(created.node_ids.len(), 3);
+                assert_eq!(created.amt_node_reward.0, 10u128.pow(24));
+            }
+            EventLogVariant::BountyDepositAdded(deposit) => {
+                assert_eq!(deposit.deposit, BountyDepositKind::NodeReward);
+                assert_eq!(deposit.total.0, 2 * 10u128.pow(24));
+            }
+            EventLogVariant::AnswerPosted(answer) => assert_eq!(answer.status, NodeResponseStatus::SUCCESS),
+            EventLogVariant::BountyCompleted(completed) => {
+                assert_eq!(completed.reward_recipients.len(), 2);
+                assert_eq!(completed.agreeing_nodes, completed.reward_recipients);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn known_events_round_trip_to_the_original_line() {
+    for line in LIFECYCLE.lines().filter(|line| line.starts_with("EVENT_JSON:")) {
+        match decode_log(line).unwrap() {
+            DecodedEvent::Known(log) => assert_eq!(log.to_string(), line),
+            DecodedEvent::Unknown(unknown) => panic!("{} should be a known event", unknown.event),
+        }
+    }
+}
+
+#[test]
+fn newer_minor_versions_decode_gracefully() {
+    let events = decode_all(UNKNOWN);
+    match &events[0] {
+        DecodedEvent::Unknown(unknown) => {
+            assert_eq!(unknown.event, "bounty_paused");
+            assert_eq!(unknown.version, "1.4.0");
+            assert_eq!(unknown.event_nonce, Some(42));
+            assert_eq!(unknown.event_seq, Some(3));
+            assert_eq!(unknown.data["until"], 1700000000000u64);
+        }
+        DecodedEvent::Known(_) => panic!("bounty_paused should be an unknown event"),
+    }
+    // Fields added in a newer minor version are ignored
+    match &events[1] {
+        DecodedEvent::Known(log) => assert!(matches!(log.event, EventLogVariant::NodeRegistered(_))),
+        DecodedEvent::Unknown(_) => panic!("node_registered should be a known event"),
+    }
+}
+
+#[test]
+fn rejects_foreign_incompatible_and_malformed_events() {
+    let results = decode_logs(INVALID.lines());
+    assert_eq!(results.len(), 4);
+    assert!(matches!(&results[0], Err(DecodeError::UnsupportedStandard(standard)) if standard == "nep171"));
+    assert!(matches!(&results[1], Err(DecodeError::UnsupportedVersion(version)) if version == "2.0.0"));
+    assert!(matches!(&results[2], Err(DecodeError::InvalidEvent { event, .. }) if event == "node_registered"));
+    assert!(matches!(&results[3], Err(DecodeError::InvalidJson(_))));
+}
+
+#[test]
+fn plain_log_lines_are_not_events() {
+    assert!(matches!(decode_log("Closing bounty 0-0.bounty.alice.near"), Err(DecodeError::NotAnEvent)));
+    assert_eq!(decode_logs(vec!["Elected node: worker.node.bob.near"]).len(), 0);
+}
@@ -0,0 +1,101 @@
+// Records tests/fixtures/lifecycle.log by driving the mock coordinator, which runs the contract's own code, through a
+// node and bounty lifecycle. The fixture holds every log line the calls produced, in order, plain log! lines included.
+//
+// Run with RECORD_LIFECYCLE=1 to rewrite the fixture after the contract's events change.
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use mock_coordinator::{MockConfig, MockCoordinator};
+use near_sdk::{AccountId, Balance};
+use near_units::parse_near;
+use serde_json::{json, Value};
+
+const COORDINATOR: &str = "coordinator.near";
+const NODE_OWNERS: [&str; 4] = ["bob.near", "carol.near", "dave.near", "erin.near"];
+
+fn fixture() -> PathBuf {
+    return PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/lifecycle.log");
+}
+
+struct Recorder {
+    mock: MockCoordinator,
+    logs: Vec<String>,
+}
+
+impl Recorder {
+    fn call(&mut self, signer_id: &str, method: &str, args: Value, deposit: Balance) -> Value {
+        self.mock.advance_time(Duration::from_secs(1));
+        let signer: AccountId = signer_id.parse().unwrap();
+        let outcome = self.mock.call(&signer, method, args, deposit).unwrap_or_else(|e| panic!("{} failed: {}", method, e));
+        self.logs.extend(outcome.logs.iter().cloned());
+        return outcome.json().unwrap();
+    }
+
+    fn view(&mut self, method: &str, args: Value) -> Value {
+        return self.mock.view(method, args).unwrap().json().unwrap();
+    }
+}
+
+fn node_owner(node_id: &str) -> &str {
+    return NODE_OWNERS.iter().find(|owner| node_id.ends_with(&format!(".node.{}", owner))).unwrap();
+}
+
+fn node_ids(bounty: &Value) -> Vec<String> {
+    return bounty["elected_nodes"].as_array().unwrap().iter().map(|node_id| node_id.as_str().unwrap().to_string()).collect();
+}
+
+fn record() -> Vec<String> {
+    let mock = MockCoordinator::new(MockConfig { coordinator_id: COORDINATOR.parse().unwrap(), ..MockConfig::default() });
+    let mut recorder = Recorder { mock, logs: vec![] };
+
+    // One node per owner, so any three can be elected together and one is left to replace a rejecter
+    let mut nodes = vec![];
+    for owner in NODE_OWNERS {
+        let args = json!({ "name": "worker", "absolute_timeout": 60000, "allow_network": true, "allow_gpu": false });
+        let node = recorder.call(owner, "register_node", args, parse_near!("1N"));
+        nodes.push(node["id"].as_str().unwrap().to_string());
+    }
+    let args = json!({ "node_id": nodes[0], "allow_network": true, "allow_gpu": true, "absolute_timeout": 30000 });
+    recorder.call(NODE_OWNERS[0], "update_node", args, 0);
+    recorder.call(NODE_OWNERS[0], "set_node_offline", json!({ "node_id": nodes[0], "offline": true }), 0);
+    recorder.call(NODE_OWNERS[0], "set_node_offline", json!({ "node_id": nodes[0], "offline": false }), 0);
+
+    let args = json!({
+        "file_location": "https://github.com/ad0ll/docker-hello-world.git",
+        "file_download_protocol": "GIT",
+        "min_nodes": 2,
+        "timeout_seconds": 30,
+        "network_required": false,
+        "gpu_required": false,
+        "amt_storage": parse_near!("1N").to_string(),
+        "amt_node_reward": parse_near!("1N").to_string(),
+    });
+    let bounty = recorder.call("alice.near", "create_bounty", args, parse_near!("2N"));
+    let bounty_id = bounty["id"].as_str().unwrap().to_string();
+    recorder.call("alice.near", "add_node_reward_deposit", json!({ "bounty_id": bounty_id }), parse_near!("1N"));
+
+    // The first elected node rejects and the spare node takes its place
+    let rejecter = node_ids(&bounty)[0].clone();
+    let args = json!({ "bounty_id": bounty_id, "node_id": rejecter, "message": "busy" });
+    recorder.call(node_owner(&rejecter), "reject_bounty", args, 0);
+    let elected = node_ids(&recorder.view("get_bounty", json!({ "bounty_id": bounty_id })));
+    for node_id in elected.iter().take(2) {
+        let args = json!({ "bounty_id": bounty_id, "node_id": node_id, "answer": "42", "message": "", "status": "SUCCESS" });
+        recorder.call(node_owner(node_id), "post_answer", args, 0);
+    }
+    recorder.call(node_owner(&elected[0]), "collect_reward", json!({ "node_id": elected[0], "bounty_id": bounty_id }), 0);
+
+    recorder.call(node_owner(&rejecter), "remove_node", json!({ "node_id": rejecter }), 0);
+    return recorder.logs;
+}
+
+#[test]
+fn checked_in_lifecycle_matches_a_recorded_run() {
+    let recorded = record().join("\n") + "\n";
+    if std::env::var("RECORD_LIFECYCLE").is_ok() {
+        fs::write(fixture(), &recorded).unwrap();
+    }
+    let checked_in = fs::read_to_string(fixture()).unwrap();
+    assert_eq!(checked_in, recorded, "Re-record the lifecycle with RECORD_LIFECYCLE=1");
+}
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use near_sdk::AccountId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, Value};
+
+// How the nodes' answers are parsed and compared. Chosen by the owner at creation.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ResultType {
+    //Answers must match byte for byte
+    String,
+    //Answers are parsed as integers, so "007" and "7" agree
+    Integer,
+    //Answers are parsed as decimals, and agree if they're within tolerance of each other. tolerance is a decimal string, e.g. "0.0001"
+    Decimal { tolerance: String },
+    //Answers are parsed as JSON, so key order and whitespace don't matter
+    Json,
+}
+
+impl Display for ResultType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResultType::String => write!(f, "String"),
+            ResultType::Integer => write!(f, "Integer"),
+            ResultType::Decimal { tolerance } => write!(f, "Decimal(±{})", tolerance),
+            ResultType::Json => write!(f, "Json"),
+        }
+    }
+}
+
+// How the parsed answers are combined into the bounty's canonical result. Chosen by the owner at creation.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AggregationFunction {
+    //The most common answer, answers equal to it agree. For decimals, the answer with the most others within tolerance of it.
+    Mode,
+    //The middle answer, integers and decimals only. Answers within tolerance of it agree.
+    Median,
+    //The mean of the answers within tolerance of the median, decimals only. Those answers agree, outliers don't.
+    MeanWithinTolerance,
+    //The union of every answer, JSON arrays only. Every array agrees.
+    SetUnion,
+}
+
+impl Display for AggregationFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregationFunction::Mode => write!(f, "Mode"),
+            AggregationFunction::Median => write!(f, "Median"),
+            AggregationFunction::MeanWithinTolerance => write!(f, "MeanWithinTolerance"),
+            AggregationFunction::SetUnion => write!(f, "SetUnion"),
+        }
+    }
+}
+
+impl AggregationFunction {
+    pub fn supports(&self, result_type: &ResultType) -> bool {
+        return match self {
+            AggregationFunction::Mode => true,
+            AggregationFunction::Median => matches!(result_type, ResultType::Integer | ResultType::Decimal { .. }),
+            AggregationFunction::MeanWithinTolerance => matches!(result_type, ResultType::Decimal { .. }),
+            AggregationFunction::SetUnion => result_type == &ResultType::Json,
+        };
+    }
+}
+
+// An answer parsed according to the bounty's result type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedAnswer {
+    Text(String),
+    Integer(i128),
+    Decimal(f64),
+    Json(Value),
+}
+
+impl ParsedAnswer {
+    fn as_f64(&self) -> f64 {
+        return match self {
+            ParsedAnswer::Integer(i) => *i as f64,
+            ParsedAnswer::Decimal(d) => *d,
+            _ => panic!("{:?} is not a number", self),
+        };
+    }
+}
+
+// The canonical form of an answer, equal answers have equal canonical forms
+impl Display for ParsedAnswer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParsedAnswer::Text(s) => write!(f, "{}", s),
+            ParsedAnswer::Integer(i) => write!(f, "{}", i),
+            ParsedAnswer::Decimal(d) => write!(f, "{}", d),
+            ParsedAnswer::Json(v) => write!(f, "{}", canonical_json(v)),
+        }
+    }
+}
+
+impl ResultType {
+    // None if the answer isn't a valid value of this type
+    pub fn parse(&self, answer: &str) -> Option<ParsedAnswer> {
+        return match self {
+            ResultType::String => Some(ParsedAnswer::Text(answer.to_string())),
+            ResultType::Integer => answer.trim().parse().ok().map(ParsedAnswer::Integer),
+            ResultType::Decimal { .. } => answer
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|d| d.is_finite())
+                .map(ParsedAnswer::Decimal),
+            ResultType::Json => serde_json::from_str(answer).ok().map(ParsedAnswer::Json),
+        };
+    }
+
+    // How far apart two answers can be and still agree, 0 for everything but decimals
+    pub fn get_tolerance(&self) -> f64 {
+        return match self {
+            ResultType::Decimal { tolerance } => tolerance.parse().unwrap_or(0.0),
+            _ => 0.0,
+        };
+    }
+
+    pub fn is_valid(&self) -> bool {
+        return match self {
+            ResultType::Decimal { tolerance } => tolerance.parse::<f64>().is_ok_and(|t| t.is_finite() && t >= 0.0),
+            _ => true,
+        };
+    }
+
+    // Whether two canonical results agree, used to compare a bounty against its dispute re-run or audit
+    pub fn results_agree(&self, a: &str, b: &str) -> bool {
+        return match (self.parse(a), self.parse(b)) {
+            (Some(ParsedAnswer::Decimal(a)), Some(ParsedAnswer::Decimal(b))) => (a - b).abs() <= self.get_tolerance(),
+            (Some(a), Some(b)) => a.to_string() == b.to_string(),
+            _ => a == b,
+        };
+    }
+}
+
+// The canonical result of a bounty and which successful answers agree with it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AggregateResult {
+    pub result_type: ResultType,
+    pub aggregation: AggregationFunction,
+    //None until at least one successful answer parses as the result type
+    pub canonical_result: Option<String>,
+    pub agreeing_nodes: Vec<AccountId>,
+    pub disagreeing_nodes: Vec<AccountId>,
+    //Successful answers that couldn't be parsed as the result type, they never agree
+    pub invalid_nodes: Vec<AccountId>,
+}
+
+// Computes the canonical result of the answers. Answers are (node id, solution), in any order, the result doesn't depend on it.
+pub fn aggregate(result_type: &ResultType, aggregation: &AggregationFunction, answers: &[(AccountId, String)]) -> AggregateResult {
+    let mut parsed: Vec<(AccountId, ParsedAnswer)> = vec![];
+    let mut invalid_nodes: Vec<AccountId> = vec![];
+    for (node_id, answer) in answers {
+        match result_type.parse(answer) {
+            // Set union only takes arrays
+            Some(ParsedAnswer::Json(value)) if aggregation == &AggregationFunction::SetUnion && !value.is_array() => {
+                invalid_nodes.push(node_id.clone())
+            }
+            Some(value) => parsed.push((node_id.clone(), value)),
+            None => invalid_nodes.push(node_id.clone()),
+        }
+    }
+    // Sorted by canonical form then node id, so ties are broken the same way on every node
+    parsed.sort_by(|(a_id, a), (b_id, b)| a.to_string().cmp(&b.to_string()).then(a_id.cmp(b_id)));
+    invalid_nodes.sort();
+
+    let tolerance = result_type.get_tolerance();
+    let within = |a: &ParsedAnswer, b: &ParsedAnswer| match (a, b) {
+        (ParsedAnswer::Integer(a), ParsedAnswer::Integer(b)) => a == b,
+        (ParsedAnswer::Decimal(_), _) | (_, ParsedAnswer::Decimal(_)) => (a.as_f64() - b.as_f64()).abs() <= tolerance,
+        _ => a.to_string() == b.to_string(),
+    };
+    let canonical: Option<ParsedAnswer> = if parsed.is_empty() {
+        None
+    } else {
+        match aggregation {
+            AggregationFunction::Mode => {
+                // The answer that agrees with the most others, BTreeMap so the same answer wins a tie every time
+                let mut support: BTreeMap<String, (usize, ParsedAnswer)> = BTreeMap::new();
+                for (_, candidate) in parsed.iter() {
+                    let count = parsed.iter().filter(|(_, other)| within(candidate, other)).count();
+                    support.insert(candidate.to_string(), (count, candidate.clone()));
+                }
+                support.into_values().max_by_key(|(count, _)| *count).map(|(_, value)| value)
+            }
+            AggregationFunction::Median => Some(median(&parsed)),
+            AggregationFunction::MeanWithinTolerance => {
+                let median = median(&parsed);
+                let inliers: Vec<f64> = parsed.iter().filter(|(_, v)| within(v, &median)).map(|(_, v)| v.as_f64()).collect();
+                Some(ParsedAnswer::Decimal(inliers.iter().sum::<f64>() / inliers.len() as f64))
+            }
+            AggregationFunction::SetUnion => {
+                let mut union: BTreeMap<String, Value> = BTreeMap::new();
+                for (_, answer) in parsed.iter() {
+                    if let ParsedAnswer::Json(Value::Array(items)) = answer {
+                        for item in items {
+                            union.insert(canonical_json(item), item.clone());
+                        }
+                    }
+                }
+                Some(ParsedAnswer::Json(Value::Array(union.into_values().collect())))
+            }
+        }
+    };
+
+    let mut agreeing_nodes: Vec<AccountId> = vec![];
+    let mut disagreeing_nodes: Vec<AccountId> = vec![];
+    for (node_id, answer) in parsed.iter() {
+        let agrees = match (aggregation, &canonical) {
+            (AggregationFunction::SetUnion, _) => true,
+            (AggregationFunction::MeanWithinTolerance, _) => within(answer, &median(&parsed)),
+            (_, Some(canonical)) => within(answer, canonical),
+            (_, None) => false,
+        };
+        if agrees {
+            agreeing_nodes.push(node_id.clone());
+        } else {
+            disagreeing_nodes.push(node_id.clone());
+        }
+    }
+    agreeing_nodes.sort();
+    disagreeing_nodes.sort();
+    return AggregateResult {
+        result_type: result_type.clone(),
+        aggregation: aggregation.clone(),
+        canonical_result: canonical.map(|value| value.to_string()),
+        agreeing_nodes,
+        disagreeing_nodes,
+        invalid_nodes,
+    };
+}
+
+// The middle value, or the mean of the two middle values for an even count of decimals. Integers take the lower middle
+// value so the result is still an integer.
+fn median(parsed: &[(AccountId, ParsedAnswer)]) -> ParsedAnswer {
+    let mut values: Vec<&ParsedAnswer> = parsed.iter().map(|(_, v)| v).collect();
+    values.sort_by(|a, b| match (a, b) {
+        (ParsedAnswer::Integer(a), ParsedAnswer::Integer(b)) => a.cmp(b),
+        _ => a.as_f64().partial_cmp(&b.as_f64()).unwrap(),
+    });
+    let mid = (values.len() - 1) / 2;
+    return match values[mid] {
+        ParsedAnswer::Decimal(lower) if values.len().is_multiple_of(2) => ParsedAnswer::Decimal((lower + values[mid + 1].as_f64()) / 2.0),
+        value => value.clone(),
+    };
+}
+
+// JSON with object keys sorted and no whitespace, so equal values serialize the same way whatever features serde_json was built with
+fn canonical_json(value: &Value) -> String {
+    return match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<&String, String> = map.iter().map(|(k, v)| (k, canonical_json(v))).collect();
+            let fields: Vec<String> = sorted
+                .into_iter()
+                .map(|(k, v)| format!("{}:{}", serde_json::to_string(k).unwrap(), v))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => format!("[{}]", items.iter().map(canonical_json).collect::<Vec<String>>().join(",")),
+        other => other.to_string(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(values: &[&str]) -> Vec<(AccountId, String)> {
+        return values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (format!("node{}.near", i).parse().unwrap(), v.to_string()))
+            .collect();
+    }
+
+    fn ids(nodes: &[usize]) -> Vec<AccountId> {
+        return nodes.iter().map(|i| format!("node{}.near", i).parse().unwrap()).collect();
+    }
+
+    fn decimal(tolerance: &str) -> ResultType {
+        return ResultType::Decimal { tolerance: tolerance.to_string() };
+    }
+
+    #[test]
+    fn string_mode_matches_exact_answers() {
+        let result = aggregate(&ResultType::String, &AggregationFunction::Mode, &answers(&["42", "42", "42 "]));
+        assert_eq!(result.canonical_result, Some("42".to_string()));
+        assert_eq!(result.agreeing_nodes, ids(&[0, 1]));
+        assert_eq!(result.disagreeing_nodes, ids(&[2]));
+    }
+
+    #[test]
+    fn integer_mode_ignores_formatting_and_flags_garbage() {
+        let result = aggregate(&ResultType::Integer, &AggregationFunction::Mode, &answers(&["007", " 7", "8", "seven"]));
+        assert_eq!(result.canonical_result, Some("7".to_string()));
+        assert_eq!(result.agreeing_nodes, ids(&[0, 1]));
+        assert_eq!(result.disagreeing_nodes, ids(&[2]));
+        assert_eq!(result.invalid_nodes, ids(&[3]));
+    }
+
+    #[test]
+    fn decimal_mode_groups_answers_within_tolerance() {
+        let result = aggregate(&decimal("0.001"), &AggregationFunction::Mode, &answers(&["3.14159", "3.1418", "3.1412", "2.71"]));
+        assert_eq!(result.agreeing_nodes, ids(&[0, 1, 2]));
+        assert_eq!(result.disagreeing_nodes, ids(&[3]));
+        let canonical: f64 = result.canonical_result.unwrap().parse().unwrap();
+        assert!((canonical - 3.1416).abs() <= 0.001);
+    }
+
+    #[test]
+    fn median_of_integers_and_decimals() {
+        let result = aggregate(&ResultType::Integer, &AggregationFunction::Median, &answers(&["10", "1", "7", "3"]));
+        assert_eq!(result.canonical_result, Some("3".to_string()));
+        assert_eq!(result.agreeing_nodes, ids(&[3]));
+
+        let result = aggregate(&decimal("0.5"), &AggregationFunction::Median, &answers(&["1.0", "2.0", "3.0", "100"]));
+        assert_eq!(result.canonical_result, Some("2.5".to_string()));
+        assert_eq!(result.agreeing_nodes, ids(&[1, 2]));
+    }
+
+    #[test]
+    fn mean_within_tolerance_drops_outliers() {
+        let result = aggregate(&decimal("0.1"), &AggregationFunction::MeanWithinTolerance, &answers(&["1.0", "1.05", "0.95", "9.0"]));
+        let canonical: f64 = result.canonical_result.unwrap().parse().unwrap();
+        assert!((canonical - 1.0).abs() < 1e-9, "outlier should be left out of the mean, got {}", canonical);
+        assert_eq!(result.agreeing_nodes, ids(&[0, 1, 2]));
+        assert_eq!(result.disagreeing_nodes, ids(&[3]));
+    }
+
+    #[test]
+    fn json_mode_ignores_key_order_and_set_union_merges_arrays() {
+        let result = aggregate(&ResultType::Json, &AggregationFunction::Mode, &answers(&["{\"a\":1,\"b\":[2]}", "{ \"b\": [2], \"a\": 1 }", "{}"]));
+        assert_eq!(result.canonical_result, Some("{\"a\":1,\"b\":[2]}".to_string()));
+        assert_eq!(result.agreeing_nodes, ids(&[0, 1]));
+
+        let result = aggregate(&ResultType::Json, &AggregationFunction::SetUnion, &answers(&["[\"b\",\"a\"]", "[\"c\", \"a\"]", "{}"]));
+        assert_eq!(result.canonical_result, Some("[\"a\",\"b\",\"c\"]".to_string()));
+        assert_eq!(result.agreeing_nodes, ids(&[0, 1]));
+        assert_eq!(result.invalid_nodes, ids(&[2]));
+    }
+
+    #[test]
+    fn no_valid_answers_has_no_result() {
+        let result = aggregate(&ResultType::Integer, &AggregationFunction::Median, &answers(&["x"]));
+        assert_eq!(result.canonical_result, None);
+        assert!(result.agreeing_nodes.is_empty());
+    }
+
+    #[test]
+    fn aggregations_only_support_matching_types() {
+        assert!(AggregationFunction::Mode.supports(&ResultType::Json));
+        assert!(AggregationFunction::Median.supports(&ResultType::Integer));
+        assert!(!AggregationFunction::Median.supports(&ResultType::String));
+        assert!(!AggregationFunction::MeanWithinTolerance.supports(&ResultType::Integer));
+        assert!(!AggregationFunction::SetUnion.supports(&decimal("1")));
+        assert!(!decimal("-1").is_valid() && !decimal("NaN").is_valid() && !decimal("abc").is_valid());
+        assert!(decimal("0.01").results_agree("1.001", "1.0"));
+        assert!(!decimal("0.01").results_agree("1.1", "1.0"));
+    }
+}
@@ -1,16 +1,19 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
-use near_sdk::{AccountId, Balance, log, near_bindgen};
+use near_sdk::{AccountId, Balance, log, near_bindgen, require};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{UnorderedMap, UnorderedSet};
 use near_sdk::env::{
-    block_timestamp_ms, predecessor_account_id, signer_account_id,
+    block_timestamp, block_timestamp_ms, predecessor_account_id, signer_account_id,
+    storage_byte_cost,
 };
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Deserializer, Serialize, Serializer};
 use near_sdk::serde::de::{Error, MapAccess, Visitor};
 use near_sdk::serde::ser::SerializeStruct;
 
+use crate::aggregation::{aggregate, AggregateResult, AggregationFunction, ResultType};
 use crate::coordinator::PayoutStrategy;
 
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
@@ -73,6 +76,177 @@ impl Display for BountyStatus {
     }
 }
 
+// Every money-moving method records its effect here, so deposited == paid_out + refunded + remaining always holds
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Escrow {
+    pub deposited: Balance,
+    pub paid_out: Balance,
+    pub refunded: Balance,
+    pub remaining: Balance,
+}
+
+impl Escrow {
+    pub fn new(deposited: Balance) -> Self {
+        Self {
+            deposited,
+            paid_out: 0,
+            refunded: 0,
+            remaining: deposited,
+        }
+    }
+
+    pub fn deposit(&mut self, amount: Balance) {
+        self.deposited += amount;
+        self.remaining += amount;
+    }
+
+    pub fn pay_out(&mut self, amount: Balance) {
+        require!(amount <= self.remaining, "Escrow cannot pay out more than it holds");
+        self.paid_out += amount;
+        self.remaining -= amount;
+    }
+
+    pub fn refund(&mut self, amount: Balance) {
+        require!(amount <= self.remaining, "Escrow cannot refund more than it holds");
+        self.refunded += amount;
+        self.remaining -= amount;
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DisputeStatus {
+    Undisputed,
+    //Re-run is in flight, payouts stay locked until it closes
+    Pending,
+    //Re-run agreed with the original nodes, they get the reward and the owner's bond. If the re-run failed or expired
+    //the original nodes still get the reward, but the bond goes back to the owner
+    Upheld,
+    //Re-run succeeded and disagreed with the original nodes, the owner gets the reward and the bond back
+    Overturned,
+}
+
+impl Display for DisputeStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisputeStatus::Undisputed => write!(f, "UNDISPUTED"),
+            DisputeStatus::Pending => write!(f, "PENDING"),
+            DisputeStatus::Upheld => write!(f, "UPHELD"),
+            DisputeStatus::Overturned => write!(f, "OVERTURNED"),
+        }
+    }
+}
+
+// How the reward pool is divided between the nodes selected by the PayoutStrategy. Chosen by the owner at creation.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RewardPolicy {
+    EqualSplit,
+    //Only the first n recipients to answer are paid, the pool is split equally between them
+    FirstN { n: u64 },
+    //bonus_bps of the pool is weighted towards the fastest answers, the rest is split equally
+    LatencyWeighted { bonus_bps: u16 },
+    //Every recipient is paid a fixed price, whatever is left over is refunded to the owner when the bounty closes
+    FixedPerNode { price: U128 },
+    //Every recipient is paid the price it bid in the bounty's auction. Only used by auctions.
+    Bid,
+}
+
+impl Display for RewardPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RewardPolicy::EqualSplit => write!(f, "EqualSplit"),
+            RewardPolicy::FirstN { n } => write!(f, "FirstN({})", n),
+            RewardPolicy::LatencyWeighted { bonus_bps } => write!(f, "LatencyWeighted({}bps)", bonus_bps),
+            RewardPolicy::FixedPerNode { price } => write!(f, "FixedPerNode({})", price.0),
+            RewardPolicy::Bid => write!(f, "Bid"),
+        }
+    }
+}
+
+// A node's offer to run an auctioned bounty for price
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Bid {
+    pub node_id: AccountId,
+    pub price: U128,
+    pub timestamp: u64,
+}
+
+// The optional settings of create_bounty, passed as a single JSON object. Anything left out takes the default below.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Default)]
+#[serde(crate = "near_sdk::serde", default)]
+pub struct BountyOptions {
+    //Defaults to Bid for auctions, EqualSplit otherwise
+    pub reward_policy: Option<RewardPolicy>,
+    //Defaults to 0, the bounty can't be disputed
+    pub dispute_window_seconds: Option<u64>,
+    //Defaults to String
+    pub result_type: Option<ResultType>,
+    //Defaults to Mode
+    pub aggregation: Option<AggregationFunction>,
+    //Defaults to 0, nodes are elected immediately instead of bidding
+    pub bidding_window_seconds: Option<u64>,
+    //Defaults to 1
+    pub max_nodes_per_owner: Option<u64>,
+    //Defaults to None, any node in the coordinator can be elected
+    pub pool_id: Option<String>,
+    //Defaults to no excluded nodes
+    pub excluded_nodes: Option<Vec<AccountId>>,
+}
+
+impl BountyOptions {
+    // Dispute re-runs and audits inherit the original bounty's settings, but are always split equally and can't be disputed themselves.
+    // Every node that was elected for the original is excluded for good, so reelections and replacements can't hand it back to them.
+    pub fn rerun_of(bounty: &Bounty) -> Self {
+        let mut excluded_nodes = bounty.excluded_nodes.clone();
+        for node_id in bounty.elected_nodes.iter().chain(bounty.replaced_nodes.iter()) {
+            if !excluded_nodes.contains(node_id) {
+                excluded_nodes.push(node_id.clone());
+            }
+        }
+        Self {
+            reward_policy: Some(RewardPolicy::EqualSplit),
+            dispute_window_seconds: Some(0),
+            result_type: Some(bounty.result_type.clone()),
+            aggregation: Some(bounty.aggregation.clone()),
+            bidding_window_seconds: Some(0),
+            max_nodes_per_owner: Some(bounty.max_nodes_per_owner),
+            pool_id: bounty.pool_id.clone(),
+            excluded_nodes: Some(excluded_nodes),
+        }
+    }
+
+    pub fn get_reward_policy(&self) -> RewardPolicy {
+        return self.reward_policy.clone().unwrap_or(if self.get_bidding_window_seconds() > 0 { RewardPolicy::Bid } else { RewardPolicy::EqualSplit });
+    }
+
+    pub fn get_dispute_window_seconds(&self) -> u64 {
+        return self.dispute_window_seconds.unwrap_or(0);
+    }
+
+    pub fn get_result_type(&self) -> ResultType {
+        return self.result_type.clone().unwrap_or(ResultType::String);
+    }
+
+    pub fn get_aggregation(&self) -> AggregationFunction {
+        return self.aggregation.clone().unwrap_or(AggregationFunction::Mode);
+    }
+
+    pub fn get_bidding_window_seconds(&self) -> u64 {
+        return self.bidding_window_seconds.unwrap_or(0);
+    }
+
+    pub fn get_max_nodes_per_owner(&self) -> u64 {
+        return self.max_nodes_per_owner.unwrap_or(1);
+    }
+
+    pub fn get_excluded_nodes(&self) -> Vec<AccountId> {
+        return self.excluded_nodes.clone().unwrap_or_default();
+    }
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
@@ -80,7 +254,7 @@ pub struct NodeResponse {
     pub node_id: AccountId,
     pub solution: String,
     pub message: String,
-    // pub timestamp: u64,
+    pub timestamp: u64,
     pub status: NodeResponseStatus,
     pub payout_claimed: bool,
 }
@@ -99,7 +273,7 @@ impl NodeResponse {
             node_id,
             solution,
             message,
-            // timestamp: block_timestamp(),
+            timestamp: block_timestamp(),
             status,
             payout_claimed: false,
         }
@@ -148,6 +322,54 @@ pub struct Bounty {
     //Unused storage is refunded to the owner once the contract is closed
     pub amt_node_reward: Balance,
     //Total payout to the nodes.
+    pub amt_gas_reimbursement: Balance,
+    //Fixed reimbursement reserved from amt_node_reward for every node that answers or rejects before close
+    pub reward_policy: RewardPolicy,
+    //How the remainder of amt_node_reward is divided between the reward recipients
+    pub protocol_fee_bps: u16,
+    //Protocol fee in basis points, taken from every payout and credited to the coordinator's treasury
+    pub dispute_window_seconds: u64,
+    //How long after closing the owner can dispute the result. Payouts are locked until it passes. If 0, no disputes.
+    pub closed_at: u64,
+    //UTC timestamp for when the bounty was closed, 0 while pending
+    pub dispute_status: DisputeStatus,
+    //Undisputed, Pending, Upheld, Overturned
+    pub dispute_bounty_id: Option<AccountId>,
+    //Re-run bounty created when the owner disputes this bounty
+    pub amt_dispute_bond: Balance,
+    //Bond escrowed by the owner when disputing, goes to whichever side the re-run agrees with
+    pub parent_bounty_id: Option<AccountId>,
+    //Set on dispute re-runs, points at the disputed bounty
+    pub audit_levy_bps: u16,
+    //Levy in basis points taken from every payout to fund spot-check audits
+    pub escrow: Escrow,
+    //Ledger of every yoctoNEAR deposited into, paid out of and refunded from this bounty
+    pub storage_used: u64,
+    //Bytes of contract storage used by the bounty, measured as it's written. Paid for out of amt_storage.
+    pub event_seq: u64,
+    //Number of events emitted for this bounty, the latest event about it carries this value
+    pub result_type: ResultType,
+    //How answers are parsed and compared. String matches them byte for byte.
+    pub aggregation: AggregationFunction,
+    //How the successful answers are combined into the canonical result
+    pub bidding_ends_at: u64,
+    //UTC timestamp for when the auction stops taking bids, 0 if nodes are elected at creation
+    pub bids: Vec<Bid>,
+    //Bids placed while the auction is open, at most one per node
+    pub max_nodes_per_owner: u64,
+    //Most nodes with the same owner that can be elected for this bounty, 0 for no limit
+    pub pool_id: Option<String>,
+    //Pool the bounty's nodes are elected from, None for the public queue
+    pub excluded_nodes: Vec<AccountId>,
+    //Nodes the owner doesn't want elected for this bounty, on top of their blocklist
+    pub replaced_nodes: Vec<AccountId>,
+    //Nodes that were elected and later replaced, they're never elected for this bounty again
+    pub replacements: u64,
+    //Replacements elected for nodes that rejected the bounty, at most MAX_REPLACEMENTS
+    pub elected_at: u64,
+    //UTC timestamp (ms) for when nodes were last elected. Unanswered nodes time out timeout_seconds after it
+    pub reelection_rounds: u64,
+    //Times reelect_unanswered_nodes replaced timed out nodes, at most MAX_REELECTION_ROUNDS
     pub timeout_seconds: u64,
     // Bounty timeout in seconds. If 0, no timeout.
     // pub result: String, //TODO This was going to be the single, definitive result. Need to summarize all the responses to get this.
@@ -166,7 +388,7 @@ impl Serialize for Bounty {
             S: Serializer,
     {
         // 3 is the number of fields in the struct.
-        let mut state = serializer.serialize_struct("Bounty", 15)?;
+        let mut state = serializer.serialize_struct("Bounty", 39)?;
         state.serialize_field("id", &self.id)?;
         state.serialize_field("owner_id", &self.owner_id)?;
         state.serialize_field("coordinator_id", &self.coordinator_id)?;
@@ -179,6 +401,30 @@ impl Serialize for Bounty {
         state.serialize_field("gpu_required", &self.gpu_required)?;
         state.serialize_field("amt_storage", &self.amt_storage)?;
         state.serialize_field("amt_node_reward", &self.amt_node_reward)?;
+        state.serialize_field("amt_gas_reimbursement", &self.amt_gas_reimbursement)?;
+        state.serialize_field("reward_policy", &self.reward_policy)?;
+        state.serialize_field("protocol_fee_bps", &self.protocol_fee_bps)?;
+        state.serialize_field("dispute_window_seconds", &self.dispute_window_seconds)?;
+        state.serialize_field("closed_at", &self.closed_at)?;
+        state.serialize_field("dispute_status", &self.dispute_status)?;
+        state.serialize_field("dispute_bounty_id", &self.dispute_bounty_id)?;
+        state.serialize_field("amt_dispute_bond", &self.amt_dispute_bond)?;
+        state.serialize_field("parent_bounty_id", &self.parent_bounty_id)?;
+        state.serialize_field("audit_levy_bps", &self.audit_levy_bps)?;
+        state.serialize_field("escrow", &self.escrow)?;
+        state.serialize_field("storage_used", &self.storage_used)?;
+        state.serialize_field("event_seq", &self.event_seq)?;
+        state.serialize_field("result_type", &self.result_type)?;
+        state.serialize_field("aggregation", &self.aggregation)?;
+        state.serialize_field("bidding_ends_at", &self.bidding_ends_at)?;
+        state.serialize_field("bids", &self.bids)?;
+        state.serialize_field("max_nodes_per_owner", &self.max_nodes_per_owner)?;
+        state.serialize_field("pool_id", &self.pool_id)?;
+        state.serialize_field("excluded_nodes", &self.excluded_nodes)?;
+        state.serialize_field("replaced_nodes", &self.replaced_nodes)?;
+        state.serialize_field("replacements", &self.replacements)?;
+        state.serialize_field("elected_at", &self.elected_at)?;
+        state.serialize_field("reelection_rounds", &self.reelection_rounds)?;
         state.serialize_field("timeout_seconds", &self.timeout_seconds)?;
         state.serialize_field("elected_nodes", &self.elected_nodes)?;
         state.serialize_field("unanswered_nodes", &self.unanswered_nodes.to_vec())?;
@@ -225,6 +471,30 @@ impl<'de> Deserialize<'de> for Bounty {
                 let mut gpu_required = None;
                 let mut amt_storage = None;
                 let mut amt_node_reward = None;
+                let mut amt_gas_reimbursement = None;
+                let mut reward_policy = None;
+                let mut protocol_fee_bps = None;
+                let mut dispute_window_seconds = None;
+                let mut closed_at = None;
+                let mut dispute_status = None;
+                let mut dispute_bounty_id = None;
+                let mut amt_dispute_bond = None;
+                let mut parent_bounty_id = None;
+                let mut audit_levy_bps = None;
+                let mut escrow = None;
+                let mut storage_used = None;
+                let mut event_seq = None;
+                let mut result_type = None;
+                let mut aggregation = None;
+                let mut bidding_ends_at = None;
+                let mut bids = None;
+                let mut max_nodes_per_owner = None;
+                let mut pool_id = None;
+                let mut excluded_nodes = None;
+                let mut replaced_nodes = None;
+                let mut replacements = None;
+                let mut elected_at = None;
+                let mut reelection_rounds = None;
                 let mut timeout_seconds = None;
                 let mut elected_nodes = None;
 
@@ -302,6 +572,150 @@ impl<'de> Deserialize<'de> for Bounty {
                             }
                             amt_node_reward = Some(map.next_value()?);
                         }
+                        "amt_gas_reimbursement" => {
+                            if amt_gas_reimbursement.is_some() {
+                                return Err(Error::duplicate_field("amt_gas_reimbursement"));
+                            }
+                            amt_gas_reimbursement = Some(map.next_value()?);
+                        }
+                        "reward_policy" => {
+                            if reward_policy.is_some() {
+                                return Err(Error::duplicate_field("reward_policy"));
+                            }
+                            reward_policy = Some(map.next_value()?);
+                        }
+                        "protocol_fee_bps" => {
+                            if protocol_fee_bps.is_some() {
+                                return Err(Error::duplicate_field("protocol_fee_bps"));
+                            }
+                            protocol_fee_bps = Some(map.next_value()?);
+                        }
+                        "dispute_window_seconds" => {
+                            if dispute_window_seconds.is_some() {
+                                return Err(Error::duplicate_field("dispute_window_seconds"));
+                            }
+                            dispute_window_seconds = Some(map.next_value()?);
+                        }
+                        "closed_at" => {
+                            if closed_at.is_some() {
+                                return Err(Error::duplicate_field("closed_at"));
+                            }
+                            closed_at = Some(map.next_value()?);
+                        }
+                        "dispute_status" => {
+                            if dispute_status.is_some() {
+                                return Err(Error::duplicate_field("dispute_status"));
+                            }
+                            dispute_status = Some(map.next_value()?);
+                        }
+                        "dispute_bounty_id" => {
+                            if dispute_bounty_id.is_some() {
+                                return Err(Error::duplicate_field("dispute_bounty_id"));
+                            }
+                            dispute_bounty_id = Some(map.next_value()?);
+                        }
+                        "amt_dispute_bond" => {
+                            if amt_dispute_bond.is_some() {
+                                return Err(Error::duplicate_field("amt_dispute_bond"));
+                            }
+                            amt_dispute_bond = Some(map.next_value()?);
+                        }
+                        "parent_bounty_id" => {
+                            if parent_bounty_id.is_some() {
+                                return Err(Error::duplicate_field("parent_bounty_id"));
+                            }
+                            parent_bounty_id = Some(map.next_value()?);
+                        }
+                        "audit_levy_bps" => {
+                            if audit_levy_bps.is_some() {
+                                return Err(Error::duplicate_field("audit_levy_bps"));
+                            }
+                            audit_levy_bps = Some(map.next_value()?);
+                        }
+                        "escrow" => {
+                            if escrow.is_some() {
+                                return Err(Error::duplicate_field("escrow"));
+                            }
+                            escrow = Some(map.next_value()?);
+                        }
+                        "storage_used" => {
+                            if storage_used.is_some() {
+                                return Err(Error::duplicate_field("storage_used"));
+                            }
+                            storage_used = Some(map.next_value()?);
+                        }
+                        "event_seq" => {
+                            if event_seq.is_some() {
+                                return Err(Error::duplicate_field("event_seq"));
+                            }
+                            event_seq = Some(map.next_value()?);
+                        }
+                        "result_type" => {
+                            if result_type.is_some() {
+                                return Err(Error::duplicate_field("result_type"));
+                            }
+                            result_type = Some(map.next_value()?);
+                        }
+                        "aggregation" => {
+                            if aggregation.is_some() {
+                                return Err(Error::duplicate_field("aggregation"));
+                            }
+                            aggregation = Some(map.next_value()?);
+                        }
+                        "bidding_ends_at" => {
+                            if bidding_ends_at.is_some() {
+                                return Err(Error::duplicate_field("bidding_ends_at"));
+                            }
+                            bidding_ends_at = Some(map.next_value()?);
+                        }
+                        "bids" => {
+                            if bids.is_some() {
+                                return Err(Error::duplicate_field("bids"));
+                            }
+                            bids = Some(map.next_value()?);
+                        }
+                        "max_nodes_per_owner" => {
+                            if max_nodes_per_owner.is_some() {
+                                return Err(Error::duplicate_field("max_nodes_per_owner"));
+                            }
+                            max_nodes_per_owner = Some(map.next_value()?);
+                        }
+                        "pool_id" => {
+                            if pool_id.is_some() {
+                                return Err(Error::duplicate_field("pool_id"));
+                            }
+                            pool_id = Some(map.next_value()?);
+                        }
+                        "excluded_nodes" => {
+                            if excluded_nodes.is_some() {
+                                return Err(Error::duplicate_field("excluded_nodes"));
+                            }
+                            excluded_nodes = Some(map.next_value()?);
+                        }
+                        "replaced_nodes" => {
+                            if replaced_nodes.is_some() {
+                                return Err(Error::duplicate_field("replaced_nodes"));
+                            }
+                            replaced_nodes = Some(map.next_value()?);
+                        }
+                        "replacements" => {
+                            if replacements.is_some() {
+                                return Err(Error::duplicate_field("replacements"));
+                            }
+                            replacements = Some(map.next_value()?);
+                        }
+                        "elected_at" => {
+                            if elected_at.is_some() {
+                                return Err(Error::duplicate_field("elected_at"));
+                            }
+                            elected_at = Some(map.next_value()?);
+                        }
+                        "reelection_rounds" => {
+                            if reelection_rounds.is_some() {
+                                return Err(Error::duplicate_field("reelection_rounds"));
+                            }
+                            reelection_rounds = Some(map.next_value()?);
+                        }
                         "timeout_seconds" => {
                             if timeout_seconds.is_some() {
                                 return Err(Error::duplicate_field("timeout_seconds"));
@@ -337,6 +751,32 @@ impl<'de> Deserialize<'de> for Bounty {
                 let amt_storage = amt_storage.ok_or_else(|| Error::missing_field("amt_storage"))?;
                 let amt_node_reward =
                     amt_node_reward.ok_or_else(|| Error::missing_field("amt_node_reward"))?;
+                let amt_gas_reimbursement = amt_gas_reimbursement
+                    .ok_or_else(|| Error::missing_field("amt_gas_reimbursement"))?;
+                let reward_policy =
+                    reward_policy.ok_or_else(|| Error::missing_field("reward_policy"))?;
+                let protocol_fee_bps = protocol_fee_bps.ok_or_else(|| Error::missing_field("protocol_fee_bps"))?;
+                let dispute_window_seconds = dispute_window_seconds.ok_or_else(|| Error::missing_field("dispute_window_seconds"))?;
+                let closed_at = closed_at.ok_or_else(|| Error::missing_field("closed_at"))?;
+                let dispute_status = dispute_status.ok_or_else(|| Error::missing_field("dispute_status"))?;
+                let dispute_bounty_id = dispute_bounty_id.ok_or_else(|| Error::missing_field("dispute_bounty_id"))?;
+                let amt_dispute_bond = amt_dispute_bond.ok_or_else(|| Error::missing_field("amt_dispute_bond"))?;
+                let parent_bounty_id = parent_bounty_id.ok_or_else(|| Error::missing_field("parent_bounty_id"))?;
+                let audit_levy_bps = audit_levy_bps.ok_or_else(|| Error::missing_field("audit_levy_bps"))?;
+                let escrow = escrow.ok_or_else(|| Error::missing_field("escrow"))?;
+                let storage_used = storage_used.ok_or_else(|| Error::missing_field("storage_used"))?;
+                let event_seq = event_seq.ok_or_else(|| Error::missing_field("event_seq"))?;
+                let result_type = result_type.ok_or_else(|| Error::missing_field("result_type"))?;
+                let aggregation = aggregation.ok_or_else(|| Error::missing_field("aggregation"))?;
+                let bidding_ends_at = bidding_ends_at.ok_or_else(|| Error::missing_field("bidding_ends_at"))?;
+                let bids = bids.ok_or_else(|| Error::missing_field("bids"))?;
+                let max_nodes_per_owner = max_nodes_per_owner.ok_or_else(|| Error::missing_field("max_nodes_per_owner"))?;
+                let pool_id = pool_id.ok_or_else(|| Error::missing_field("pool_id"))?;
+                let excluded_nodes = excluded_nodes.ok_or_else(|| Error::missing_field("excluded_nodes"))?;
+                let replaced_nodes = replaced_nodes.ok_or_else(|| Error::missing_field("replaced_nodes"))?;
+                let replacements = replacements.ok_or_else(|| Error::missing_field("replacements"))?;
+                let elected_at = elected_at.ok_or_else(|| Error::missing_field("elected_at"))?;
+                let reelection_rounds = reelection_rounds.ok_or_else(|| Error::missing_field("reelection_rounds"))?;
                 let timeout_seconds =
                     timeout_seconds.ok_or_else(|| Error::missing_field("timeout_seconds"))?;
                 let elected_nodes =
@@ -354,6 +794,30 @@ impl<'de> Deserialize<'de> for Bounty {
                     gpu_required,
                     amt_storage,
                     amt_node_reward,
+                    amt_gas_reimbursement,
+                    reward_policy,
+                    protocol_fee_bps,
+                    dispute_window_seconds,
+                    closed_at,
+                    dispute_status,
+                    dispute_bounty_id,
+                    amt_dispute_bond,
+                    parent_bounty_id,
+                    audit_levy_bps,
+                    escrow,
+                    storage_used,
+                    event_seq,
+                    result_type,
+                    aggregation,
+                    bidding_ends_at,
+                    bids,
+                    max_nodes_per_owner,
+                    pool_id,
+                    excluded_nodes,
+                    replaced_nodes,
+                    replacements,
+                    elected_at,
+                    reelection_rounds,
                     timeout_seconds,
                     elected_nodes,
                     //TODO The below isn't real deserialization
@@ -388,6 +852,30 @@ impl<'de> Deserialize<'de> for Bounty {
             "gpu_required",
             "amt_storage",
             "amt_node_reward",
+            "amt_gas_reimbursement",
+            "reward_policy",
+            "protocol_fee_bps",
+            "dispute_window_seconds",
+            "closed_at",
+            "dispute_status",
+            "dispute_bounty_id",
+            "amt_dispute_bond",
+            "parent_bounty_id",
+            "audit_levy_bps",
+            "escrow",
+            "storage_used",
+            "event_seq",
+            "result_type",
+            "aggregation",
+            "bidding_ends_at",
+            "bids",
+            "max_nodes_per_owner",
+            "pool_id",
+            "excluded_nodes",
+            "replaced_nodes",
+            "replacements",
+            "elected_at",
+            "reelection_rounds",
             "timeout_seconds",
             "elected_nodes",
         ];
@@ -407,11 +895,35 @@ impl PartialEq<Self> for Bounty {
             && self.status == other.status
             && self.min_nodes == other.min_nodes
             && self.timeout_seconds == other.timeout_seconds
+            && self.reelection_rounds == other.reelection_rounds
+            && self.elected_at == other.elected_at
+            && self.replacements == other.replacements
+            && self.replaced_nodes == other.replaced_nodes
+            && self.excluded_nodes == other.excluded_nodes
+            && self.pool_id == other.pool_id
+            && self.max_nodes_per_owner == other.max_nodes_per_owner
+            && self.bids == other.bids
+            && self.bidding_ends_at == other.bidding_ends_at
+            && self.aggregation == other.aggregation
+            && self.result_type == other.result_type
+            && self.event_seq == other.event_seq
+            && self.storage_used == other.storage_used
+            && self.escrow == other.escrow
+            && self.audit_levy_bps == other.audit_levy_bps
+            && self.parent_bounty_id == other.parent_bounty_id
+            && self.amt_dispute_bond == other.amt_dispute_bond
+            && self.dispute_bounty_id == other.dispute_bounty_id
+            && self.dispute_status == other.dispute_status
+            && self.closed_at == other.closed_at
+            && self.dispute_window_seconds == other.dispute_window_seconds
+            && self.protocol_fee_bps == other.protocol_fee_bps
             && self.bounty_created == other.bounty_created
             && self.network_required == other.network_required
             && self.gpu_required == other.gpu_required
             && self.amt_storage == other.amt_storage
             && self.amt_node_reward == other.amt_node_reward
+            && self.amt_gas_reimbursement == other.amt_gas_reimbursement
+            && self.reward_policy == other.reward_policy
             && self.elected_nodes == other.elected_nodes
             && self.answers.len() == other.answers.len() //TODO: Make this a real comparison
             && self.failed_nodes.len() == other.failed_nodes.len() //TODO: Make this a real comparison
@@ -432,11 +944,35 @@ impl Default for Bounty {
             status: BountyStatus::Pending,
             min_nodes: 0,
             timeout_seconds: 30,
+            reelection_rounds: 0,
+            elected_at: 0,
+            replacements: 0,
+            replaced_nodes: vec![],
+            excluded_nodes: vec![],
+            pool_id: None,
+            max_nodes_per_owner: 1,
+            bids: Vec::new(),
+            bidding_ends_at: 0,
+            aggregation: AggregationFunction::Mode,
+            result_type: ResultType::String,
+            event_seq: 0,
+            storage_used: 0,
+            escrow: Escrow::default(),
+            audit_levy_bps: 0,
+            parent_bounty_id: None,
+            amt_dispute_bond: 0,
+            dispute_bounty_id: None,
+            dispute_status: DisputeStatus::Undisputed,
+            closed_at: 0,
+            dispute_window_seconds: 0,
+            protocol_fee_bps: 0,
             bounty_created: block_timestamp_ms(),
             network_required: false,
             gpu_required: false,
             amt_storage: 0,
             amt_node_reward: 0,
+            amt_gas_reimbursement: 0,
+            reward_policy: RewardPolicy::EqualSplit,
             elected_nodes: Vec::new(),
             answers: UnorderedMap::new("bounty-answers".as_bytes()),
             failed_nodes: UnorderedSet::new("bounty-failed-nodes".as_bytes()),
@@ -462,7 +998,12 @@ impl Bounty {
         gpu_required: bool,
         amt_storage: u128,
         amt_node_reward: u128,
+        amt_gas_reimbursement: u128,
+        protocol_fee_bps: u16,
+        audit_levy_bps: u16,
+        options: BountyOptions,
     ) -> Self {
+        let bidding_window_seconds = options.get_bidding_window_seconds();
         Self {
             id: id.clone(),
             owner_id: signer_account_id(),
@@ -492,6 +1033,30 @@ impl Bounty {
             gpu_required,
             amt_storage, // Unused storage is refunded to the creator once the contract is closed
             amt_node_reward, // If the bounty is completed, nodes will be reimbursed for spent gas. If it's completed AND successful, nodes get full reward
+            amt_gas_reimbursement, // Reserved out of amt_node_reward for every node that answers before the bounty closes
+            reward_policy: options.get_reward_policy(),
+            protocol_fee_bps,
+            dispute_window_seconds: options.get_dispute_window_seconds(),
+            closed_at: 0,
+            dispute_status: DisputeStatus::Undisputed,
+            dispute_bounty_id: None,
+            amt_dispute_bond: 0,
+            parent_bounty_id: None,
+            audit_levy_bps,
+            escrow: Escrow::new(amt_storage + amt_node_reward),
+            storage_used: 0,
+            event_seq: 0,
+            result_type: options.get_result_type(),
+            aggregation: options.get_aggregation(),
+            bidding_ends_at: if bidding_window_seconds > 0 { block_timestamp_ms() + bidding_window_seconds * 1000 } else { 0 },
+            bids: Vec::new(),
+            max_nodes_per_owner: options.get_max_nodes_per_owner(),
+            excluded_nodes: options.get_excluded_nodes(),
+            pool_id: options.pool_id,
+            replaced_nodes: vec![],
+            replacements: 0,
+            elected_at: block_timestamp_ms(),
+            reelection_rounds: 0,
         }
     }
 
@@ -513,8 +1078,13 @@ impl Bounty {
         return res;
     }
 
+    // A bounty only succeeds once at least min_nodes successful answers agree on its canonical result
+    pub fn has_consensus(&self) -> bool {
+        return self.get_aggregate_result().agreeing_nodes.len() as u64 >= self.min_nodes;
+    }
+
     pub fn get_payout_strategy(&self) -> PayoutStrategy {
-        if self.successful_nodes.len() >= self.min_nodes {
+        if self.has_consensus() {
             return PayoutStrategy::SuccessfulNodes;
         } else if self.failed_nodes.len() >= self.min_nodes {
             return PayoutStrategy::FailedNodes;
@@ -525,18 +1095,235 @@ impl Bounty {
         }
     }
 
-    pub fn get_amt_reward_per_node(&self) -> Balance {
-        return match self.get_payout_strategy() {
-            PayoutStrategy::AllAnsweredNodes => self.amt_node_reward / self.answers.len() as u128,
-            PayoutStrategy::FailedNodes => self.amt_node_reward / self.failed_nodes.len() as u128,
-            PayoutStrategy::SuccessfulNodes => self.amt_node_reward / self.successful_nodes.len() as u128, //Technically could be amt_node_reward/min_nodes
+    // Elected nodes that answered (success, failure or reject) before close, each one earns the reimbursement.
+    // Rejecters that were replaced earn it too, replace_rejected_node only swaps one out while amt_node_reward covers it.
+    pub fn get_reimbursed_node_ids(&self) -> Vec<AccountId> {
+        return self
+            .elected_nodes
+            .iter()
+            .chain(self.replaced_nodes.iter())
+            .filter(|node_id| self.answers.get(node_id).is_some())
+            .cloned()
+            .collect();
+    }
+
+    pub fn get_amt_gas_reimbursement_total(&self) -> Balance {
+        return self.amt_gas_reimbursement * self.get_reimbursed_node_ids().len() as u128;
+    }
+
+    // What's left of amt_node_reward once every answering node has been reimbursed
+    pub fn get_amt_reward_pool(&self) -> Balance {
+        return self.amt_node_reward.saturating_sub(self.get_amt_gas_reimbursement_total());
+    }
+
+    // Payout recipients ordered by when they answered, fastest first
+    pub fn get_payout_recipient_ids_by_answer_time(&self) -> Vec<AccountId> {
+        let mut recipients: Vec<(u64, AccountId)> = self
+            .get_payout_recipient_ids()
+            .into_iter()
+            .map(|id| (self.answers.get(&id).unwrap().timestamp, id))
+            .collect();
+        // Stable sort, so answers in the same block keep the order they were posted in
+        recipients.sort_by_key(|(timestamp, _)| *timestamp);
+        return recipients.into_iter().map(|(_, id)| id).collect();
+    }
+
+    // Splits the reward pool between the payout recipients according to the bounty's reward policy
+    pub fn get_reward_amounts(&self) -> Vec<(AccountId, Balance)> {
+        let recipients = self.get_payout_recipient_ids_by_answer_time();
+        if recipients.is_empty() {
+            return vec![];
+        }
+        let pool = self.get_amt_reward_pool();
+        let n = recipients.len() as u128;
+        return match &self.reward_policy {
+            RewardPolicy::EqualSplit => recipients.into_iter().map(|id| (id, pool / n)).collect(),
+            RewardPolicy::FirstN { n: paid } => {
+                let paid = std::cmp::min(*paid as u128, n);
+                recipients
+                    .into_iter()
+                    .enumerate()
+                    .map(|(rank, id)| (id, if (rank as u128) < paid { pool / paid } else { 0 }))
+                    .collect()
+            }
+            RewardPolicy::LatencyWeighted { bonus_bps } => {
+                // The fastest recipient has a weight of n, the slowest has a weight of 1
+                let bonus_pool = pool * *bonus_bps as u128 / 10_000;
+                let base = (pool - bonus_pool) / n;
+                let total_weight = n * (n + 1) / 2;
+                recipients
+                    .into_iter()
+                    .enumerate()
+                    .map(|(rank, id)| (id, base + bonus_pool * (n - rank as u128) / total_weight))
+                    .collect()
+            }
+            RewardPolicy::FixedPerNode { price } => {
+                let mut remaining = pool;
+                recipients
+                    .into_iter()
+                    .map(|id| {
+                        let amount = std::cmp::min(price.0, remaining);
+                        remaining -= amount;
+                        (id, amount)
+                    })
+                    .collect()
+            }
+            RewardPolicy::Bid => {
+                let mut remaining = pool;
+                recipients
+                    .into_iter()
+                    .map(|id| {
+                        let price = self.get_bid(&id).map(|bid| bid.price.0).unwrap_or(0);
+                        let amount = std::cmp::min(price, remaining);
+                        remaining -= amount;
+                        (id, amount)
+                    })
+                    .collect()
+            }
+        };
+    }
+
+    pub fn get_bid(&self, node_id: &AccountId) -> Option<&Bid> {
+        return self.bids.iter().find(|bid| &bid.node_id == node_id);
+    }
+
+    // Whether file_location names exactly one version of the package: an IPFS cid, a git url ending in #<commit sha>,
+    // or an https url ending in #sha256=<hex digest> that nodes check the download against
+    pub fn is_package_pinned(&self) -> bool {
+        let is_hex = |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_hexdigit());
+        return match self.file_download_protocol {
+            SupportedDownloadProtocols::IPFS => true,
+            SupportedDownloadProtocols::GIT => {
+                self.file_location.rsplit_once('#').is_some_and(|(_, commit)| is_hex(commit, 40))
+            }
+            SupportedDownloadProtocols::HTTPS => self
+                .file_location
+                .rsplit_once("#sha256=")
+                .is_some_and(|(_, digest)| is_hex(digest, 64)),
+            SupportedDownloadProtocols::EMPTY => false,
         };
     }
+
+    pub fn is_auction(&self) -> bool {
+        return self.bidding_ends_at > 0;
+    }
+
+    // Auctions elect nodes when bidding closes, until then the bounty is pending with nobody elected
+    pub fn is_bidding(&self) -> bool {
+        return self.is_auction() && self.status == BountyStatus::Pending && self.elected_nodes.is_empty();
+    }
+
+    // The most a node can bid, so that any set of winning bids fits in amt_node_reward after gas reimbursements
+    pub fn get_amt_max_bid(&self, total_nodes: u64) -> Balance {
+        let total_nodes = total_nodes.max(1) as u128;
+        return self.amt_node_reward.saturating_sub(self.amt_gas_reimbursement * total_nodes) / total_nodes;
+    }
+
+    pub fn get_amt_reward_for_node(&self, node_id: &AccountId) -> Balance {
+        return self
+            .get_reward_amounts()
+            .into_iter()
+            .find(|(id, _)| id == node_id)
+            .map(|(_, amount)| amount)
+            .unwrap_or(0);
+    }
+
+    // Reimbursements plus rewards owed to answering nodes, before any dispute is settled
+    pub fn get_amt_promised_payouts(&self) -> Balance {
+        let rewards: Balance = self.get_reward_amounts().iter().map(|(_, amount)| amount).sum();
+        return self.get_amt_gas_reimbursement_total() + rewards;
+    }
+
+    // Reward that no node is entitled to, returned to the owner when the bounty closes.
+    // Covers the remainder of fixed price rewards, cancelled bounties nobody answered, and integer division dust.
+    pub fn get_amt_reward_refund(&self) -> Balance {
+        return self.amt_node_reward.saturating_sub(self.get_amt_promised_payouts());
+    }
+
+    pub fn get_amt_storage_used(&self) -> Balance {
+        return self.storage_used as u128 * storage_byte_cost();
+    }
+
+    pub fn get_amt_dispute_bond_share(&self) -> Balance {
+        let recipients = self.get_payout_recipient_ids().len() as u128;
+        if recipients == 0 {
+            return 0;
+        }
+        return self.amt_dispute_bond / recipients;
+    }
+
+    // Total owed to a node: the gas reimbursement for answering, plus its share of the reward under the payout strategy and reward policy
+    pub fn get_amt_payout_for_node(&self, node_id: &AccountId) -> Balance {
+        if self.answers.get(node_id).is_none() || self.dispute_status == DisputeStatus::Overturned {
+            return 0;
+        }
+        let mut payout = self.get_amt_reward_for_node(node_id);
+        if self.get_reimbursed_node_ids().contains(node_id) {
+            payout += self.amt_gas_reimbursement;
+        }
+        if self.dispute_status == DisputeStatus::Upheld && self.get_payout_recipient_ids().contains(node_id) {
+            payout += self.get_amt_dispute_bond_share();
+        }
+        return payout;
+    }
+
+    // Payouts are escrowed while the owner can still dispute the bounty, and while a dispute is being re-run
+    pub fn is_payout_locked(&self) -> bool {
+        return match self.dispute_status {
+            DisputeStatus::Pending => true,
+            DisputeStatus::Undisputed => self.is_disputable(),
+            DisputeStatus::Upheld | DisputeStatus::Overturned => false,
+        };
+    }
+
+    pub fn is_disputable(&self) -> bool {
+        return self.status == BountyStatus::Success
+            && self.dispute_status == DisputeStatus::Undisputed
+            && block_timestamp_ms() < self.closed_at + self.dispute_window_seconds * 1000;
+    }
+
+    // The canonical result of the successful answers under the bounty's result type and aggregation, and which answers agree with it
+    pub fn get_aggregate_result(&self) -> AggregateResult {
+        let answers: Vec<(AccountId, String)> = self
+            .successful_nodes
+            .iter()
+            .map(|node_id| (node_id.clone(), self.answers.get(&node_id).unwrap().solution))
+            .collect();
+        return aggregate(&self.result_type, &self.aggregation, &answers);
+    }
+
+    // The canonical result, used to compare a dispute re-run or an audit against the original
+    pub fn get_consensus_solution(&self) -> Option<String> {
+        return self.get_aggregate_result().canonical_result;
+    }
+
+    // Whether another run of this bounty came to the same result, within tolerance for decimals
+    pub fn agrees_with(&self, other: &Bounty) -> bool {
+        return match (self.get_consensus_solution(), other.get_consensus_solution()) {
+            (Some(ours), Some(theirs)) => self.result_type.results_agree(&ours, &theirs),
+            _ => false,
+        };
+    }
+
+    pub fn get_amt_protocol_fee(&self, payout: Balance) -> Balance {
+        return payout * self.protocol_fee_bps as u128 / 10_000;
+    }
+
+    // Fees the treasury will collect once every node has collected its payout
+    pub fn get_amt_fees_total(&self) -> Balance {
+        return self
+            .answers
+            .keys()
+            .map(|node_id| self.get_amt_protocol_fee(self.get_amt_payout_for_node(&node_id)))
+            .sum();
+    }
+
     pub fn get_payout_recipient_ids(&self) -> Vec<AccountId> {
         return match self.get_payout_strategy() {
             PayoutStrategy::AllAnsweredNodes => self.answers.keys().collect(),
             PayoutStrategy::FailedNodes => self.failed_nodes.iter().collect(),
-            PayoutStrategy::SuccessfulNodes => self.successful_nodes.iter().collect(),
+            // Successful answers that disagree with the canonical result are reimbursed, but don't share the reward
+            PayoutStrategy::SuccessfulNodes => self.get_aggregate_result().agreeing_nodes,
         };
     }
     pub fn get_payout_recipients_by_payout_claimed(&self, payout_claimed: bool) -> Vec<AccountId> {
@@ -1,30 +1,57 @@
 use std::collections::HashMap;
-use std::mem::size_of_val;
 
 use near_sdk::{AccountId, Balance, env, log, near_bindgen, Promise, require};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{UnorderedMap, UnorderedSet};
 use near_sdk::env::{
-    attached_deposit, block_timestamp, current_account_id, log_str, random_seed, signer_account_id,
-    storage_byte_cost,
+    account_balance, attached_deposit, block_timestamp, block_timestamp_ms, current_account_id, log_str, random_seed,
+    signer_account_id, storage_usage,
 };
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_units::parse_near;
 
+use crate::aggregation::AggregateResult;
 use crate::bounty::{
-    Bounty, BountyStatus, NodeResponse, NodeResponseStatus, SupportedDownloadProtocols,
+    Bid, Bounty, BountyOptions, BountyStatus, DisputeStatus, NodeResponse, NodeResponseStatus, RewardPolicy,
+    SupportedDownloadProtocols,
 };
-use crate::events::{BountyCompletedLog, BountyCreatedLog, BountyRetryLog, EventLog, EventLogVariant};
-use crate::node::Node;
+use crate::events::{
+    AnswerPostedLog, BountyAuditMismatchLog, BountyAwardedLog, BountyBidPlacedLog, BountyCompletedLog, BountyCreatedLog, BountyDepositAddedLog,
+    BountyDepositKind, BountyDisputedLog, BountyDisputeResolvedLog, BountyReelectedLog, BountyRejectedLog, BountyRetryLog, EventLog,
+    EventLogVariant, NodeRegisteredLog, NodeRemovedLog, NodeStatusChangedLog, NodeUpdatedLog, PoolCreatedLog,
+    PoolMembershipChangedLog, RewardAmountLog, RewardCollectedLog,
+};
+use crate::node::{AcceptanceRules, Node};
+use crate::pool::{Pool, PoolMembershipChange, PoolStats};
 
 pub const MIN_STORAGE: Balance = parse_near!("0.1 N");
 pub const MIN_REWARD: Balance = parse_near!("0.1 N");
-pub const EVENT_STANDARD_NAME: &str = "NEP-297";
+//Default amount reserved from a bounty's node reward for every node that answers, roughly the cost of post_answer
+pub const DEFAULT_GAS_REIMBURSEMENT: Balance = parse_near!("0.001 N");
+//Upper bound on the protocol fee the admin can set, in basis points
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1000;
+//Minimum bond the owner must escrow on top of the re-run's storage and reward to dispute a bounty
+pub const MIN_DISPUTE_BOND: Balance = parse_near!("0.1 N");
+//How long a dispute re-run has to close, or its own timeout if that's longer. After that anyone can expire it.
+pub const RERUN_TIMEOUT_SECONDS: u64 = 86_400;
+//Upper bounds on the audit settings the admin can set, in basis points
+pub const MAX_AUDIT_RATE_BPS: u16 = 10_000;
+pub const MAX_AUDIT_LEVY_BPS: u16 = 500;
+pub const EVENT_STANDARD_NAME: &str = "crtimk";
 pub const EVENT_STANDARD_SPEC: &str = "1.0.0";
 pub const BOUNTY_CREATED_EVENT_NAME: &str = "BountyCreated";
 pub const BOUNTY_COMPLETED_EVENT_NAME: &str = "BountyCompleted";
 //How many extra nodes to elect for each bounty to allow for node failures
 pub const NODE_PADDING: f64 = 1.25;
+//Nodes with no runs yet can bid on auctions, otherwise at least this share of their runs must have succeeded
+pub const MIN_BID_SUCCESS_RATE_BPS: u64 = 5000;
+//Most entries an owner's blocklist, or a bounty's exclude list, can hold. Every election checks them.
+pub const MAX_BLOCKLIST_LEN: usize = 100;
+//Most replacements a bounty elects for nodes that reject it, so rejections can't keep growing its elected nodes
+pub const MAX_REPLACEMENTS: u64 = 10;
+//Most times a bounty's timed out nodes can be reelected, after that the owner should cancel it
+pub const MAX_REELECTION_ROUNDS: u64 = 3;
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub enum PayoutStrategy {
@@ -35,6 +62,94 @@ pub enum PayoutStrategy {
     AllAnsweredNodes, //If a bounty is cancelled, all nodes should get paid
 }
 
+// Result of the solvency invariant check. Amounts are strings since javascript doesn't support u128.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SolvencyReport {
+    pub escrowed: U128,
+    pub total_escrowed: U128,
+    pub treasury_balance: U128,
+    pub audit_pool: U128,
+    pub node_deposits: U128,
+    pub contract_balance: U128,
+    //total_escrowed matches the sum of every bounty's ledger
+    pub ledger_consistent: bool,
+    //The contract holds at least as much as it owes to bounties, the treasury, the audit pool and node deposits
+    pub solvent: bool,
+}
+
+// What collect_reward would pay a node for a bounty right now. Amounts are strings since javascript doesn't support u128.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingPayout {
+    pub node_id: AccountId,
+    pub bounty_id: AccountId,
+    //Gas reimbursement plus the node's share of the reward, before fees
+    pub gross: U128,
+    pub fee: U128,
+    pub audit_levy: U128,
+    //What the node owner receives
+    pub net: U128,
+}
+
+// Admin set limits on what a single account can use, so one account can't crowd out everybody else
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Quotas {
+    //Nodes an account can have registered, online or offline. 0 for no limit
+    pub max_nodes_per_owner: u64,
+    //Bounties an account can have in flight at once. 0 for no limit
+    pub max_active_bounties_per_owner: u64,
+    //Share of any one bounty's elected nodes that can belong to one account, in basis points. An account can always have one
+    pub max_elected_share_bps: u16,
+}
+
+impl Default for Quotas {
+    fn default() -> Self {
+        Self {
+            max_nodes_per_owner: 0,
+            max_active_bounties_per_owner: 0,
+            max_elected_share_bps: 10_000,
+        }
+    }
+}
+
+// An account's usage next to the quotas that apply to it
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuotaUsage {
+    pub account_id: AccountId,
+    pub quotas: Quotas,
+    //True if the admin set quotas for this account, rather than it using the coordinator's defaults
+    pub overridden: bool,
+    pub nodes: u64,
+    pub active_bounties: u64,
+}
+
+// Nodes and node owners a bounty owner never wants elected for their bounties
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Blocklist {
+    pub node_ids: Vec<AccountId>,
+    //Every node of these accounts is blocked, including ones registered later
+    pub owner_ids: Vec<AccountId>,
+}
+
+impl Blocklist {
+    pub fn blocks(&self, node: &Node) -> bool {
+        return self.node_ids.contains(&node.id) || self.owner_ids.contains(&node.owner_id);
+    }
+}
+
+// Why an election couldn't fill every slot, see try_elect_nodes
+#[derive(Debug, PartialEq)]
+pub enum ElectionShortfall {
+    //Not enough qualified nodes
+    Nodes,
+    //Enough qualified nodes, but too many of them share an owner
+    Owners,
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Coordinator {
@@ -48,8 +163,36 @@ pub struct Coordinator {
     pub universal_bounty_index: u64,
     pub total_completed_bounties: u64,
     pub total_payouts: Balance,
+    pub gas_reimbursement: Balance,
+    //Account allowed to change protocol settings such as the fee rate
+    pub admin_id: AccountId,
+    pub protocol_fee_bps: u16,
+    //Treasury withdrawals are only ever sent to this account
+    pub treasury_account_id: AccountId,
+    pub treasury_balance: Balance,
+    pub total_fees: Balance,
+    //Fraction of closed successful bounties that are re-run on disjoint nodes to audit the result
+    pub audit_rate_bps: u16,
+    pub audit_levy_bps: u16,
+    //Funded by the audit levy, pays for audit bounties
+    pub audit_pool: Balance,
+    //Sum of every bounty's escrow.remaining, kept in step with the per-bounty ledgers
+    pub total_escrowed: Balance,
+    //Incremented for every emitted event, so listeners can detect the ones they missed
+    pub event_nonce: u64,
+    //Audit bounty id -> audited bounty id. Kept off the bounty so nodes can't tell audits apart from normal bounties
+    audits: UnorderedMap<AccountId, AccountId>,
     pub node_by_owner: UnorderedMap<AccountId, Vec<AccountId>>,
     pub bounty_by_owner: UnorderedMap<AccountId, Vec<AccountId>>,
+    pub quotas: Quotas,
+    //Accounts the admin gave their own quotas, in place of the defaults above
+    pub owner_quotas: UnorderedMap<AccountId, Quotas>,
+    //Private node pools by id, bounties can target one to only elect its members
+    pub pools: UnorderedMap<String, Pool>,
+    //Bounty owner -> nodes and node owners they don't want elected, paid for by the bounty owner
+    pub blocklists: UnorderedMap<AccountId, Blocklist>,
+    //Accounts the admin allows to reelect timed out nodes on any bounty, on top of the bounty's owner
+    pub keepers: UnorderedSet<AccountId>,
     // pub gpu_nodes: UnorderedSet<AccountId>,
     // pub network_nodes: UnorderedSet<AccountId>,
     // pub gpu_and_network_nodes: UnorderedSet<AccountId>,
@@ -67,8 +210,25 @@ impl Default for Coordinator {
             universal_bounty_index: 0,
             total_completed_bounties: 0,
             total_payouts: 0,
+            gas_reimbursement: DEFAULT_GAS_REIMBURSEMENT,
+            admin_id: current_account_id(),
+            protocol_fee_bps: 0,
+            treasury_account_id: current_account_id(),
+            treasury_balance: 0,
+            total_fees: 0,
+            audit_rate_bps: 0,
+            audit_levy_bps: 0,
+            audit_pool: 0,
+            total_escrowed: 0,
+            event_nonce: 0,
+            audits: UnorderedMap::new("coordinator.audits".as_bytes()),
             node_by_owner: UnorderedMap::new("coordinator.node_by_owner".as_bytes()),
             bounty_by_owner: UnorderedMap::new("coordinator.bounty_by_owner".as_bytes()),
+            quotas: Quotas::default(),
+            owner_quotas: UnorderedMap::new("coordinator.owner_quotas".as_bytes()),
+            pools: UnorderedMap::new("coordinator.pools".as_bytes()),
+            blocklists: UnorderedMap::new("coordinator.blocklists".as_bytes()),
+            keepers: UnorderedSet::new("coordinator.keepers".as_bytes()),
         }
     }
 }
@@ -85,12 +245,29 @@ impl Coordinator {
             node_queue: Vec::new(),
             bounties: UnorderedMap::new("coordinator.bounties".as_bytes()),
             active_bounties: UnorderedSet::new("coordinator.active_bounties".as_bytes()),
-            offline_nodes: UnorderedMap::new("coordinator.bounties".as_bytes()),
+            offline_nodes: UnorderedMap::new("coordinator.offline_nodes".as_bytes()),
             universal_bounty_index: 0,
             total_completed_bounties: 0,
             total_payouts: 0,
+            gas_reimbursement: DEFAULT_GAS_REIMBURSEMENT,
+            admin_id: current_account_id(),
+            protocol_fee_bps: 0,
+            treasury_account_id: current_account_id(),
+            treasury_balance: 0,
+            total_fees: 0,
+            audit_rate_bps: 0,
+            audit_levy_bps: 0,
+            audit_pool: 0,
+            total_escrowed: 0,
+            event_nonce: 0,
+            audits: UnorderedMap::new("coordinator.audits".as_bytes()),
             node_by_owner: UnorderedMap::new("coordinator.node_by_owner".as_bytes()),
             bounty_by_owner: UnorderedMap::new("coordinator.bounty_by_owner".as_bytes()),
+            quotas: Quotas::default(),
+            owner_quotas: UnorderedMap::new("coordinator.owner_quotas".as_bytes()),
+            pools: UnorderedMap::new("coordinator.pools".as_bytes()),
+            blocklists: UnorderedMap::new("coordinator.blocklists".as_bytes()),
+            keepers: UnorderedSet::new("coordinator.keepers".as_bytes()),
         }
     }
 
@@ -127,6 +304,244 @@ impl Coordinator {
         return self.total_payouts;
     }
 
+    pub fn get_gas_reimbursement(&self) -> Balance {
+        return self.gas_reimbursement;
+    }
+
+    // Only applies to bounties created after the change, existing bounties keep the amount they were created with
+    pub fn set_gas_reimbursement(&mut self, amount: String) -> Balance {
+        self.assert_admin();
+        self.gas_reimbursement = amount.parse().unwrap();
+        log!("Gas reimbursement set to {}", self.gas_reimbursement);
+        return self.gas_reimbursement;
+    }
+
+    pub fn get_total_fees(&self) -> Balance {
+        return self.total_fees;
+    }
+
+    pub fn get_treasury_balance(&self) -> Balance {
+        return self.treasury_balance;
+    }
+
+    pub fn get_treasury_account(&self) -> AccountId {
+        return self.treasury_account_id.clone();
+    }
+
+    pub fn get_protocol_fee_bps(&self) -> u16 {
+        return self.protocol_fee_bps;
+    }
+
+    pub fn get_admin(&self) -> AccountId {
+        return self.admin_id.clone();
+    }
+
+    pub(crate) fn assert_admin(&self) {
+        require!(
+            signer_account_id() == self.admin_id,
+            "Only the coordinator admin can change protocol settings"
+        );
+    }
+
+    pub fn set_admin(&mut self, admin_id: AccountId) -> AccountId {
+        self.assert_admin();
+        log!("Transferring admin role from {} to {}", self.admin_id, admin_id);
+        self.admin_id = admin_id;
+        return self.admin_id.clone();
+    }
+
+    pub fn get_keepers(&self) -> Vec<AccountId> {
+        return self.keepers.to_vec();
+    }
+
+    pub fn add_keeper(&mut self, account_id: AccountId) -> Vec<AccountId> {
+        self.assert_admin();
+        log!("Adding keeper {}", account_id);
+        self.keepers.insert(&account_id);
+        return self.keepers.to_vec();
+    }
+
+    pub fn remove_keeper(&mut self, account_id: AccountId) -> Vec<AccountId> {
+        self.assert_admin();
+        require!(self.keepers.remove(&account_id), format!("{} is not a keeper", account_id));
+        log!("Removing keeper {}", account_id);
+        return self.keepers.to_vec();
+    }
+
+    // Like the gas reimbursement, the fee is fixed on each bounty when it's created
+    pub fn set_protocol_fee_bps(&mut self, fee_bps: u16) -> u16 {
+        self.assert_admin();
+        require!(
+            fee_bps <= MAX_PROTOCOL_FEE_BPS,
+            format!("Protocol fee cannot exceed {} basis points", MAX_PROTOCOL_FEE_BPS)
+        );
+        log!("Protocol fee set to {} basis points", fee_bps);
+        self.protocol_fee_bps = fee_bps;
+        return self.protocol_fee_bps;
+    }
+
+    pub fn set_treasury_account(&mut self, treasury_account_id: AccountId) -> AccountId {
+        self.assert_admin();
+        log!("Treasury withdrawals will now be sent to {}", treasury_account_id);
+        self.treasury_account_id = treasury_account_id;
+        return self.treasury_account_id.clone();
+    }
+
+    // Withdraws the given amount from the treasury, or everything if no amount is given
+    pub fn withdraw_treasury(&mut self, amount: Option<String>) -> Promise {
+        self.assert_admin();
+        let amount: Balance = amount.map(|a| a.parse().unwrap()).unwrap_or(self.treasury_balance);
+        require!(amount > 0, "Nothing to withdraw from the treasury");
+        require!(amount <= self.treasury_balance, "Cannot withdraw more than the treasury balance");
+        self.treasury_balance -= amount;
+        log!("Withdrawing {} from the treasury to {}", amount, self.treasury_account_id);
+        return Promise::new(self.treasury_account_id.clone()).transfer(amount);
+    }
+
+    pub fn get_audit_rate_bps(&self) -> u16 {
+        return self.audit_rate_bps;
+    }
+
+    pub fn get_audit_levy_bps(&self) -> u16 {
+        return self.audit_levy_bps;
+    }
+
+    pub fn get_audit_pool(&self) -> Balance {
+        return self.audit_pool;
+    }
+
+    pub fn set_audit_rate_bps(&mut self, rate_bps: u16) -> u16 {
+        self.assert_admin();
+        require!(rate_bps <= MAX_AUDIT_RATE_BPS, format!("Audit rate cannot exceed {} basis points", MAX_AUDIT_RATE_BPS));
+        log!("Audit rate set to {} basis points", rate_bps);
+        self.audit_rate_bps = rate_bps;
+        return self.audit_rate_bps;
+    }
+
+    // Like the protocol fee, the levy is fixed on each bounty when it's created
+    pub fn set_audit_levy_bps(&mut self, levy_bps: u16) -> u16 {
+        self.assert_admin();
+        require!(levy_bps <= MAX_AUDIT_LEVY_BPS, format!("Audit levy cannot exceed {} basis points", MAX_AUDIT_LEVY_BPS));
+        log!("Audit levy set to {} basis points", levy_bps);
+        self.audit_levy_bps = levy_bps;
+        return self.audit_levy_bps;
+    }
+
+    pub fn get_quotas(&self, account_id: Option<AccountId>) -> Quotas {
+        return account_id
+            .and_then(|account_id| self.owner_quotas.get(&account_id))
+            .unwrap_or_else(|| self.quotas.clone());
+    }
+
+    pub(crate) fn assert_valid_quotas(quotas: &Quotas) {
+        require!(
+            quotas.max_elected_share_bps > 0 && quotas.max_elected_share_bps <= 10_000,
+            "Elected share must be between 1 and 10000 basis points"
+        );
+    }
+
+    // Applies to every account the admin hasn't given its own quotas
+    pub fn set_quotas(&mut self, max_nodes_per_owner: u64, max_active_bounties_per_owner: u64, max_elected_share_bps: u16) -> Quotas {
+        self.assert_admin();
+        let quotas = Quotas { max_nodes_per_owner, max_active_bounties_per_owner, max_elected_share_bps };
+        Coordinator::assert_valid_quotas(&quotas);
+        log!("Default quotas set to {:?}", quotas);
+        self.quotas = quotas;
+        return self.quotas.clone();
+    }
+
+    // Gives an account its own quotas, or puts it back on the defaults if quotas is null
+    pub fn set_owner_quotas(&mut self, account_id: AccountId, quotas: Option<Quotas>) -> Quotas {
+        self.assert_admin();
+        match quotas {
+            Some(quotas) => {
+                Coordinator::assert_valid_quotas(&quotas);
+                log!("Quotas for {} set to {:?}", account_id, quotas);
+                self.owner_quotas.insert(&account_id, &quotas);
+            }
+            None => {
+                log!("{} is back on the default quotas", account_id);
+                self.owner_quotas.remove(&account_id);
+            }
+        }
+        return self.get_quotas(Some(account_id));
+    }
+
+    // Panics if owner_id can't have another active bounty. Dispute re-runs are listed under the owner, so they count too.
+    pub(crate) fn require_active_bounty_quota(&self, owner_id: &AccountId) {
+        let max_active_bounties = self.get_quotas(Some(owner_id.clone())).max_active_bounties_per_owner;
+        require!(
+            max_active_bounties == 0 || self.get_active_bounty_count_for_owner(owner_id.clone()) < max_active_bounties,
+            format!("{} already has the maximum of {} active bounties", owner_id, max_active_bounties)
+        );
+    }
+
+    pub fn get_active_bounty_count_for_owner(&self, owner_id: AccountId) -> u64 {
+        return self
+            .bounty_by_owner
+            .get(&owner_id)
+            .unwrap_or_default()
+            .iter()
+            .filter(|bounty_id| self.active_bounties.contains(bounty_id))
+            .count() as u64;
+    }
+
+    pub fn get_quota_usage(&self, account_id: AccountId) -> QuotaUsage {
+        return QuotaUsage {
+            quotas: self.get_quotas(Some(account_id.clone())),
+            overridden: self.owner_quotas.get(&account_id).is_some(),
+            nodes: self.get_nodes_for_owner_count(account_id.clone()) as u64,
+            active_bounties: self.get_active_bounty_count_for_owner(account_id.clone()),
+            account_id,
+        };
+    }
+
+    // The most of one bounty's elected_count nodes that can belong to owner_id
+    pub(crate) fn get_owner_election_cap(&self, owner_id: &AccountId, elected_count: u64) -> u64 {
+        let share_bps = self.get_quotas(Some(owner_id.clone())).max_elected_share_bps as u64;
+        return std::cmp::max(1, elected_count * share_bps / 10_000);
+    }
+
+    pub fn get_event_nonce(&self) -> u64 {
+        return self.event_nonce;
+    }
+
+    pub fn get_total_escrowed(&self) -> Balance {
+        return self.total_escrowed;
+    }
+
+    // Recomputes every bounty's escrow and checks it against the running total and the contract's balance
+    pub fn get_solvency_report(&self) -> SolvencyReport {
+        let escrowed: Balance = self.bounties.values().map(|bounty| bounty.escrow.remaining).sum();
+        let node_deposits: Balance = self.nodes.values().chain(self.offline_nodes.values()).map(|node| node.deposit).sum();
+        let contract_balance = account_balance();
+        return SolvencyReport {
+            escrowed: escrowed.into(),
+            total_escrowed: self.total_escrowed.into(),
+            treasury_balance: self.treasury_balance.into(),
+            audit_pool: self.audit_pool.into(),
+            node_deposits: node_deposits.into(),
+            contract_balance: contract_balance.into(),
+            ledger_consistent: escrowed == self.total_escrowed,
+            solvent: escrowed + self.treasury_balance + self.audit_pool + node_deposits <= contract_balance,
+        };
+    }
+
+    pub(crate) fn escrow_deposit(&mut self, bounty: &mut Bounty, amount: Balance) {
+        bounty.escrow.deposit(amount);
+        self.total_escrowed += amount;
+    }
+
+    pub(crate) fn escrow_pay_out(&mut self, bounty: &mut Bounty, amount: Balance) {
+        bounty.escrow.pay_out(amount);
+        self.total_escrowed -= amount;
+    }
+
+    pub(crate) fn escrow_refund(&mut self, bounty: &mut Bounty, amount: Balance) {
+        bounty.escrow.refund(amount);
+        self.total_escrowed -= amount;
+    }
+
     pub fn get_node(&self, node_id: AccountId) -> Node {
         log!("get_node {}", node_id);
         return self
@@ -182,6 +597,19 @@ impl Coordinator {
         return nodes;
     }
 
+    // Nodes moved offline with set_node_offline, these aren't returned by get_nodes_for_owner
+    pub fn get_offline_nodes_for_owner(&self, owner_id: AccountId) -> Vec<Node> {
+        let node_ids = self.node_by_owner.get(&owner_id);
+        if node_ids.is_none() {
+            return vec![];
+        }
+        return node_ids
+            .unwrap()
+            .iter()
+            .filter_map(|node_id| self.offline_nodes.get(node_id))
+            .collect();
+    }
+
     pub fn get_bounties(&self) -> Vec<Bounty> {
         log!("Fetching all {} bounties", self.bounties.len());
         let mut vec: Vec<Bounty> = vec![];
@@ -264,6 +692,11 @@ impl Coordinator {
             self.nodes.get(&node_id).is_none(),
             format!("Node already registered: {}", node_id.clone())
         );
+        let max_nodes = self.get_quotas(Some(signer_account_id())).max_nodes_per_owner;
+        require!(
+            max_nodes == 0 || (self.get_nodes_for_owner_count(signer_account_id()) as u64) < max_nodes,
+            format!("{} already has the maximum of {} nodes", signer_account_id(), max_nodes)
+        );
         let metadata = Node::new_node(node_id.clone(), absolute_timeout, allow_network, allow_gpu);
         self.nodes.insert(&node_id, &metadata);
         self.node_queue.push(node_id.clone());
@@ -275,6 +708,16 @@ impl Coordinator {
         self.node_by_owner
             .insert(&signer_account_id(), &owner_nodes);
         log!("finished adding node to coordinator, data: {}", metadata);
+        self.emit_event(EventLogVariant::NodeRegistered(NodeRegisteredLog {
+            coordinator_id: current_account_id(),
+            node_id: node_id.clone(),
+            owner_id: metadata.owner_id.clone(),
+            allow_network,
+            allow_gpu,
+            absolute_timeout,
+            deposit: metadata.deposit.into(),
+            message: None,
+        }));
 
         return self
             .nodes
@@ -307,6 +750,82 @@ impl Coordinator {
         node.allow_gpu = allow_gpu;
         node.absolute_timeout = absolute_timeout;
         self.nodes.insert(&node_id, &node);
+        self.emit_event(EventLogVariant::NodeUpdated(NodeUpdatedLog {
+            coordinator_id: current_account_id(),
+            node_id: node_id.clone(),
+            owner_id: node.owner_id.clone(),
+            allow_network,
+            allow_gpu,
+            absolute_timeout,
+            min_reward: Some(node.min_reward.into()),
+            min_reward_per_second: Some(node.min_reward_per_second.into()),
+            acceptance_rules: Some(node.acceptance_rules.clone()),
+            message: None,
+        }));
+        return node;
+    }
+
+    // Sets the least a node will run a bounty for, amounts are yoctoNEAR strings. Nodes are only elected to bounties whose
+    // reward per elected node covers min_reward + min_reward_per_second * timeout_seconds
+    pub fn set_node_price(&mut self, node_id: AccountId, min_reward: String, min_reward_per_second: String) -> Node {
+        let mut node = self
+            .nodes
+            .get(&node_id)
+            .unwrap_or_else(|| panic!("Node {} is not registered", node_id));
+        require!(
+            node.owner_id == signer_account_id() || signer_account_id() == current_account_id(),
+            "Only the owner or the coordinator can update a node"
+        );
+        node.min_reward = min_reward.parse().unwrap_or_else(|_| panic!("Invalid min_reward: {}", min_reward));
+        node.min_reward_per_second = min_reward_per_second
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid min_reward_per_second: {}", min_reward_per_second));
+        log!("Setting price of node {} to {} + {} per second", node_id, node.min_reward, node.min_reward_per_second);
+        self.nodes.insert(&node_id, &node);
+        self.emit_event(EventLogVariant::NodeUpdated(NodeUpdatedLog {
+            coordinator_id: current_account_id(),
+            node_id: node_id.clone(),
+            owner_id: node.owner_id.clone(),
+            allow_network: node.allow_network,
+            allow_gpu: node.allow_gpu,
+            absolute_timeout: node.absolute_timeout,
+            min_reward: Some(node.min_reward.into()),
+            min_reward_per_second: Some(node.min_reward_per_second.into()),
+            acceptance_rules: Some(node.acceptance_rules.clone()),
+            message: None,
+        }));
+        return node;
+    }
+
+    // Replaces the rules for which bounties a node takes on, the node is no longer elected for bounties they turn down
+    pub fn set_node_rules(&mut self, node_id: AccountId, rules: AcceptanceRules) -> Node {
+        let mut node = self
+            .nodes
+            .get(&node_id)
+            .unwrap_or_else(|| panic!("Node {} is not registered", node_id));
+        require!(
+            node.owner_id == signer_account_id() || signer_account_id() == current_account_id(),
+            "Only the owner or the coordinator can update a node"
+        );
+        require!(
+            rules.allowed_owners.len() + rules.denied_owners.len() <= MAX_BLOCKLIST_LEN,
+            format!("Acceptance rules cannot list more than {} owners", MAX_BLOCKLIST_LEN)
+        );
+        log!("Setting acceptance rules of node {} to {:?}", node_id, rules);
+        node.acceptance_rules = rules;
+        self.nodes.insert(&node_id, &node);
+        self.emit_event(EventLogVariant::NodeUpdated(NodeUpdatedLog {
+            coordinator_id: current_account_id(),
+            node_id: node_id.clone(),
+            owner_id: node.owner_id.clone(),
+            allow_network: node.allow_network,
+            allow_gpu: node.allow_gpu,
+            absolute_timeout: node.absolute_timeout,
+            min_reward: Some(node.min_reward.into()),
+            min_reward_per_second: Some(node.min_reward_per_second.into()),
+            acceptance_rules: Some(node.acceptance_rules.clone()),
+            message: None,
+        }));
         return node;
     }
 
@@ -333,13 +852,36 @@ impl Coordinator {
             self.node_by_owner.insert(&node.owner_id, &owner_nodes);
         }
 
+        //Take the node out of every pool it's in or invited to, the freed storage goes back to each pool's owner
+        let pools: Vec<Pool> = self.pools.values().filter(|pool| pool.is_member(&node_id) || pool.is_invited(&node_id)).collect();
+        for mut pool in pools {
+            let initial_storage = storage_usage();
+            pool.members.retain(|member| member != &node_id);
+            pool.invited.retain(|invited| invited != &node_id);
+            self.pools.insert(&pool.id, &pool);
+            self.emit_pool_membership_event(&pool.id, &node_id, PoolMembershipChange::Removed);
+            self.settle_storage(initial_storage, &pool.owner_id);
+        }
+
         //We do not remove the nodes from self.nodes, since this has data we need.
+        //Zero the deposit instead, so it can't be refunded twice
         log!(
             "removed node with id {}, refunding deposit of {}",
             node_id,
             node.deposit
         );
-        return Promise::new(node.owner_id).transfer(node.deposit);
+        let deposit = node.deposit;
+        let mut node = node;
+        node.deposit = 0;
+        self.nodes.insert(&node_id, &node);
+        self.emit_event(EventLogVariant::NodeRemoved(NodeRemovedLog {
+            coordinator_id: current_account_id(),
+            node_id: node_id.clone(),
+            owner_id: node.owner_id.clone(),
+            refunded: deposit.into(),
+            message: None,
+        }));
+        return Promise::new(node.owner_id).transfer(deposit);
     }
 
     // Only intended for developmment, should be removed before deploying to production
@@ -376,6 +918,13 @@ impl Coordinator {
             removed = self.offline_nodes.remove(&node_id).unwrap();
             self.nodes.insert(&node_id, &removed);
         }
+        self.emit_event(EventLogVariant::NodeStatusChanged(NodeStatusChangedLog {
+            coordinator_id: current_account_id(),
+            node_id: node_id.clone(),
+            owner_id: removed.owner_id.clone(),
+            online: !offline,
+            message: None,
+        }));
         return removed;
     }
 
@@ -383,32 +932,71 @@ impl Coordinator {
     #[private]
     pub(crate) fn rand_u64() -> u64 {
         //TODO Random seed may have security vulnerabilities. This is a risk we will likely have to take, but should read docs
-        let seeds = random_seed();
-        let mut seed: u64 = seeds[0] as u64;
-        for i in 1..(64 / 8) {
-            seed = seed * seeds[i] as u64;
-        }
-        return seed;
+        //Read 8 bytes of the seed as one number. Multiplying the bytes together skews the result towards 0 and small factors,
+        //which made rolls like the audit rate fire far more often than configured
+        return u64::from_le_bytes(random_seed()[..8].try_into().unwrap());
     }
 
+    // elected_count is how many nodes the bounty's reward will be split between once the election is done
     #[private]
-    pub(crate) fn node_qualified(node: &Node, bounty: &Bounty) -> bool {
-        if !node.allow_network && bounty.network_required {
+    pub(crate) fn node_qualified(node: &Node, bounty: &Bounty, elected_count: u64) -> bool {
+        return Coordinator::node_accepts(
+            node,
+            bounty.network_required,
+            bounty.gpu_required,
+            bounty.timeout_seconds,
+            bounty.amt_node_reward / elected_count.max(1) as u128,
+        ) && Coordinator::node_rules_allow(node, bounty);
+    }
+
+    pub(crate) fn node_rules_allow(node: &Node, bounty: &Bounty) -> bool {
+        if let Some(reason) = node.acceptance_rules.check(bounty) {
+            log!("Node {} {}, rejecting election", node.id, reason);
+            return false;
+        }
+        return true;
+    }
+
+    pub(crate) fn node_accepts(node: &Node, network_required: bool, gpu_required: bool, timeout_seconds: u64, reward_per_node: u128) -> bool {
+        if !node.allow_network && network_required {
             log!("Node {} does not allow network, but bounty requires it, rejecting election", node.id);
             return false;
         }
-        if !node.allow_gpu && bounty.gpu_required {
+        if !node.allow_gpu && gpu_required {
             log!("Node {} does not allow gpu, but bounty requires it, rejecting election", node.id);
             return false;
         }
         //TODO This conversion from seconds to milli is sloppy
-        if node.absolute_timeout < bounty.timeout_seconds * 1000 {
-            log!("Node {} has a timeout of {} which is less than the required timeout of {}", node.id, node.absolute_timeout, bounty.timeout_seconds);
+        if node.absolute_timeout < timeout_seconds * 1000 {
+            log!("Node {} has a timeout of {} which is less than the required timeout of {}", node.id, node.absolute_timeout, timeout_seconds);
+            return false;
+        }
+        if node.get_min_price(timeout_seconds) > reward_per_node {
+            log!("Node {} has a price of {} which is more than the reward per node of {}", node.id, node.get_min_price(timeout_seconds), reward_per_node);
             return false;
         }
         return true;
     }
 
+    // How many online nodes would accept a bounty with this reward and these requirements, assuming it elects
+    // get_node_padding(min_nodes) nodes like create_bounty does
+    pub fn get_accepting_node_count(
+        &self,
+        amt_node_reward: String,
+        min_nodes: u64,
+        timeout_seconds: u64,
+        network_required: bool,
+        gpu_required: bool,
+    ) -> u64 {
+        let amt_node_reward: u128 = amt_node_reward.parse().unwrap();
+        let reward_per_node = amt_node_reward / self.get_node_padding(min_nodes).max(1) as u128;
+        return self
+            .nodes
+            .values()
+            .filter(|node| Coordinator::node_accepts(node, network_required, gpu_required, timeout_seconds, reward_per_node))
+            .count() as u64;
+    }
+
     pub fn get_bounty_answer_counts(&self, bounty_id: AccountId) -> HashMap<String, u64> {
         let bounty = self
             .bounties
@@ -423,6 +1011,220 @@ impl Coordinator {
         map.insert("unanswered_nodes".to_string(), bounty.unanswered_nodes.len());
         return map;
     }
+    pub fn get_blocklist(&self, account_id: AccountId) -> Blocklist {
+        return self.blocklists.get(&account_id).unwrap_or_default();
+    }
+
+    // Blocks nodes and node owners from being elected for the signer's bounties, including ones already in flight when
+    // they reelect. The attached deposit pays for the storage, the rest is refunded.
+    #[payable]
+    pub fn add_to_blocklist(&mut self, node_ids: Option<Vec<AccountId>>, owner_ids: Option<Vec<AccountId>>) -> Blocklist {
+        let account_id = signer_account_id();
+        let mut blocklist = self.get_blocklist(account_id.clone());
+        for node_id in node_ids.unwrap_or_default() {
+            if !blocklist.node_ids.contains(&node_id) {
+                blocklist.node_ids.push(node_id);
+            }
+        }
+        for owner_id in owner_ids.unwrap_or_default() {
+            if !blocklist.owner_ids.contains(&owner_id) {
+                blocklist.owner_ids.push(owner_id);
+            }
+        }
+        require!(
+            blocklist.node_ids.len() + blocklist.owner_ids.len() <= MAX_BLOCKLIST_LEN,
+            format!("A blocklist cannot hold more than {} entries", MAX_BLOCKLIST_LEN)
+        );
+        let initial_storage = storage_usage();
+        self.blocklists.insert(&account_id, &blocklist);
+        log!("{} blocks {} nodes and {} owners", account_id, blocklist.node_ids.len(), blocklist.owner_ids.len());
+        self.settle_storage(initial_storage, &account_id);
+        return blocklist;
+    }
+
+    // Unblocks nodes and node owners, the storage this frees is refunded to the signer
+    pub fn remove_from_blocklist(&mut self, node_ids: Option<Vec<AccountId>>, owner_ids: Option<Vec<AccountId>>) -> Blocklist {
+        let account_id = signer_account_id();
+        let mut blocklist = self.get_blocklist(account_id.clone());
+        let node_ids = node_ids.unwrap_or_default();
+        let owner_ids = owner_ids.unwrap_or_default();
+        blocklist.node_ids.retain(|node_id| !node_ids.contains(node_id));
+        blocklist.owner_ids.retain(|owner_id| !owner_ids.contains(owner_id));
+        let initial_storage = storage_usage();
+        if blocklist == Blocklist::default() {
+            self.blocklists.remove(&account_id);
+        } else {
+            self.blocklists.insert(&account_id, &blocklist);
+        }
+        log!("{} blocks {} nodes and {} owners", account_id, blocklist.node_ids.len(), blocklist.owner_ids.len());
+        self.settle_storage(initial_storage, &account_id);
+        return blocklist;
+    }
+
+    pub fn get_pool(&self, pool_id: String) -> Pool {
+        return self.pools.get(&pool_id).unwrap_or_else(|| panic!("Pool {} does not exist", pool_id));
+    }
+
+    pub fn get_pools_for_node(&self, node_id: AccountId) -> Vec<Pool> {
+        return self.pools.values().filter(|pool| pool.is_member(&node_id) || pool.is_invited(&node_id)).collect();
+    }
+
+    pub fn get_pool_stats(&self, pool_id: String) -> PoolStats {
+        let pool = self.get_pool(pool_id.clone());
+        let active_bounties = self
+            .active_bounties
+            .iter()
+            .filter_map(|bounty_id| self.bounties.get(&bounty_id))
+            .filter(|bounty| bounty.pool_id.as_ref() == Some(&pool_id))
+            .count() as u64;
+        return PoolStats {
+            pool_id,
+            owner_id: pool.owner_id.clone(),
+            members: pool.members.len() as u64,
+            online_members: self.get_pool_candidates(&pool.id).len() as u64,
+            invited: pool.invited.len() as u64,
+            total_bounties: pool.total_bounties,
+            active_bounties,
+        };
+    }
+
+    // Members of the pool that are registered and online, the nodes a bounty against the pool can be elected from
+    pub(crate) fn get_pool_candidates(&self, pool_id: &str) -> Vec<AccountId> {
+        return self
+            .get_pool(pool_id.to_string())
+            .members
+            .into_iter()
+            .filter(|node_id| self.nodes.get(node_id).is_some() && self.is_node_registered(node_id))
+            .collect();
+    }
+
+    // Removed nodes stay in self.nodes so their history can still be read, but they're dropped from their owner's nodes
+    pub(crate) fn is_node_registered(&self, node_id: &AccountId) -> bool {
+        return self
+            .nodes
+            .get(node_id)
+            .or_else(|| self.offline_nodes.get(node_id))
+            .is_some_and(|node| self.node_by_owner.get(&node.owner_id).is_some_and(|node_ids| node_ids.contains(node_id)));
+    }
+
+    // Charges the storage written since initial_storage to the attached deposit and refunds what's left to the signer.
+    // If storage was freed instead, what it cost goes back to payer_id, who paid for it.
+    pub(crate) fn settle_storage(&self, initial_storage: u64, payer_id: &AccountId) {
+        let final_storage = storage_usage();
+        let mut refund = attached_deposit();
+        if final_storage > initial_storage {
+            let amt_storage = (final_storage - initial_storage) as u128 * env::storage_byte_cost();
+            require!(refund >= amt_storage, format!("Attached deposit must cover {} yoctoNEAR of storage", amt_storage));
+            refund -= amt_storage;
+        } else if initial_storage > final_storage {
+            Promise::new(payer_id.clone()).transfer((initial_storage - final_storage) as u128 * env::storage_byte_cost());
+        }
+        if refund > 0 {
+            Promise::new(signer_account_id()).transfer(refund);
+        }
+    }
+
+    pub(crate) fn emit_pool_membership_event(&mut self, pool_id: &str, node_id: &AccountId, change: PoolMembershipChange) {
+        let owner_id = self
+            .nodes
+            .get(node_id)
+            .or_else(|| self.offline_nodes.get(node_id))
+            .map(|node| node.owner_id)
+            .unwrap_or_else(signer_account_id);
+        log!("Node {} in pool {}: {}", node_id, pool_id, change);
+        self.emit_event(EventLogVariant::PoolMembershipChanged(PoolMembershipChangedLog {
+            coordinator_id: current_account_id(),
+            pool_id: pool_id.to_string(),
+            node_id: node_id.clone(),
+            owner_id,
+            change,
+            message: None,
+        }));
+    }
+
+    // Creates an empty pool owned by the signer. The attached deposit pays for the pool's storage, the rest is refunded.
+    #[payable]
+    pub fn create_pool(&mut self, pool_id: String) -> Pool {
+        require!(
+            Pool::is_valid_id(&pool_id),
+            format!("Pool ids must be 1 to {} letters, digits, dashes or underscores", crate::pool::MAX_POOL_ID_LEN)
+        );
+        require!(self.pools.get(&pool_id).is_none(), format!("Pool {} already exists", pool_id));
+        let initial_storage = storage_usage();
+        let pool = Pool::new_pool(pool_id.clone(), signer_account_id(), block_timestamp_ms());
+        self.pools.insert(&pool_id, &pool);
+        log!("Created pool {} for {}", pool_id, pool.owner_id);
+        self.emit_event(EventLogVariant::PoolCreated(PoolCreatedLog {
+            coordinator_id: current_account_id(),
+            pool_id,
+            owner_id: pool.owner_id.clone(),
+            message: None,
+        }));
+        self.settle_storage(initial_storage, &pool.owner_id);
+        return pool;
+    }
+
+    // Invites a node to the signer's pool. The node only becomes a member once its owner accepts.
+    #[payable]
+    pub fn invite_to_pool(&mut self, pool_id: String, node_id: AccountId) -> Pool {
+        let mut pool = self.get_pool(pool_id.clone());
+        require!(pool.owner_id == signer_account_id(), "Only the pool owner can invite nodes");
+        require!(self.is_node_registered(&node_id), format!("Node {} is not registered", node_id));
+        require!(
+            !pool.is_member(&node_id) && !pool.is_invited(&node_id),
+            format!("Node {} is already a member of or invited to pool {}", node_id, pool_id)
+        );
+        let initial_storage = storage_usage();
+        pool.invited.push(node_id.clone());
+        self.pools.insert(&pool_id, &pool);
+        self.emit_pool_membership_event(&pool_id, &node_id, PoolMembershipChange::Invited);
+        self.settle_storage(initial_storage, &pool.owner_id);
+        return pool;
+    }
+
+    // Called by a node's owner to accept an invite to a pool
+    pub fn join_pool(&mut self, pool_id: String, node_id: AccountId) -> Pool {
+        let mut pool = self.get_pool(pool_id.clone());
+        let node = self
+            .nodes
+            .get(&node_id)
+            .or_else(|| self.offline_nodes.get(&node_id))
+            .unwrap_or_else(|| panic!("Node {} is not registered", node_id));
+        require!(self.is_node_registered(&node_id), format!("Node {} is not registered", node_id));
+        require!(node.owner_id == signer_account_id(), "Only the node owner can accept an invite");
+        require!(pool.is_invited(&node_id), format!("Node {} has not been invited to pool {}", node_id, pool_id));
+        pool.invited.retain(|invited| invited != &node_id);
+        pool.members.push(node_id.clone());
+        self.pools.insert(&pool_id, &pool);
+        self.emit_pool_membership_event(&pool_id, &node_id, PoolMembershipChange::Joined);
+        return pool;
+    }
+
+    // Takes a node out of a pool, or cancels its invite. The pool owner can remove any node, a node's owner can only
+    // remove their own. The storage this frees is refunded to the pool owner.
+    pub fn leave_pool(&mut self, pool_id: String, node_id: AccountId) -> Pool {
+        let mut pool = self.get_pool(pool_id.clone());
+        require!(
+            pool.is_member(&node_id) || pool.is_invited(&node_id),
+            format!("Node {} is not a member of or invited to pool {}", node_id, pool_id)
+        );
+        let node_owner_id = self.nodes.get(&node_id).or_else(|| self.offline_nodes.get(&node_id)).map(|node| node.owner_id);
+        let change = if pool.owner_id == signer_account_id() {
+            PoolMembershipChange::Removed
+        } else if node_owner_id == Some(signer_account_id()) {
+            PoolMembershipChange::Left
+        } else {
+            panic!("Only the pool owner or the node owner can take a node out of a pool");
+        };
+        let initial_storage = storage_usage();
+        pool.members.retain(|member| member != &node_id);
+        pool.invited.retain(|invited| invited != &node_id);
+        self.pools.insert(&pool_id, &pool);
+        self.emit_pool_membership_event(&pool_id, &node_id, change);
+        self.settle_storage(initial_storage, &pool.owner_id);
+        return pool;
+    }
+
     #[private]
     pub fn get_node_padding(&self, min_nodes: u64) -> u64 {
            return (min_nodes as f64 * NODE_PADDING).ceil() as u64;
@@ -438,14 +1240,21 @@ impl Coordinator {
         gpu_required: bool,
         amt_storage: String,
         amt_node_reward: String,
+        options: Option<BountyOptions>,
     ) -> Bounty {
         let amt_storage: u128 = amt_storage.parse().unwrap();
         let amt_node_reward: u128 = amt_node_reward.parse().unwrap();
+        let options = options.unwrap_or_default();
+        let bidding_window_seconds = options.get_bidding_window_seconds();
+        let reward_policy = options.get_reward_policy();
+        let result_type = options.get_result_type();
+        let aggregation = options.get_aggregation();
         let total_nodes = self.get_node_padding(min_nodes);
         require!(
             attached_deposit() == amt_storage + amt_node_reward,
             "Attached deposit must be equal to the sum of the storage and node reward amounts"
         );
+        self.require_active_bounty_quota(&signer_account_id());
         require!(
             amt_storage >= MIN_STORAGE,
             "Refundable storage deposit must be at least 0.1N"
@@ -463,20 +1272,42 @@ impl Coordinator {
             total_nodes.clone() <= self.nodes.len(),
             "Total nodes cannot be greater than the number of nodes available in the coordinator"
         );
-        // Truncate the block timestamp to reduce the overall length of the bounty id
-        let bounty_key: AccountId = format!(
-            "{}-{}.bounty.{}",
-            self.universal_bounty_index,
-            (block_timestamp() % 1000000000),
-            signer_account_id()
-        )
-            .parse()
-            .unwrap();
-        log!("Bounty id is: {}", bounty_key);
+        let amt_gas_reimbursement = self.gas_reimbursement;
         require!(
-            self.bounties.get(&bounty_key).is_none(),
-            "Bounty already exists"
+            amt_gas_reimbursement * (total_nodes as u128) < amt_node_reward,
+            format!("Node reward must be greater than the gas reimbursement reserved for every elected node ({} x {})", amt_gas_reimbursement, total_nodes)
         );
+        match &reward_policy {
+            RewardPolicy::EqualSplit => {}
+            RewardPolicy::FirstN { n } => require!(*n > 0, "FirstN reward policy must pay at least one node"),
+            RewardPolicy::LatencyWeighted { bonus_bps } => require!(*bonus_bps <= 10_000, "Latency bonus cannot exceed 10000 basis points"),
+            RewardPolicy::FixedPerNode { price } => require!(
+                price.0 * (total_nodes as u128) <= amt_node_reward - amt_gas_reimbursement * (total_nodes as u128),
+                "Node reward must cover the fixed price for every elected node after gas reimbursements"
+            ),
+            RewardPolicy::Bid => require!(bidding_window_seconds > 0, "Bid reward policy can only be used by auctions"),
+        }
+        require!(
+            bidding_window_seconds == 0 || reward_policy == RewardPolicy::Bid,
+            "Auctions must use the Bid reward policy"
+        );
+        require!(result_type.is_valid(), "Decimal tolerance must be a non-negative number");
+        require!(
+            aggregation.supports(&result_type),
+            format!("Aggregation {} can't be used with result type {}", aggregation, result_type)
+        );
+        require!(
+            options.get_excluded_nodes().len() <= MAX_BLOCKLIST_LEN,
+            format!("A bounty cannot exclude more than {} nodes", MAX_BLOCKLIST_LEN)
+        );
+        if let Some(pool_id) = &options.pool_id {
+            let pool = self.get_pool(pool_id.clone());
+            require!(
+                pool.owner_id == signer_account_id(),
+                format!("Only the owner of pool {} can create bounties against it", pool_id)
+            );
+        }
+        let bounty_key = self.next_bounty_id(&signer_account_id());
         let mut bounty = Bounty::new_bounty(
             bounty_key.clone(),
             file_location,
@@ -487,101 +1318,413 @@ impl Coordinator {
             gpu_required,
             amt_storage,
             amt_node_reward,
+            amt_gas_reimbursement,
+            self.protocol_fee_bps,
+            self.audit_levy_bps,
+            options,
         );
         require!(
             bounty.owner_id == signer_account_id(),
             "The bounty's owner id must be the signer"
         ); //Cautionary check. We don't want to risk preventing the creator from cancelling the bounty to withdraw their funds
 
+        self.publish_bounty(&mut bounty, total_nodes, &vec![]);
+        return bounty;
+    }
 
-        bounty.elected_nodes = self.elect_nodes(&bounty, total_nodes as usize);
-        let mut owner_bounties = self
-            .bounty_by_owner
-            .get(&signer_account_id())
-            .unwrap_or_else(|| vec![]);
+    pub(crate) fn next_bounty_id(&self, owner_id: &AccountId) -> AccountId {
+        // Truncate the block timestamp to reduce the overall length of the bounty id
+        let bounty_key: AccountId = format!(
+            "{}-{}.bounty.{}",
+            self.universal_bounty_index,
+            (block_timestamp() % 1000000000),
+            owner_id
+        )
+            .parse()
+            .unwrap();
+        log!("Bounty id is: {}", bounty_key);
+        require!(
+            self.bounties.get(&bounty_key).is_none(),
+            "Bounty already exists"
+        );
+        return bounty_key;
+    }
+
+    // Elects nodes for a freshly built bounty, stores it, and lets the elected nodes know there's work to do
+    pub(crate) fn publish_bounty(&mut self, bounty: &mut Bounty, total_nodes: u64, excluded_nodes: &Vec<AccountId>) {
+        let bounty_key = bounty.id.clone();
+        let initial_storage = storage_usage();
+        self.total_escrowed += bounty.escrow.remaining;
+        if let Some(pool_id) = &bounty.pool_id {
+            let mut pool = self.get_pool(pool_id.clone());
+            pool.total_bounties += 1;
+            self.pools.insert(pool_id, &pool);
+        }
+        if bounty.is_auction() {
+            log!("Bounty {} is an auction, nodes will be elected when bidding closes", bounty_key);
+        } else if bounty.elected_nodes.is_empty() {
+            bounty.elected_nodes = self.elect_nodes(&bounty, total_nodes as usize, excluded_nodes);
+        }
+        // Audits are listed under the coordinator, so they don't show up in the owner's bounties or use up its quota
+        let index_owner_id = match self.audits.get(&bounty_key) {
+            Some(_) => current_account_id(),
+            None => bounty.owner_id.clone(),
+        };
+        let mut owner_bounties = self
+            .bounty_by_owner
+            .get(&index_owner_id)
+            .unwrap_or_else(|| vec![]);
         owner_bounties.push(bounty.id.clone());
         self.universal_bounty_index += 1;
         self.bounty_by_owner
-            .insert(&signer_account_id(), &owner_bounties);
+            .insert(&index_owner_id, &owner_bounties);
         self.bounties.insert(&bounty_key, &bounty);
         self.active_bounties.insert(&bounty_key);
+        bounty.storage_used = storage_usage() - initial_storage;
+        require!(bounty.get_amt_storage_used() <= bounty.amt_storage, "Not enough storage deposit to store the bounty");
+
+        self.emit_bounty_event(bounty, EventLogVariant::BountyCreated(BountyCreatedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_key.clone(),
+            node_ids: bounty.elected_nodes.clone(),
+            owner_id: bounty.owner_id.clone(),
+            file_location: bounty.file_location.clone(),
+            file_download_protocol: bounty.file_download_protocol.clone(),
+            min_nodes: bounty.min_nodes,
+            timeout_seconds: bounty.timeout_seconds,
+            network_required: bounty.network_required,
+            gpu_required: bounty.gpu_required,
+            amt_storage: bounty.amt_storage.into(),
+            amt_node_reward: bounty.amt_node_reward.into(),
+            reward_policy: bounty.reward_policy.clone(),
+            bidding_ends_at: if bounty.is_auction() { Some(bounty.bidding_ends_at) } else { None },
+            message: None,
+        }));
+        self.bounties.insert(&bounty_key, &bounty);
+    }
+
+    pub(crate) fn node_reputable(node: &Node) -> bool {
+        return node.get_success_rate_bps().is_none_or(|rate| rate >= MIN_BID_SUCCESS_RATE_BPS);
+    }
+
+    // Bids on an auctioned bounty while bidding is open, bidding again replaces the node's previous bid.
+    // Bids are kept on the bounty, so they're paid for out of its storage deposit.
+    pub fn bid_on_bounty(&mut self, bounty_id: AccountId, node_id: AccountId, price: String) -> Bid {
+        let mut bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
+        let node = self.nodes.get(&node_id).unwrap_or_else(|| panic!("Node {} is not registered", node_id));
+        require!(node.owner_id == signer_account_id(), "Only the node owner can bid for it");
+        require!(
+            bounty.is_bidding() && block_timestamp_ms() < bounty.bidding_ends_at,
+            "Bounty is not taking bids"
+        );
+        let price: u128 = price.parse().unwrap_or_else(|_| panic!("Invalid price: {}", price));
+        let amt_max_bid = bounty.get_amt_max_bid(self.get_node_padding(bounty.min_nodes));
+        require!(
+            price <= amt_max_bid,
+            format!("Bid cannot be more than {}, the node reward per elected node after gas reimbursements", amt_max_bid)
+        );
+        require!(
+            Coordinator::node_accepts(&node, bounty.network_required, bounty.gpu_required, bounty.timeout_seconds, price),
+            format!("Node {} does not qualify for the bounty at this price", node_id)
+        );
+        require!(Coordinator::node_reputable(&node), format!("Node {} has too many unsuccessful runs to bid", node_id));
+        if let Some(reason) = node.acceptance_rules.check(&bounty) {
+            panic!("Node {} {}, it cannot bid on bounty {}", node_id, reason, bounty_id);
+        }
+        if let Some(pool_id) = &bounty.pool_id {
+            require!(self.get_pool(pool_id.clone()).is_member(&node_id), format!("Node {} is not a member of pool {}", node_id, pool_id));
+        }
+        let blocklist = self.blocklists.get(&bounty.owner_id).unwrap_or_default();
+        require!(
+            !Coordinator::node_excluded(&node, &bounty, &[], &blocklist),
+            format!("The owner of bounty {} has excluded node {}", bounty_id, node_id)
+        );
+
+        let initial_storage = storage_usage();
+        let bid = Bid { node_id: node_id.clone(), price: price.into(), timestamp: block_timestamp_ms() };
+        bounty.bids.retain(|existing| existing.node_id != node_id);
+        bounty.bids.push(bid.clone());
+        self.bounties.insert(&bounty_id, &bounty);
+        bounty.storage_used = (bounty.storage_used + storage_usage()).saturating_sub(initial_storage);
+        require!(bounty.get_amt_storage_used() <= bounty.amt_storage, "Not enough storage left to store bid");
+        log!("Node {} bid {} on bounty {}", node_id, price, bounty_id);
+        self.emit_bounty_event(&mut bounty, EventLogVariant::BountyBidPlaced(BountyBidPlacedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            node_id,
+            owner_id: node.owner_id,
+            price: price.into(),
+            message: None,
+        }));
+        self.bounties.insert(&bounty_id, &bounty);
+        return bid;
+    }
+
+    // Anyone can close bidding once the window has passed. The cheapest bids from nodes that are still online, qualified
+    // and reputable are elected, up to the padded node count, and the reward they don't use is refunded to the owner.
+    // If fewer than min_nodes bids are left, the bounty is cancelled instead.
+    pub fn award_bounty(&mut self, bounty_id: AccountId) -> Promise {
+        let mut bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
+        require!(bounty.is_bidding(), "Bounty is not an open auction");
+        require!(block_timestamp_ms() >= bounty.bidding_ends_at, "Bidding is still open");
+        let total_nodes = self.get_node_padding(bounty.min_nodes) as usize;
+        let blocklist = self.blocklists.get(&bounty.owner_id).unwrap_or_default();
+        let mut candidates: Vec<(Bid, u64)> = vec![];
+        for bid in bounty.bids.iter() {
+            let node = match self.nodes.get(&bid.node_id) {
+                Some(node) => node,
+                None => {
+                    log!("Node {} is offline or removed, dropping its bid", bid.node_id);
+                    continue;
+                }
+            };
+            if !Coordinator::node_accepts(&node, bounty.network_required, bounty.gpu_required, bounty.timeout_seconds, bid.price.0)
+                || !Coordinator::node_reputable(&node)
+                || !Coordinator::node_rules_allow(&node, &bounty)
+                || bounty.pool_id.as_ref().is_some_and(|pool_id| !self.get_pool(pool_id.clone()).is_member(&bid.node_id))
+                || Coordinator::node_excluded(&node, &bounty, &[], &blocklist)
+            {
+                log!("Node {} no longer qualifies, dropping its bid", bid.node_id);
+                continue;
+            }
+            candidates.push((bid.clone(), node.get_success_rate_bps().unwrap_or(0)));
+        }
+        // Cheapest first, ties go to the more reliable node, then to whoever bid first
+        candidates.sort_by(|(a, a_rate), (b, b_rate)| {
+            a.price.0.cmp(&b.price.0).then(b_rate.cmp(a_rate)).then(a.timestamp.cmp(&b.timestamp))
+        });
+        let mut elected_by_owner: HashMap<AccountId, u64> = HashMap::new();
+        let mut winning_bids: Vec<Bid> = vec![];
+        let mut owner_limited = false;
+        for (bid, _) in candidates {
+            if winning_bids.len() == total_nodes {
+                break;
+            }
+            if !self.owner_under_election_cap(&bid.node_id, &bounty, &elected_by_owner, total_nodes as u64) {
+                log!("The owner of node {} has its share of bounty {}, dropping its bid", bid.node_id, bounty_id);
+                owner_limited = true;
+                continue;
+            }
+            *elected_by_owner.entry(self.nodes.get(&bid.node_id).unwrap().owner_id).or_insert(0) += 1;
+            winning_bids.push(bid);
+        }
+
+        if (winning_bids.len() as u64) < bounty.min_nodes {
+            log!("Bounty {} only has {} usable bids for {} nodes, cancelling", bounty_id, winning_bids.len(), bounty.min_nodes);
+            let event = EventLogVariant::BountyAwarded(BountyAwardedLog {
+                coordinator_id: current_account_id(),
+                bounty_id: bounty_id.clone(),
+                node_ids: vec![],
+                winning_bids: vec![],
+                amt_node_reward: bounty.amt_node_reward.into(),
+                amt_refund: 0.into(),
+                message: Some(if owner_limited {
+                    format!("Not enough bids from different owners, {} of {} nodes", winning_bids.len(), bounty.min_nodes)
+                } else {
+                    format!("Not enough bids, {} of {} nodes", winning_bids.len(), bounty.min_nodes)
+                }),
+            });
+            self.emit_bounty_event(&mut bounty, event);
+            let refund = self.close_bounty(&mut bounty, true).expect("Auctions are never audits, so their owner is always refunded");
+            self.bounties.insert(&bounty_id, &bounty);
+            return refund;
+        }
+
+        let amt_node_reward: Balance = winning_bids.iter().map(|bid| bid.price.0).sum::<Balance>()
+            + bounty.amt_gas_reimbursement * winning_bids.len() as u128;
+        let amt_refund = bounty.amt_node_reward - amt_node_reward;
+        bounty.amt_node_reward = amt_node_reward;
+        bounty.elected_nodes = winning_bids.iter().map(|bid| bid.node_id.clone()).collect();
+        bounty.elected_at = block_timestamp_ms();
+        self.escrow_refund(&mut bounty, amt_refund);
+        log!("Awarded bounty {} to {} nodes, refunding {} to {}", bounty_id, winning_bids.len(), amt_refund, bounty.owner_id);
+        let event = EventLogVariant::BountyAwarded(BountyAwardedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            node_ids: bounty.elected_nodes.clone(),
+            winning_bids,
+            amt_node_reward: amt_node_reward.into(),
+            amt_refund: amt_refund.into(),
+            message: None,
+        });
+        self.emit_bounty_event(&mut bounty, event);
+        self.bounties.insert(&bounty_id, &bounty);
+        return Promise::new(bounty.owner_id.clone()).transfer(amt_refund);
+    }
 
-        let bounty_created_log: EventLog = EventLog {
+    // Wraps the event in our standard and writes it to the logs, where indexers and nodes pick it up
+    pub(crate) fn emit_event(&mut self, event: EventLogVariant) {
+        self.write_event(None, event);
+    }
+
+    // Same as emit_event, but also bumps the bounty's event_seq. The caller must reinsert the bounty afterwards.
+    pub(crate) fn emit_bounty_event(&mut self, bounty: &mut Bounty, event: EventLogVariant) {
+        bounty.event_seq += 1;
+        self.write_event(Some(bounty.event_seq), event);
+    }
+
+    fn write_event(&mut self, event_seq: Option<u64>, event: EventLogVariant) {
+        self.event_nonce += 1;
+        let event_log = EventLog {
             standard: EVENT_STANDARD_NAME.to_string(),
             version: EVENT_STANDARD_SPEC.to_string(),
-            event: EventLogVariant::BountyCreated(BountyCreatedLog {
-                coordinator_id: current_account_id(),
-                bounty_id: bounty_key.clone(),
-                node_ids: bounty.elected_nodes.clone(),
-                message: None,
-            }),
+            event_nonce: self.event_nonce,
+            event_seq,
+            event,
         };
-
-        log_str(&bounty_created_log.to_string());
-        return bounty;
+        log_str(&event_log.to_string());
     }
 
+    // excluded_nodes are never elected, even if they're qualified
     #[private]
-    pub fn elect_nodes(&mut self, bounty: &Bounty, total_elections: usize) -> Vec<AccountId>{
+    pub fn elect_nodes(&mut self, bounty: &Bounty, total_elections: usize, excluded_nodes: &Vec<AccountId>) -> Vec<AccountId>{
+        return match self.try_elect_nodes(bounty, total_elections, excluded_nodes) {
+            Ok(elected_nodes) => elected_nodes,
+            Err(ElectionShortfall::Owners) => Coordinator::panic_not_enough_owners(bounty),
+            Err(ElectionShortfall::Nodes) => Coordinator::panic_not_enough_nodes(bounty),
+        };
+    }
+
+    // Same as elect_nodes, but leaves the queue as it was and returns why instead of panicking when it can't elect
+    // total_elections nodes
+    pub(crate) fn try_elect_nodes(&mut self, bounty: &Bounty, total_elections: usize, excluded_nodes: &[AccountId]) -> Result<Vec<AccountId>, ElectionShortfall> {
         let mut unelected_nodes: Vec<AccountId> = vec![];
         let mut elected_nodes: Vec<AccountId> = vec![];
+        let elected_count = (bounty.elected_nodes.len() + total_elections) as u64;
+        let mut elected_by_owner: HashMap<AccountId, u64> = HashMap::new();
+        for node_id in bounty.elected_nodes.iter() {
+            if let Some(node) = self.nodes.get(node_id) {
+                *elected_by_owner.entry(node.owner_id).or_insert(0) += 1;
+            }
+        }
+        let blocklist = self.blocklists.get(&bounty.owner_id).unwrap_or_default();
+        //Set once a qualified node is skipped because its owner already has enough nodes on the bounty
+        let mut owner_limited = false;
+        //Pool bounties sample from the pool's online members, everything else from the public queue
+        let mut queue = match &bounty.pool_id {
+            Some(pool_id) => self.get_pool_candidates(pool_id),
+            None => std::mem::take(&mut self.node_queue),
+        };
+        let mut shortfall: Option<ElectionShortfall> = None;
         while elected_nodes.len() < total_elections {
             let key: AccountId;
-            if self.node_queue.len() == 1 {
-                key = self.node_queue.pop().unwrap();
-                if !Coordinator::node_qualified(&self.nodes.get(&key).unwrap(), &bounty) {
-                    log!("Node {} is not qualified for bounty {}. Since this is the only node left to elect, panic", key, bounty.id);
-                    panic!("Not enough qualified nodes to fill bounty");
+            if queue.is_empty() {
+                log!("Ran out of nodes after electing {} of {} for bounty {}", elected_nodes.len(), total_elections, bounty.id);
+                shortfall = Some(if owner_limited { ElectionShortfall::Owners } else { ElectionShortfall::Nodes });
+                break;
+            } else if queue.len() == 1 {
+                key = queue.pop().unwrap();
+                let node = self.nodes.get(&key).unwrap();
+                if Coordinator::node_excluded(&node, bounty, excluded_nodes, &blocklist) || !Coordinator::node_qualified(&node, &bounty, elected_count) {
+                    log!("Node {} is not qualified for bounty {}. Since this is the only node left to elect, give up", key, bounty.id);
+                    shortfall = Some(if owner_limited { ElectionShortfall::Owners } else { ElectionShortfall::Nodes });
+                    unelected_nodes.push(key);
+                    break;
+                }
+                if !self.owner_under_election_cap(&key, bounty, &elected_by_owner, elected_count) {
+                    log!("The owner of node {} has its share of bounty {}. Since this is the only node left to elect, give up", key, bounty.id);
+                    shortfall = Some(ElectionShortfall::Owners);
+                    unelected_nodes.push(key);
+                    break;
                 }
                 log!("elected {} (only node in queue)", key);
             } else {
                 let seed = Coordinator::rand_u64();
-                let random_node = seed as usize % self.node_queue.len();
+                let random_node = seed as usize % queue.len();
                 log!(
                     "electing node at: {}, (seed: {}, index: {}, node_queue_len: {}, supposed_node: {})",
                     random_node,
                     seed as usize,
                     random_node,
-                    self.node_queue.len() as u64,
-                    seed as usize % self.node_queue.len()
+                    queue.len() as u64,
+                    seed as usize % queue.len()
                 );
                 // let key = self.node_queue.swap_remove(random_node); // O(1) by replacing removed with last element
                 // Remove node to eliminate possibility of collisions
-                key = self.node_queue.swap_remove(random_node as usize);
-                if !Coordinator::node_qualified(&self.nodes.get(&key).unwrap(), &bounty) {
+                key = queue.swap_remove(random_node as usize);
+                let node = self.nodes.get(&key).unwrap();
+                if Coordinator::node_excluded(&node, bounty, excluded_nodes, &blocklist) {
+                    log!("Node {} is excluded from bounty {}. Skipping", key, bounty.id);
+                    unelected_nodes.push(key);
+                    continue;
+                }
+                if !Coordinator::node_qualified(&node, &bounty, elected_count) {
                     log!("Node {} is not qualified for bounty {}. Skipping", key, bounty.id);
                     unelected_nodes.push(key);
                     continue;
                 }
+                if !self.owner_under_election_cap(&key, bounty, &elected_by_owner, elected_count) {
+                    log!("The owner of node {} has its share of bounty {}. Skipping", key, bounty.id);
+                    owner_limited = true;
+                    unelected_nodes.push(key);
+                    continue;
+                }
             }
             require!(!bounty.elected_nodes.contains(&key), "Node already elected");
+            *elected_by_owner.entry(self.nodes.get(&key).unwrap().owner_id).or_insert(0) += 1;
             elected_nodes.push(key.clone());
         }
 
         for node in &elected_nodes {
-            self.node_queue.push(node.clone());
             log!("Elected node: {}", node);
         }
-        for node in unelected_nodes {
-            log!("Restoring unelected node: {}", node);
-            self.node_queue.push(node.clone());
+        if bounty.pool_id.is_none() {
+            for node in &elected_nodes {
+                queue.push(node.clone());
+            }
+            for node in unelected_nodes {
+                log!("Restoring unelected node: {}", node);
+                queue.push(node.clone());
+            }
+            self.node_queue = queue;
         }
-        return elected_nodes;
+        if let Some(shortfall) = shortfall {
+            return Err(shortfall);
+        }
+        return Ok(elected_nodes);
     }
-    #[private]
-    pub fn send_retry_event(&self, bounty_id: &AccountId, nodes: &Vec<AccountId>) {
-        require!(self.bounties.get(&bounty_id).is_some(), "Bounty does not exist");
-        let bounty_request_retry_log: EventLog = EventLog {
-            standard: EVENT_STANDARD_NAME.to_string(),
-            version: EVENT_STANDARD_SPEC.to_string(),
-            event: EventLogVariant::BountyRetry(BountyRetryLog {
-                coordinator_id: current_account_id(),
-                bounty_id: bounty_id.clone(),
-                node_ids: nodes.clone(),
-                message: Some("".to_string()),
-            }),
-        };
-        log_str(&bounty_request_retry_log.to_string());
+    // Whether node_id's owner can have another node elected, given how many of elected_count it already has.
+    // The owner is held to the lower of its quota and the bounty's max_nodes_per_owner.
+    fn owner_under_election_cap(&self, node_id: &AccountId, bounty: &Bounty, elected_by_owner: &HashMap<AccountId, u64>, elected_count: u64) -> bool {
+        let owner_id = self.nodes.get(node_id).unwrap().owner_id;
+        let mut cap = self.get_owner_election_cap(&owner_id, elected_count);
+        if bounty.max_nodes_per_owner > 0 {
+            cap = std::cmp::min(cap, bounty.max_nodes_per_owner);
+        }
+        return elected_by_owner.get(&owner_id).copied().unwrap_or(0) < cap;
+    }
+
+    // Nodes the caller excluded, the bounty excludes, or its owner blocked
+    fn node_excluded(node: &Node, bounty: &Bounty, excluded_nodes: &[AccountId], blocklist: &Blocklist) -> bool {
+        return excluded_nodes.contains(&node.id) || bounty.excluded_nodes.contains(&node.id) || blocklist.blocks(node);
+    }
+
+    fn panic_not_enough_nodes(bounty: &Bounty) -> ! {
+        match &bounty.pool_id {
+            Some(pool_id) => panic!("Not enough qualified nodes in pool {} to fill bounty", pool_id),
+            None => panic!("Not enough qualified nodes to fill bounty"),
+        }
+    }
+
+    fn panic_not_enough_owners(bounty: &Bounty) -> ! {
+        if bounty.max_nodes_per_owner > 0 {
+            panic!(
+                "Not enough qualified nodes with different owners to fill bounty, at most {} of its nodes can share an owner",
+                bounty.max_nodes_per_owner
+            );
+        }
+        panic!("Not enough qualified nodes with different owners to fill bounty without going over an owner's elected share");
+    }
+
+    // The caller must reinsert the bounty afterwards, since this bumps its event_seq
+    pub(crate) fn send_retry_event(&mut self, bounty: &mut Bounty, nodes: &Vec<AccountId>) {
+        self.emit_bounty_event(bounty, EventLogVariant::BountyRetry(BountyRetryLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty.id.clone(),
+            node_ids: nodes.clone(),
+            replaced_node_ids: vec![],
+            message: Some("".to_string()),
+        }));
     }
 
     //View function to fetch an answer that can only be run after the bounty has been completed
@@ -647,16 +1790,25 @@ impl Coordinator {
             bounty.owner_id == signer_account_id() || signer_account_id() == current_account_id(),
             "Only the bounty owner or the coordinator contract can cancel a bounty"
         );
+        require!(
+            bounty.parent_bounty_id.is_none() && self.audits.get(&bounty_id).is_none(),
+            "This bounty cannot be cancelled"
+        );
         self.close_bounty(&mut bounty, true);
         self.bounties.insert(&bounty_id, &bounty);
     }
 
+    // Skips bounties that are closed, or that can't be cancelled such as dispute re-runs
     pub fn cancel_all_my_bounties(&mut self) {
         let owner_bounties = self
             .bounty_by_owner
             .get(&signer_account_id())
             .unwrap_or_else(|| vec![]);
         for bounty_id in owner_bounties {
+            let bounty = self.bounties.get(&bounty_id).unwrap();
+            if bounty.status != BountyStatus::Pending || bounty.parent_bounty_id.is_some() || self.audits.get(&bounty_id).is_some() {
+                continue;
+            }
             self.cancel_bounty(bounty_id.clone());
         }
     }
@@ -688,8 +1840,8 @@ impl Coordinator {
                 &node_id
             );
             return false; // You have already posted an answer
-        } else if bounty.successful_nodes.len() >= bounty.min_nodes {
-            log!("Should not publish, we have enough successful nodes to close the bounty");
+        } else if bounty.has_consensus() {
+            log!("Should not publish, enough successful nodes agree to close the bounty");
             return false;
         } else if bounty.failed_nodes.len() >= bounty.min_nodes {
             log!("Should not publish, we have enough failed nodes to close the bounty");
@@ -718,17 +1870,97 @@ impl Coordinator {
             signer_account_id() == node.owner_id,
             "Only the node owner can reject a bounty"
         );
+        require!(bounty.status == BountyStatus::Pending, "Bounty is complete, it can no longer be rejected");
+        require!(bounty.elected_nodes.contains(&node_id), "You are not an elected node");
+        require!(bounty.answers.get(&node_id).is_none(), "You have already submitted an answer");
         let response =
-            NodeResponse::new_node_response(node_id.clone(), "".to_string(), message, NodeResponseStatus::REJECT);
+            NodeResponse::new_node_response(node_id.clone(), "".to_string(), message.clone(), NodeResponseStatus::REJECT);
+        let initial_storage = storage_usage();
         bounty.rejected_nodes.insert(&node_id);
         bounty.answers.insert(&node_id, &response);
+        bounty.storage_used = (bounty.storage_used + storage_usage()).saturating_sub(initial_storage);
+        require!(bounty.get_amt_storage_used() <= bounty.amt_storage, "Not enough storage left to store answer");
         node.rejected_runs = node.rejected_runs + 1;
         node.last_reject = block_timestamp();
         self.nodes.insert(&node_id, &node);
+        self.emit_bounty_event(&mut bounty, EventLogVariant::BountyRejected(BountyRejectedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            node_id: node_id.clone(),
+            message: Some(message),
+        }));
+        bounty.unanswered_nodes.remove(&node_id);
+        self.replace_rejected_node(&mut bounty, &node_id);
         self.bounties.insert(&bounty_id, &bounty);
         return response;
     }
 
+    // Counts a run the node was elected for and never answered against its record
+    fn mark_unanswered(&mut self, node_id: &AccountId, bounty_id: &AccountId) {
+        log!("Node {} did not respond to bounty {}", node_id, bounty_id);
+        let mut node = match self.nodes.get(node_id) {
+            Some(node) => node,
+            None => {
+                log!("Node {} does not exist, can't mark unanswered", node_id);
+                return;
+            }
+        };
+        node.last_unanswered = block_timestamp();
+        node.unanswered_runs += 1;
+        self.nodes.insert(node_id, &node);
+    }
+
+    // Elects a node to take over the slot of node_id, which was elected and rejected the bounty. Every node elected for the
+    // bounty so far is excluded. The rejection stands if no replacement can be elected.
+    // The caller must reinsert the bounty afterwards.
+    fn replace_rejected_node(&mut self, bounty: &mut Bounty, node_id: &AccountId) {
+        if bounty.is_auction() {
+            log!("Bounty {} was auctioned, not replacing {} with a node that didn't bid", bounty.id, node_id);
+            return;
+        }
+        if bounty.replacements >= MAX_REPLACEMENTS {
+            log!("Bounty {} already had {} replacements, not replacing {}", bounty.id, MAX_REPLACEMENTS, node_id);
+            return;
+        }
+        //The rejecter keeps its gas reimbursement, so every elected node, every replaced rejecter and this one have to fit in amt_node_reward
+        let reimbursements = bounty.elected_nodes.len() + bounty.replaced_nodes.iter().filter(|replaced| bounty.answers.get(replaced).is_some()).count() + 1;
+        if bounty.amt_gas_reimbursement * reimbursements as u128 > bounty.amt_node_reward {
+            log!("Bounty {} can't cover another gas reimbursement, not replacing {}", bounty.id, node_id);
+            return;
+        }
+        let mut excluded_nodes = bounty.elected_nodes.clone();
+        excluded_nodes.extend(bounty.replaced_nodes.iter().cloned());
+        // The replacement takes the rejected node's slot, so it doesn't count towards the owner caps or the reward split
+        let slot = bounty.elected_nodes.iter().position(|elected| elected == node_id).unwrap();
+        bounty.elected_nodes.remove(slot);
+        let replacements = match self.try_elect_nodes(bounty, 1, &excluded_nodes) {
+            Ok(replacements) => replacements,
+            Err(shortfall) => {
+                log!("No replacement for {} on bounty {}: {:?}", node_id, bounty.id, shortfall);
+                bounty.elected_nodes.insert(slot, node_id.clone());
+                return;
+            }
+        };
+        log!("Replacing {} with {} on bounty {}", node_id, replacements[0], bounty.id);
+        //The rejecter gives up its slot, but not its gas reimbursement, see get_reimbursed_node_ids
+        bounty.replaced_nodes.push(node_id.clone());
+        bounty.replacements += 1;
+        //Give the replacement the full timeout before any node can be reelected for timing out
+        bounty.elected_at = block_timestamp_ms();
+        for replacement in replacements.iter() {
+            bounty.elected_nodes.insert(slot, replacement.clone());
+            bounty.unanswered_nodes.insert(replacement);
+        }
+        //Only the replacement needs to start working, the other elected nodes already have the bounty
+        self.emit_bounty_event(bounty, EventLogVariant::BountyRetry(BountyRetryLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty.id.clone(),
+            node_ids: replacements,
+            replaced_node_ids: vec![node_id.clone()],
+            message: Some("".to_string()),
+        }));
+    }
+
     // Post your answer, and then if the bounty is complete, close the bounty
     // Closing the bounty should be separate from posting the answer in the final product, but bounty creators
     // aren't necessarily running nodes, so we need to figure out a way to delegate it to someone in the pool
@@ -763,17 +1995,7 @@ impl Coordinator {
         );
         //
         let node_response = NodeResponse::new_node_response(node_id.clone(), answer.clone(), message.clone(), status.clone());
-        let estimated_storage = storage_byte_cost() * size_of_val(&node_response) as u128;
-        let used_storage = storage_byte_cost() * size_of_val(&bounty) as u128;
-
-        log!("Estimated storage cost for answer {}, bounty has used {}, has {} left",
-            estimated_storage,
-            used_storage,
-            bounty.amt_storage - used_storage
-        );
-        require!(estimated_storage < (bounty.amt_storage),
-            "Not enough storage left to store answer");
-
+        let initial_storage = storage_usage();
         bounty.answers.insert(&node_id, &node_response.clone());
         bounty.unanswered_nodes.remove(&node_id);
         if status == NodeResponseStatus::SUCCESS {
@@ -794,9 +2016,22 @@ impl Coordinator {
                 status
             );
         }
+        bounty.storage_used = (bounty.storage_used + storage_usage()).saturating_sub(initial_storage);
+        log!("Bounty has used {} of its {} storage deposit", bounty.get_amt_storage_used(), bounty.amt_storage);
+        require!(bounty.get_amt_storage_used() <= bounty.amt_storage, "Not enough storage left to store answer");
+        self.emit_bounty_event(&mut bounty, EventLogVariant::AnswerPosted(AnswerPostedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            node_id: node_id.clone(),
+            status,
+            message: None,
+        }));
+        //Rejecting through post_answer gets the same replacement as reject_bounty
+        if bounty.rejected_nodes.contains(&node_id) {
+            self.replace_rejected_node(&mut bounty, &node_id);
+        }
 
-
-        if bounty.successful_nodes.len() == bounty.min_nodes || bounty.failed_nodes.len() == bounty.min_nodes {
+        if bounty.has_consensus() || bounty.failed_nodes.len() == bounty.min_nodes {
             self.close_bounty(&mut bounty, false);
         }
 
@@ -809,52 +2044,88 @@ impl Coordinator {
     pub fn should_collect_reward(&self, node_id: AccountId, bounty_id: AccountId) -> bool {
         let bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
         let node_response = bounty.answers.get(&node_id).unwrap_or_else(|| panic!("Node {} has not submitted an answer to bounty {}", node_id, bounty_id));
-        let payout_strategy = bounty.get_payout_strategy();
         require!(self.nodes.get(&node_id).is_some(), "Node does not exist");
-        require!(bounty.elected_nodes.contains(&node_id), "You are not elected for this bounty");
+        require!(
+            bounty.elected_nodes.contains(&node_id) || bounty.replaced_nodes.contains(&node_id),
+            "You are not elected for this bounty"
+        );
         require!(bounty.status != BountyStatus::Pending, "Cannot collect rewards for pending bounties");
         require!(node_response.payout_claimed == false, "You have already claimed your payout");
+        require!(!bounty.is_payout_locked(), "Payouts for this bounty are locked until its dispute window has passed and any dispute is resolved");
 
-        return match payout_strategy {
-            PayoutStrategy::AllAnsweredNodes => true,
-            PayoutStrategy::FailedNodes => node_response.status == NodeResponseStatus::FAILURE,
-            PayoutStrategy::SuccessfulNodes => node_response.status == NodeResponseStatus::SUCCESS,
+        // Answering before close always earns the gas reimbursement, the payout strategy and reward policy decide the rest
+        return bounty.get_amt_payout_for_node(&node_id) > 0;
+    }
+
+    // Breaks down what collect_reward would pay, zero if the node has nothing left to collect. Doesn't check eligibility, use should_collect_reward for that.
+    pub fn get_pending_payout(&self, node_id: AccountId, bounty_id: AccountId) -> PendingPayout {
+        let bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
+        let claimed = bounty.answers.get(&node_id).is_none_or(|response| response.payout_claimed);
+        let gross = if claimed { 0 } else { bounty.get_amt_payout_for_node(&node_id) };
+        let fee = bounty.get_amt_protocol_fee(gross);
+        let levy = gross * bounty.audit_levy_bps as u128 / 10_000;
+        return PendingPayout {
+            node_id,
+            bounty_id,
+            gross: gross.into(),
+            fee: fee.into(),
+            audit_levy: levy.into(),
+            net: (gross - fee - levy).into(),
         };
     }
 
     pub fn collect_reward(&mut self, node_id: AccountId, bounty_id: AccountId) -> Promise {
         //Should collect reward has most preflight checks for this function
         require!(self.should_collect_reward(node_id.clone(), bounty_id.clone()), "You are not eligible to collect a reward");
+        let pending = self.get_pending_payout(node_id.clone(), bounty_id.clone());
         let mut node = self.nodes.get(&node_id).unwrap_or_else(|| panic!("Node {} does not exist", node_id));
         let mut bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
         let mut node_response = bounty.answers.get(&node_id).unwrap_or_else(|| panic!("Node {} has not submitted an answer to bounty {}", node_id, bounty_id));
 
-        let payout = bounty.get_amt_reward_per_node();
-        log!("Collecting reward of {} for bounty {} for node {}", payout, bounty_id, node_id);
+        let gross_payout = pending.gross.0;
+        let fee = pending.fee.0;
+        let levy = pending.audit_levy.0;
+        let payout = pending.net.0;
+        log!("Collecting reward of {} (fee: {}, audit levy: {}) for bounty {} for node {}", payout, fee, levy, bounty_id, node_id);
 
         node_response.payout_claimed = true;
         bounty.answers.insert(&node_id, &node_response);
-        self.bounties.insert(&bounty_id, &bounty);
+        self.escrow_pay_out(&mut bounty, gross_payout);
 
         node.lifetime_earnings += payout;
         self.nodes.insert(&node_id, &node);
         self.total_payouts += payout;
+        self.treasury_balance += fee;
+        self.total_fees += fee;
+        self.audit_pool += levy;
+        self.emit_bounty_event(&mut bounty, EventLogVariant::RewardCollected(RewardCollectedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            node_id: node_id.clone(),
+            owner_id: node.owner_id.clone(),
+            amount: payout.into(),
+            fee: fee.into(),
+            audit_levy: levy.into(),
+            message: None,
+        }));
+        self.bounties.insert(&bounty_id, &bounty);
         return Promise::new(node.owner_id).transfer(payout);
     }
 
 
     #[private]
-    pub fn close_bounty(&mut self, bounty: &mut Bounty, cancel: bool) -> Promise {
+    pub fn close_bounty(&mut self, bounty: &mut Bounty, cancel: bool) -> Option<Promise> {
         require!(bounty.status == BountyStatus::Pending, "Bounty is already closed");
         if !cancel {
-            require!(bounty.successful_nodes.len() >= bounty.min_nodes || bounty.failed_nodes.len() >= bounty.min_nodes, "Bounty does not have enough answers to be closed. If you are the owner, you may cancel the bounty instead.");
+            require!(bounty.has_consensus() || bounty.failed_nodes.len() >= bounty.min_nodes, "Bounty does not have enough answers to be closed. If you are the owner, you may cancel the bounty instead.");
         }
         log!("Closing bounty {}", bounty.id);
+        bounty.closed_at = block_timestamp_ms();
 
         if cancel {
             bounty.status = BountyStatus::Cancelled;
-        } else if bounty.successful_nodes.len() >= bounty.min_nodes {
-            log!("Bounty is complete, at least {} nodes have responded successfully. Closing bounty now.", bounty.min_nodes);
+        } else if bounty.has_consensus() {
+            log!("Bounty is complete, at least {} nodes have responded successfully and agree on the result. Closing bounty now.", bounty.min_nodes);
             bounty.status = BountyStatus::Success;
         } else if bounty.failed_nodes.len() >= bounty.min_nodes {
             log!("Bounty is complete but failed because the number of failed nodes ({}) has exceeded the min number of nodes required for success ({})", bounty.failed_nodes.len(), bounty.min_nodes);
@@ -867,55 +2138,288 @@ impl Coordinator {
         if !cancel {
             log!("Marking unanswered nodes");
             for node_id in bounty.unanswered_nodes.iter() {
-                log!("Node {} did not respond to bounty {}", node_id, bounty.id);
-                let node_option = self.nodes.get(&node_id);
-                if node_option.is_none() {
-                    log!("Node {} does not exist, can't mark unanswered", node_id);
-                    continue;
-                }
-                let mut node = node_option.unwrap();
-                node.last_unanswered = block_timestamp();
-                node.unanswered_runs += 1;
-                self.nodes.insert(&node_id, &node);
+                self.mark_unanswered(&node_id, &bounty.id);
             }
         } else {
             log!("Bounty was cancelled, will not mark unanswered nodes");
         }
 
-        let bounty_completed_log: EventLog = EventLog {
-            standard: EVENT_STANDARD_NAME.to_string(),
-            version: EVENT_STANDARD_SPEC.to_string(),
-            event: EventLogVariant::BountyCompleted(BountyCompletedLog {
-                coordinator_id: current_account_id(),
-                bounty_id: bounty.id.clone(),
-                node_ids: bounty.elected_nodes.clone(),
-                reward_recipients: bounty.get_payout_recipient_ids(),
-                outcome: bounty.status.clone(),
-                payout_strategy: bounty.get_payout_strategy(),
-                message: None,
-            }),
-        };
-
-        log_str(&bounty_completed_log.to_string());
+        let aggregate_result = bounty.get_aggregate_result();
+        self.emit_bounty_event(bounty, EventLogVariant::BountyCompleted(BountyCompletedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty.id.clone(),
+            node_ids: bounty.elected_nodes.clone(),
+            reward_recipients: bounty.get_payout_recipient_ids(),
+            reward_amounts: bounty
+                .get_reward_amounts()
+                .into_iter()
+                .map(|(node_id, amount)| RewardAmountLog { node_id, amount: amount.into() })
+                .collect(),
+            outcome: bounty.status.clone(),
+            payout_strategy: bounty.get_payout_strategy(),
+            reward_policy: bounty.reward_policy.clone(),
+            protocol_fee_bps: bounty.protocol_fee_bps,
+            fees: bounty.get_amt_fees_total().into(),
+            canonical_result: aggregate_result.canonical_result,
+            agreeing_nodes: aggregate_result.agreeing_nodes,
+            message: None,
+        }));
+
+        if let Some(parent_bounty_id) = bounty.parent_bounty_id.clone() {
+            self.resolve_dispute(&parent_bounty_id, bounty);
+        }
+        let audited_bounty_id = self.audits.get(&bounty.id);
+        if let Some(audited_bounty_id) = audited_bounty_id.clone() {
+            self.resolve_audit(&audited_bounty_id, bounty);
+        } else if bounty.status == BountyStatus::Success && bounty.parent_bounty_id.is_none() {
+            self.maybe_spawn_audit(bounty);
+        }
 
         // Update coordinator stats
         self.total_completed_bounties += if cancel { 0 } else { 1 };
         self.active_bounties.remove(&bounty.id);
+        //Return unused storage deposit to bounty owner, along with any reward no node is entitled to.
+        //The storage the bounty has used stays in escrow, since the bounty is kept in state.
+        let storage_refund = bounty.amt_storage - bounty.get_amt_storage_used();
+        let reward_refund = bounty.get_amt_reward_refund();
+        self.escrow_refund(bounty, storage_refund + reward_refund);
         //Since this function mutates, insert the bounty back into the map
         self.bounties.insert(&bounty.id, &bounty);
-        //Return unused storage deposit to bounty owner
-        let storage_used = storage_byte_cost() * size_of_val(&bounty) as u128;
-        return Promise::new(bounty.owner_id.clone()).transfer(bounty.amt_storage - storage_used);
+        if audited_bounty_id.is_some() {
+            // Audits are funded by the pool, so whatever they didn't use goes back to it rather than to the owner.
+            // The funds never left the contract, so there's nothing to transfer.
+            log!("Returning {} of unused storage and {} of unclaimed reward to the audit pool", storage_refund, reward_refund);
+            self.audit_pool += storage_refund + reward_refund;
+            return None;
+        }
+        log!("Refunding {} of unused storage and {} of unclaimed reward to {}", storage_refund, reward_refund, bounty.owner_id);
+        return Some(Promise::new(bounty.owner_id.clone()).transfer(storage_refund + reward_refund));
+    }
+
+    // The owner escrows a bond on top of the storage and reward for the re-run, which is sent to nodes that weren't elected the first time
+    #[payable]
+    pub fn dispute_bounty(&mut self, bounty_id: AccountId, amt_storage: String, amt_node_reward: String) -> Bounty {
+        let mut bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
+        let amt_storage: u128 = amt_storage.parse().unwrap();
+        let amt_node_reward: u128 = amt_node_reward.parse().unwrap();
+        require!(bounty.owner_id == signer_account_id(), "Only the bounty owner can dispute a bounty");
+        require!(bounty.is_disputable(), "Bounty can only be disputed once, after it succeeds and before its dispute window has passed");
+        self.require_active_bounty_quota(&bounty.owner_id);
+        require!(amt_storage >= MIN_STORAGE, "Refundable storage deposit must be at least 0.1N");
+        require!(amt_node_reward >= MIN_REWARD, "Node reward must be at least 0.1N");
+        require!(
+            attached_deposit() >= amt_storage + amt_node_reward + MIN_DISPUTE_BOND,
+            "Attached deposit must cover the re-run's storage and node reward, plus a bond of at least 0.1N"
+        );
+        let bond = attached_deposit() - amt_storage - amt_node_reward;
+        let total_nodes = self.get_node_padding(bounty.min_nodes);
+        require!(
+            self.gas_reimbursement * (total_nodes as u128) < amt_node_reward,
+            "Node reward must be greater than the gas reimbursement reserved for every elected node"
+        );
+
+        let mut rerun = Bounty::new_bounty(
+            self.next_bounty_id(&bounty.owner_id),
+            bounty.file_location.clone(),
+            bounty.file_download_protocol.clone(),
+            bounty.min_nodes,
+            bounty.timeout_seconds,
+            bounty.network_required,
+            bounty.gpu_required,
+            amt_storage,
+            amt_node_reward,
+            self.gas_reimbursement,
+            self.protocol_fee_bps,
+            self.audit_levy_bps,
+            BountyOptions::rerun_of(&bounty),
+        );
+        rerun.parent_bounty_id = Some(bounty_id.clone());
+        log!("Disputing bounty {} with a bond of {}, re-running as {}", bounty_id, bond, rerun.id);
+        self.publish_bounty(&mut rerun, total_nodes, &vec![]);
+
+        bounty.dispute_status = DisputeStatus::Pending;
+        bounty.dispute_bounty_id = Some(rerun.id.clone());
+        bounty.amt_dispute_bond = bond;
+        self.escrow_deposit(&mut bounty, bond);
+        self.emit_bounty_event(&mut bounty, EventLogVariant::BountyDisputed(BountyDisputedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            rerun_bounty_id: rerun.id.clone(),
+            bond: bond.into(),
+            message: None,
+        }));
+        self.bounties.insert(&bounty_id, &bounty);
+        return rerun;
+    }
+
+    // Cancels a dispute re-run or audit that didn't close in time. An expired dispute re-run upholds the disputed bounty and
+    // returns the owner's bond, an expired audit returns what it didn't use to the audit pool without marking anyone.
+    // Anyone can call it once RERUN_TIMEOUT_SECONDS, or the re-run's own timeout if that's longer, have passed since it was created.
+    pub fn expire_rerun(&mut self, bounty_id: AccountId) {
+        let mut rerun = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
+        require!(
+            rerun.parent_bounty_id.is_some() || self.audits.get(&bounty_id).is_some(),
+            format!("Bounty {} is not a dispute re-run or audit", bounty_id)
+        );
+        require!(rerun.status == BountyStatus::Pending, "Bounty is already closed");
+        let deadline = rerun.bounty_created + std::cmp::max(RERUN_TIMEOUT_SECONDS, rerun.timeout_seconds) * 1000;
+        require!(block_timestamp_ms() >= deadline, format!("Re-run {} has until {} to close", bounty_id, deadline));
+        log!("Re-run {} did not close by {}, expiring it", bounty_id, deadline);
+        self.close_bounty(&mut rerun, true);
+    }
+
+    // Called when a dispute re-run closes. Only a successful re-run that disagrees with the bounty overturns it, and then
+    // the owner gets the escrowed reward and the bond back. If the re-run agrees the original nodes get both.
+    // A re-run that fails or is cancelled proves nothing, so the bounty is upheld and the owner gets the bond back.
+    pub(crate) fn resolve_dispute(&mut self, bounty_id: &AccountId, rerun: &Bounty) {
+        let mut bounty = self.bounties.get(bounty_id).unwrap_or_else(|| panic!("Disputed bounty {} does not exist", bounty_id));
+        require!(bounty.dispute_status == DisputeStatus::Pending, "Disputed bounty is not awaiting a re-run");
+        let conclusive = rerun.status == BountyStatus::Success;
+        let upheld = !conclusive || bounty.agrees_with(rerun);
+        let recipients: Vec<AccountId>;
+        // What the recipients get out of the resolution, whether it's transferred now or collected later
+        let amount: Balance;
+        if !conclusive {
+            log!("Re-run {} of bounty {} ended {}, upholding the bounty and refunding the bond to {}", rerun.id, bounty_id, rerun.status, bounty.owner_id);
+            bounty.dispute_status = DisputeStatus::Upheld;
+            recipients = vec![bounty.owner_id.clone()];
+            amount = bounty.amt_dispute_bond;
+            bounty.amt_dispute_bond = 0;
+            self.escrow_refund(&mut bounty, amount);
+            Promise::new(bounty.owner_id.clone()).transfer(amount);
+        } else if upheld {
+            // Nodes collect their share of the bond along with their reward through collect_reward
+            log!("Re-run {} agrees with bounty {}, releasing the reward and bond to the original nodes", rerun.id, bounty_id);
+            bounty.dispute_status = DisputeStatus::Upheld;
+            recipients = bounty.get_payout_recipient_ids();
+            let bond_released = bounty.get_amt_dispute_bond_share() * recipients.len() as u128;
+            amount = bounty.get_amt_promised_payouts() + bond_released;
+            let bond_dust = bounty.amt_dispute_bond - bond_released;
+            if bond_dust > 0 {
+                self.escrow_refund(&mut bounty, bond_dust);
+                Promise::new(bounty.owner_id.clone()).transfer(bond_dust);
+            }
+        } else {
+            log!("Re-run {} disagrees with bounty {}, refunding the reward and bond to {}", rerun.id, bounty_id, bounty.owner_id);
+            bounty.dispute_status = DisputeStatus::Overturned;
+            recipients = vec![bounty.owner_id.clone()];
+            // Anything the reward policy didn't promise to a node was already refunded when the bounty closed
+            amount = bounty.get_amt_promised_payouts() + bounty.amt_dispute_bond;
+            self.escrow_refund(&mut bounty, amount);
+            Promise::new(bounty.owner_id.clone()).transfer(amount);
+        }
+
+        self.emit_bounty_event(&mut bounty, EventLogVariant::BountyDisputeResolved(BountyDisputeResolvedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            rerun_bounty_id: rerun.id.clone(),
+            upheld,
+            recipients,
+            amount: amount.into(),
+            message: None,
+        }));
+        self.bounties.insert(bounty_id, &bounty);
+    }
+
+    // Rolls against the audit rate and, if the pool can afford it, re-runs the bounty on nodes that weren't elected for it.
+    // The audit is created in the original owner's name, but listed under the coordinator. It isn't secret: it's published in the
+    // same receipt that closes the audited bounty, with the same job, so nodes can tell it apart. Any successful bounty can be
+    // audited though, so nodes can't know in advance which answers will be checked.
+    pub(crate) fn maybe_spawn_audit(&mut self, bounty: &Bounty) {
+        if self.audit_rate_bps == 0 || Coordinator::rand_u64() % 10_000 >= self.audit_rate_bps as u64 {
+            return;
+        }
+        let total_nodes = self.get_node_padding(bounty.min_nodes);
+        let amt_node_reward = std::cmp::min(bounty.amt_node_reward, self.audit_pool.saturating_sub(MIN_STORAGE));
+        if amt_node_reward < MIN_REWARD || self.gas_reimbursement * (total_nodes as u128) >= amt_node_reward {
+            log!("Audit pool ({}) can't fund an audit of bounty {}", self.audit_pool, bounty.id);
+            return;
+        }
+        let mut audit = Bounty::new_bounty(
+            self.next_bounty_id(&bounty.owner_id),
+            bounty.file_location.clone(),
+            bounty.file_download_protocol.clone(),
+            bounty.min_nodes,
+            bounty.timeout_seconds,
+            bounty.network_required,
+            bounty.gpu_required,
+            MIN_STORAGE,
+            amt_node_reward,
+            self.gas_reimbursement,
+            self.protocol_fee_bps,
+            self.audit_levy_bps,
+            BountyOptions::rerun_of(bounty),
+        );
+        audit.owner_id = bounty.owner_id.clone();
+        // Elect up front, so that an audit that can't be staffed is skipped rather than stopping the audited bounty from closing.
+        // Pool bounties are only audited by other members of the pool, their owners don't trust the public queue.
+        audit.elected_nodes = match self.try_elect_nodes(&audit, total_nodes as usize, &[]) {
+            Ok(elected_nodes) => elected_nodes,
+            Err(shortfall) => {
+                log!("Not enough nodes to audit bounty {} on a disjoint set: {:?}", bounty.id, shortfall);
+                return;
+            }
+        };
+        self.audit_pool -= MIN_STORAGE + amt_node_reward;
+        self.audits.insert(&audit.id, &bounty.id);
+        self.publish_bounty(&mut audit, total_nodes, &vec![]);
+    }
+
+    // Called when an audit closes. If it doesn't agree with the audited bounty, every node that agreed on the original result is marked.
+    // An audit that failed or expired has nothing to compare against, so it's dropped without marking anyone.
+    pub(crate) fn resolve_audit(&mut self, audited_bounty_id: &AccountId, audit: &Bounty) {
+        if audit.status != BountyStatus::Success {
+            log!("Audit {} of bounty {} ended {}, dropping it", audit.id, audited_bounty_id, audit.status);
+            return;
+        }
+        let audited = self.bounties.get(audited_bounty_id).unwrap_or_else(|| panic!("Audited bounty {} does not exist", audited_bounty_id));
+        let expected = audited.get_consensus_solution();
+        let actual = audit.get_consensus_solution();
+        let passed = audited.agrees_with(audit) || actual == expected;
+        let audited_nodes: Vec<AccountId> = audited.get_aggregate_result().agreeing_nodes;
+        for node_id in audited_nodes.iter() {
+            let node_option = self.nodes.get(node_id);
+            if node_option.is_none() {
+                log!("Node {} does not exist, can't record audit result", node_id);
+                continue;
+            }
+            let mut node = node_option.unwrap();
+            if passed {
+                node.audits_passed += 1;
+            } else {
+                node.audits_failed += 1;
+            }
+            self.nodes.insert(node_id, &node);
+        }
+        if passed {
+            log!("Audit of bounty {} passed", audited_bounty_id);
+            return;
+        }
+        log!("Audit of bounty {} did not match the original result", audited_bounty_id);
+        let mut audited = audited;
+        self.emit_bounty_event(&mut audited, EventLogVariant::BountyAuditMismatch(BountyAuditMismatchLog {
+            coordinator_id: current_account_id(),
+            bounty_id: audited_bounty_id.clone(),
+            audit_bounty_id: audit.id.clone(),
+            node_ids: audited_nodes,
+            expected,
+            actual,
+            message: None,
+        }));
+        self.bounties.insert(audited_bounty_id, &audited);
     }
 
     //TODO below can be optimized with bounty.get_unpaid_nodes()/similarly named
     //If an answered node is deleted from an in-flight bounty, attempt to refund the reward to the bounty owner AFTER 1 week has passed
-    pub fn reclaim_reward_from_dropped_nodes(&self, bounty_id: &AccountId) -> Promise {
-        let bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
-        require!(bounty.bounty_created + 604800000000000 < block_timestamp(), "Bounty is not old enough to reclaim rewards from dropped nodes");
+    pub fn reclaim_reward_from_dropped_nodes(&mut self, bounty_id: AccountId) -> Promise {
+        let mut bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
+        require!(bounty.status != BountyStatus::Pending, "Cannot reclaim rewards from pending bounties");
+        require!(!bounty.is_payout_locked(), "Payouts for this bounty are locked until its dispute window has passed and any dispute is resolved");
+        require!(bounty.bounty_created + 604800000 < block_timestamp_ms(), "Bounty is not old enough to reclaim rewards from dropped nodes");
 
         let mut additional_bounty_refund: Balance = 0;
-        for (node_id, answer) in bounty.answers.iter() {
+        let answers: Vec<(AccountId, NodeResponse)> = bounty.answers.iter().collect();
+        for (node_id, answer) in answers {
             let node_option = self.nodes.get(&node_id);
             if node_option.is_some() {
                 log!("Node {} is not deleted, cannot attempt to reclaim reward", node_id);
@@ -925,15 +2429,27 @@ impl Coordinator {
                 log!("Node {} is deleted, but has already been paid for this bounty", node_id);
                 continue;
             }
-            let payout = bounty.get_amt_reward_per_node();
-            log!("Node {} is deleted and hasn't reclaimed reward in a week, refunding {} to {}", node_id, bounty.owner_id, payout);
+            let payout = bounty.get_amt_payout_for_node(&node_id);
+            log!("Node {} is deleted and hasn't reclaimed reward in a week, refunding {} to {}", node_id, payout, bounty.owner_id);
             additional_bounty_refund += payout;
+            // Mark the payout as settled so it can't be reclaimed twice
+            let mut answer = answer;
+            answer.payout_claimed = true;
+            bounty.answers.insert(&node_id, &answer);
         }
+        self.escrow_refund(&mut bounty, additional_bounty_refund);
+        self.bounties.insert(&bounty_id, &bounty);
 
         return Promise::new(bounty.owner_id.clone()).transfer(additional_bounty_refund);
     }
 
 
+    // The canonical result under the bounty's result type and aggregation, and which successful answers agree with it
+    pub fn get_bounty_aggregate_result(&self, bounty_id: AccountId) -> AggregateResult {
+        let bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
+        return bounty.get_aggregate_result();
+    }
+
     // Returns a map of {Solution: Number of nodes with solution}
     // Probably not the most optimal way to render the result
     pub fn get_bounty_result(&self, bounty_id: AccountId) -> HashMap<String, u8> {
@@ -960,8 +2476,21 @@ impl Coordinator {
             bounty.owner_id == signer_account_id() || bounty.coordinator_id == current_account_id(),
             "Only the owner of the bounty or the coordinator can add to the deposit"
         );
+        //Nothing is paid out of a closed bounty's deposits anymore, so a top up would be stuck in escrow
+        require!(bounty.status == BountyStatus::Pending, "Deposits can only be added to pending bounties");
         bounty.amt_storage += attached_deposit();
-        self.send_retry_event(&bounty_id, &bounty.elected_nodes);
+        self.escrow_deposit(&mut bounty, attached_deposit());
+        let total = bounty.amt_storage;
+        self.emit_bounty_event(&mut bounty, EventLogVariant::BountyDepositAdded(BountyDepositAddedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            deposit: BountyDepositKind::Storage,
+            amount: attached_deposit().into(),
+            total: total.into(),
+            message: None,
+        }));
+        let elected_nodes = bounty.elected_nodes.clone();
+        self.send_retry_event(&mut bounty, &elected_nodes);
         self.bounties.insert(&bounty_id, &bounty);
 
         return Promise::new(current_account_id()).transfer(attached_deposit());
@@ -979,18 +2508,48 @@ impl Coordinator {
             bounty.owner_id == signer_account_id() || bounty.coordinator_id == current_account_id(),
             "Only the owner of the bounty or the coordinator can add to the deposit"
         );
+        //Nothing is paid out of a closed bounty's deposits anymore, so a top up would be stuck in escrow
+        require!(bounty.status == BountyStatus::Pending, "Deposits can only be added to pending bounties");
         bounty.amt_node_reward += attached_deposit();
-        self.send_retry_event(&bounty_id, &bounty.elected_nodes);
+        self.escrow_deposit(&mut bounty, attached_deposit());
+        let total = bounty.amt_node_reward;
+        self.emit_bounty_event(&mut bounty, EventLogVariant::BountyDepositAdded(BountyDepositAddedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            deposit: BountyDepositKind::NodeReward,
+            amount: attached_deposit().into(),
+            total: total.into(),
+            message: None,
+        }));
+        let elected_nodes = bounty.elected_nodes.clone();
+        self.send_retry_event(&mut bounty, &elected_nodes);
         self.bounties.insert(&bounty_id, &bounty);
         return Promise::new(current_account_id()).transfer(attached_deposit());
     }
 
-    // When a bounty is stalled due to offline nodes, this can redo the in-flight elections to try it against a new set of nodes
+    // When a bounty is stalled due to offline nodes, this can redo the in-flight elections to try it against a new set of nodes.
+    // Only the bounty owner or a keeper can reelect, once the nodes have had timeout_seconds to answer. Nodes that timed out
+    // are marked unanswered and never elected for the bounty again.
     pub fn reelect_unanswered_nodes(&mut self, bounty_id: AccountId){
         let mut bounty = self.bounties.get(&bounty_id).unwrap_or_else(|| panic!("Bounty {} does not exist", bounty_id));
         require!(bounty.status == BountyStatus::Pending, "Bounty must be in-flight to reelect nodes");
-
-        //TODO Do a compare of bounty created to block timestamp to ensure reelection can only happen after timeout
+        require!(!bounty.is_bidding(), "Bounty is still an open auction, award it instead");
+        // Nodes that didn't bid never agreed to the winning prices, so like replace_rejected_node this leaves awarded auctions alone
+        require!(!bounty.is_auction(), "Bounty was auctioned, only nodes that bid can work on it, cancel it instead");
+        require!(
+            signer_account_id() == bounty.owner_id || self.keepers.contains(&signer_account_id()),
+            "Only the bounty owner or a keeper can reelect nodes"
+        );
+        require!(bounty.timeout_seconds > 0, format!("Bounty {} has no timeout, its nodes can't time out", bounty_id));
+        let deadline = bounty.elected_at + bounty.timeout_seconds * 1000;
+        require!(
+            block_timestamp_ms() >= deadline,
+            format!("Nodes have until {} to answer bounty {} before they can be reelected", deadline, bounty_id)
+        );
+        require!(
+            bounty.reelection_rounds < MAX_REELECTION_ROUNDS,
+            format!("Bounty {} has already been reelected {} times, cancel it instead", bounty_id, MAX_REELECTION_ROUNDS)
+        );
 
         let mut existing_elections: Vec<AccountId> = vec![];
         let mut timed_out_nodes: Vec<AccountId> = vec![];
@@ -1001,22 +2560,37 @@ impl Coordinator {
                 timed_out_nodes.push(node_id.clone());
             }
         }
+        require!(!timed_out_nodes.is_empty(), format!("Every node elected for bounty {} has answered", bounty_id));
         bounty.elected_nodes.retain(|node_id| bounty.answers.get(&node_id).is_some());
-        let mut new_elections = self.elect_nodes(&bounty, timed_out_nodes.len());
-        existing_elections.append(&mut new_elections);
+        bounty.replaced_nodes.extend(timed_out_nodes.iter().cloned());
+        //Nodes that answered keep their place, every node that held one before can't be elected again
+        let mut excluded_nodes = bounty.elected_nodes.clone();
+        excluded_nodes.extend(bounty.replaced_nodes.iter().cloned());
+        let new_elections = self.elect_nodes(&bounty, timed_out_nodes.len(), &excluded_nodes);
+        existing_elections.extend(new_elections.iter().cloned());
 
         bounty.elected_nodes = existing_elections;
+        bounty.elected_at = block_timestamp_ms();
+        bounty.reelection_rounds += 1;
 
         for node_id in timed_out_nodes.iter() {
             bounty.unanswered_nodes.remove(&node_id);
+            self.mark_unanswered(node_id, &bounty_id);
         }
         for node_id in new_elections.iter() {
             bounty.unanswered_nodes.insert(&node_id);
         }
-        self.bounties.insert(&bounty_id, &bounty);
+        self.emit_bounty_event(&mut bounty, EventLogVariant::BountyReelected(BountyReelectedLog {
+            coordinator_id: current_account_id(),
+            bounty_id: bounty_id.clone(),
+            timed_out_node_ids: timed_out_nodes.clone(),
+            node_ids: new_elections.clone(),
+            message: None,
+        }));
 
         //We only need newly elected nodes in the retry event, including all elections would waste compute on nodes that have already answered
-        self.send_retry_event(&bounty_id, &new_elections);
+        self.send_retry_event(&mut bounty, &new_elections);
+        self.bounties.insert(&bounty_id, &bounty);
     }
 }
 
@@ -1031,8 +2605,62 @@ impl Coordinator {
  */
 #[cfg(test)]
 mod tests {
+    use near_sdk::test_utils::{accounts, get_logs, VMContextBuilder};
+    use near_sdk::testing_env;
+    use crate::aggregation::{AggregationFunction, ResultType};
+
     use super::*;
 
+    fn set_context(signer: AccountId, deposit: Balance) {
+        set_context_at(signer, deposit, 0);
+    }
+
+    fn set_context_at(signer: AccountId, deposit: Balance, block_timestamp: u64) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id("coordinator.near".parse().unwrap())
+            .signer_account_id(signer.clone())
+            .predecessor_account_id(signer)
+            .attached_deposit(deposit)
+            .block_timestamp(block_timestamp)
+            .build());
+    }
+
+    fn register_nodes(coordinator: &mut Coordinator, owner: AccountId, n: u64) -> Vec<AccountId> {
+        set_context(owner, parse_near!("1N"));
+        let mut node_ids = vec![];
+        for i in 0..n {
+            node_ids.push(coordinator.register_node(format!("node{}", i), 60000, true, true).id);
+        }
+        return node_ids;
+    }
+
+    fn create_test_bounty(coordinator: &mut Coordinator, owner: AccountId, min_nodes: u64, reward_policy: Option<RewardPolicy>) -> Bounty {
+        set_context(owner, parse_near!("2N"));
+        return coordinator.create_bounty(
+            "https://github.com/ad0ll/docker-hello-world.git".to_string(),
+            SupportedDownloadProtocols::GIT,
+            min_nodes,
+            30,
+            false,
+            false,
+            parse_near!("1N").to_string(),
+            parse_near!("1N").to_string(),
+            Some(BountyOptions {
+                reward_policy,
+                max_nodes_per_owner: Some(0),
+                ..Default::default()
+            }),
+        );
+    }
+
+    fn answer_all(coordinator: &mut Coordinator, bounty: &Bounty, owner: AccountId, statuses: Vec<NodeResponseStatus>) -> Bounty {
+        set_context(owner, 0);
+        for (node_id, status) in bounty.elected_nodes.iter().zip(statuses) {
+            coordinator.post_answer(bounty.id.clone(), node_id.clone(), "42".to_string(), "".to_string(), status);
+        }
+        return coordinator.get_bounty(bounty.id.clone());
+    }
+
     #[test]
     fn can_register_node() {
         let mut coordinator = Coordinator::default();
@@ -1069,6 +2697,1071 @@ mod tests {
         //TODO
     }
 
+    #[test]
+    fn answering_nodes_are_reimbursed_for_gas() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        assert_eq!(bounty.amt_gas_reimbursement, DEFAULT_GAS_REIMBURSEMENT);
+
+        set_context(accounts(1), 0);
+        let failed_node = bounty.elected_nodes[0].clone();
+        let successful_nodes = bounty.elected_nodes[1..].to_vec();
+        coordinator.post_answer(bounty.id.clone(), failed_node.clone(), "1".to_string(), "".to_string(), NodeResponseStatus::FAILURE);
+        for node_id in &successful_nodes {
+            coordinator.post_answer(bounty.id.clone(), node_id.clone(), "42".to_string(), "".to_string(), NodeResponseStatus::SUCCESS);
+        }
+
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(bounty.status, BountyStatus::Success);
+        assert!(coordinator.should_collect_reward(failed_node.clone(), bounty.id.clone()), "Failed node should still be reimbursed");
+        assert_eq!(bounty.get_amt_payout_for_node(&failed_node), DEFAULT_GAS_REIMBURSEMENT);
+        assert_eq!(
+            bounty.get_amt_payout_for_node(&successful_nodes[0]),
+            DEFAULT_GAS_REIMBURSEMENT + (parse_near!("1N") - 3 * DEFAULT_GAS_REIMBURSEMENT) / 2,
+            "Successful nodes should get their reimbursement plus an equal share of the remainder"
+        );
+
+        coordinator.collect_reward(failed_node.clone(), bounty.id.clone());
+        for node_id in &successful_nodes {
+            coordinator.collect_reward(node_id.clone(), bounty.id.clone());
+        }
+        assert_eq!(coordinator.get_total_payouts(), parse_near!("1N"));
+    }
+
+    #[test]
+    fn first_n_policy_only_pays_the_fastest_recipients() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, Some(RewardPolicy::FirstN { n: 1 }));
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+
+        let first = bounty.elected_nodes[0].clone();
+        let second = bounty.elected_nodes[1].clone();
+        assert_eq!(bounty.get_amt_reward_for_node(&first), bounty.get_amt_reward_pool());
+        assert_eq!(bounty.get_amt_reward_for_node(&second), 0);
+        assert_eq!(bounty.get_amt_payout_for_node(&second), DEFAULT_GAS_REIMBURSEMENT, "Slower node should only be reimbursed");
+    }
+
+    #[test]
+    fn fixed_price_policy_refunds_the_remainder() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        let price = parse_near!("0.1N");
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, Some(RewardPolicy::FixedPerNode { price: price.into() }));
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+
+        for (_, amount) in bounty.get_reward_amounts() {
+            assert_eq!(amount, price);
+        }
+        assert_eq!(bounty.get_amt_reward_refund(), parse_near!("1N") - 2 * DEFAULT_GAS_REIMBURSEMENT - 2 * price);
+    }
+
+    #[test]
+    fn latency_weighted_policy_favours_the_fastest_recipient() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, Some(RewardPolicy::LatencyWeighted { bonus_bps: 3000 }));
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+
+        let amounts = bounty.get_reward_amounts();
+        assert!(amounts[0].1 > amounts[1].1, "Fastest recipient should earn the larger share");
+        let total: Balance = amounts.iter().map(|(_, amount)| amount).sum();
+        assert!(total <= bounty.get_amt_reward_pool());
+    }
+
+    #[test]
+    fn protocol_fee_is_credited_to_the_treasury() {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        coordinator.set_protocol_fee_bps(500);
+        register_nodes(&mut coordinator, accounts(1), 3);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        assert_eq!(bounty.protocol_fee_bps, 500);
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+
+        let expected_fees = bounty.get_amt_fees_total();
+        for node_id in bounty.elected_nodes.iter().take(2) {
+            coordinator.collect_reward(node_id.clone(), bounty.id.clone());
+        }
+        assert_eq!(coordinator.get_total_fees(), expected_fees);
+        assert_eq!(coordinator.get_treasury_balance(), expected_fees);
+        assert_eq!(coordinator.get_total_payouts() + coordinator.get_total_fees(), parse_near!("1N"));
+    }
+
+    #[test]
+    fn pending_payout_matches_what_collect_reward_pays() {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        coordinator.set_protocol_fee_bps(500);
+        register_nodes(&mut coordinator, accounts(1), 3);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+
+        let node_id = bounty.elected_nodes[0].clone();
+        let pending = coordinator.get_pending_payout(node_id.clone(), bounty.id.clone());
+        assert_eq!(pending.gross.0, bounty.get_amt_payout_for_node(&node_id));
+        assert_eq!(pending.net.0, pending.gross.0 - pending.fee.0 - pending.audit_levy.0);
+        assert!(pending.fee.0 > 0);
+
+        coordinator.collect_reward(node_id.clone(), bounty.id.clone());
+        assert_eq!(coordinator.get_node(node_id.clone()).lifetime_earnings, pending.net.0);
+        assert_eq!(coordinator.get_pending_payout(node_id, bounty.id.clone()).gross.0, 0, "Nothing is left once the payout is claimed");
+    }
+
+    fn event_names() -> Vec<String> {
+        return get_logs()
+            .iter()
+            .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+            .map(|json| serde_json::from_str::<serde_json::Value>(json).unwrap())
+            .inspect(|event| assert_eq!(event["standard"], EVENT_STANDARD_NAME))
+            .map(|event| event["event"].as_str().unwrap().to_string())
+            .collect();
+    }
+
+    #[test]
+    fn every_state_transition_emits_an_event() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 3);
+        assert_eq!(event_names(), vec!["node_registered"; 3]);
+        coordinator.update_node(node_ids[0].clone(), true, true, 30000);
+        coordinator.set_node_offline(node_ids[0].clone(), true);
+        coordinator.set_node_offline(node_ids[0].clone(), false);
+        assert_eq!(event_names(), vec!["node_registered", "node_registered", "node_registered", "node_updated", "node_status_changed", "node_status_changed"]);
+
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        let created: serde_json::Value = serde_json::from_str(get_logs().last().unwrap().strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+        assert_eq!(created["event"], "bounty_created");
+        assert_eq!(created["data"]["min_nodes"], 2);
+        assert_eq!(created["data"]["amt_node_reward"], parse_near!("1N").to_string());
+        set_context(accounts(0), parse_near!("1N"));
+        coordinator.add_node_reward_deposit(bounty.id.clone());
+        assert_eq!(event_names(), vec!["bounty_deposit_added", "bounty_retry"]);
+
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+        assert_eq!(event_names(), vec!["answer_posted", "answer_posted", "bounty_completed"]);
+        set_context(accounts(1), 0);
+        coordinator.collect_reward(bounty.elected_nodes[0].clone(), bounty.id.clone());
+        assert_eq!(event_names(), vec!["reward_collected"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Deposits can only be added to pending bounties")]
+    fn cannot_add_deposit_to_a_closed_bounty() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+        set_context(accounts(0), parse_near!("1N"));
+        coordinator.add_storage_deposit(bounty.id.clone());
+    }
+
+    #[test]
+    fn event_nonce_and_bounty_event_seq_have_no_gaps() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        assert_eq!(coordinator.get_event_nonce(), 3);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+        let events: Vec<serde_json::Value> = get_logs()
+            .iter()
+            .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+            .map(|json| serde_json::from_str(json).unwrap())
+            .collect();
+        let nonces: Vec<u64> = events.iter().map(|event| event["event_nonce"].as_u64().unwrap()).collect();
+        let seqs: Vec<u64> = events.iter().map(|event| event["event_seq"].as_u64().unwrap()).collect();
+        assert_eq!(nonces, vec![5, 6, 7]);
+        assert_eq!(seqs, vec![2, 3, 4]);
+        assert_eq!(bounty.event_seq, 4);
+        assert_eq!(coordinator.get_event_nonce(), 7);
+    }
+
+    #[test]
+    fn escrow_only_holds_used_storage_once_rewards_are_collected() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        assert_eq!(bounty.escrow.deposited, parse_near!("2N"));
+        assert_eq!(coordinator.get_total_escrowed(), parse_near!("2N"));
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+        for node_id in bounty.elected_nodes.iter().take(2) {
+            coordinator.collect_reward(node_id.clone(), bounty.id.clone());
+        }
+
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert!(bounty.storage_used > 0);
+        assert_eq!(bounty.escrow.remaining, bounty.get_amt_storage_used());
+        assert_eq!(bounty.escrow.paid_out, parse_near!("1N"));
+        assert_eq!(bounty.escrow.deposited, bounty.escrow.paid_out + bounty.escrow.refunded + bounty.escrow.remaining);
+        assert!(coordinator.get_solvency_report().ledger_consistent);
+    }
+
+    #[test]
+    fn node_deposits_count_towards_solvency() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        for balance in [parse_near!("3N"), parse_near!("2N")] {
+            testing_env!(VMContextBuilder::new()
+                .current_account_id("coordinator.near".parse().unwrap())
+                .account_balance(balance)
+                .build());
+            let report = coordinator.get_solvency_report();
+            assert_eq!(report.node_deposits.0, parse_near!("3N"));
+            assert_eq!(report.escrowed.0 + report.treasury_balance.0 + report.audit_pool.0, 0);
+            assert_eq!(report.solvent, balance == parse_near!("3N"), "Only the node deposits should decide solvency here");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the coordinator admin can change protocol settings")]
+    fn only_admin_can_set_protocol_fee() {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        set_context(accounts(0), 0);
+        coordinator.set_protocol_fee_bps(500);
+    }
+
+    // Successful bounty with a dispute window
+    fn create_disputable_bounty(coordinator: &mut Coordinator) -> Bounty {
+        register_nodes(coordinator, accounts(1), 6);
+        set_context(accounts(0), parse_near!("2N"));
+        let bounty = coordinator.create_bounty(
+            "https://github.com/ad0ll/docker-hello-world.git".to_string(),
+            SupportedDownloadProtocols::GIT,
+            2,
+            30,
+            false,
+            false,
+            parse_near!("1N").to_string(),
+            parse_near!("1N").to_string(),
+            Some(BountyOptions {
+                dispute_window_seconds: Some(3600),
+                max_nodes_per_owner: Some(0),
+                ..Default::default()
+            }),
+        );
+        let bounty = answer_all(coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+        assert!(bounty.is_payout_locked(), "Payouts should be escrowed during the dispute window");
+        return bounty;
+    }
+
+    // Successful bounty with a dispute window, disputed by its owner with a 0.5N bond. Returns the bounty and its re-run
+    fn create_disputed_bounty(coordinator: &mut Coordinator) -> (Bounty, Bounty) {
+        let bounty = create_disputable_bounty(coordinator);
+        set_context(accounts(0), parse_near!("2.5N"));
+        let rerun = coordinator.dispute_bounty(bounty.id.clone(), parse_near!("1N").to_string(), parse_near!("1N").to_string());
+        assert!(rerun.elected_nodes.iter().all(|node_id| !bounty.elected_nodes.contains(node_id)), "Re-run must not reuse original electees");
+        assert!(coordinator.get_bounty(bounty.id.clone()).is_payout_locked());
+        let disputed: serde_json::Value = serde_json::from_str(get_logs().iter().rev().find_map(|log| log.strip_prefix("EVENT_JSON:")).unwrap()).unwrap();
+        assert_eq!(disputed["event"], "bounty_disputed");
+        assert_eq!(disputed["data"]["rerun_bounty_id"], rerun.id.to_string());
+        assert_eq!(disputed["data"]["bond"], coordinator.get_bounty(bounty.id.clone()).amt_dispute_bond.to_string());
+        return (bounty, rerun);
+    }
+
+    #[test]
+    fn upheld_dispute_releases_reward_and_bond_to_original_nodes() {
+        let mut coordinator = Coordinator::default();
+        let (bounty, rerun) = create_disputed_bounty(&mut coordinator);
+        answer_all(&mut coordinator, &rerun, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(bounty.dispute_status, DisputeStatus::Upheld);
+        assert!(!bounty.is_payout_locked());
+        let node_id = bounty.get_payout_recipient_ids()[0].clone();
+        assert_eq!(
+            bounty.get_amt_payout_for_node(&node_id),
+            DEFAULT_GAS_REIMBURSEMENT + bounty.get_amt_reward_for_node(&node_id) + parse_near!("0.25N")
+        );
+    }
+
+    #[test]
+    fn overturned_dispute_refunds_reward_and_bond_to_owner() {
+        let mut coordinator = Coordinator::default();
+        let (bounty, rerun) = create_disputed_bounty(&mut coordinator);
+        set_context(accounts(1), 0);
+        for node_id in rerun.elected_nodes.iter().take(2) {
+            coordinator.post_answer(rerun.id.clone(), node_id.clone(), "43".to_string(), "".to_string(), NodeResponseStatus::SUCCESS);
+        }
+
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(bounty.dispute_status, DisputeStatus::Overturned);
+        assert!(!bounty.is_payout_locked());
+        for node_id in bounty.elected_nodes.iter() {
+            assert_eq!(bounty.get_amt_payout_for_node(node_id), 0);
+        }
+        assert_eq!(bounty.escrow.remaining, bounty.get_amt_storage_used(), "Reward and bond should have gone back to the owner");
+    }
+
+    #[test]
+    fn failed_rerun_upholds_the_bounty_and_returns_the_bond() {
+        let mut coordinator = Coordinator::default();
+        let (bounty, rerun) = create_disputed_bounty(&mut coordinator);
+        answer_all(&mut coordinator, &rerun, accounts(1), vec![NodeResponseStatus::FAILURE, NodeResponseStatus::FAILURE]);
+
+        let resolved: serde_json::Value = serde_json::from_str(
+            get_logs().iter().filter_map(|log| log.strip_prefix("EVENT_JSON:")).find(|log| log.contains("bounty_dispute_resolved")).unwrap()
+        ).unwrap();
+        assert_eq!(resolved["data"]["recipients"], serde_json::json!([accounts(0)]));
+        assert_eq!(resolved["data"]["amount"], parse_near!("0.5N").to_string(), "Only the bond goes anywhere when the re-run fails");
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(bounty.dispute_status, DisputeStatus::Upheld);
+        assert_eq!(bounty.amt_dispute_bond, 0);
+        let node_id = bounty.get_payout_recipient_ids()[0].clone();
+        assert_eq!(bounty.get_amt_payout_for_node(&node_id), DEFAULT_GAS_REIMBURSEMENT + bounty.get_amt_reward_for_node(&node_id));
+    }
+
+    #[test]
+    fn rerun_is_never_handed_back_to_the_original_nodes() {
+        let mut coordinator = Coordinator::default();
+        let (bounty, rerun) = create_disputed_bounty(&mut coordinator);
+        assert!(bounty.elected_nodes.iter().all(|node_id| rerun.excluded_nodes.contains(node_id)));
+
+        // Only the original nodes haven't been elected for the re-run, so the rejection stands without a replacement
+        set_context(accounts(1), 0);
+        coordinator.reject_bounty(rerun.id.clone(), rerun.elected_nodes[0].clone(), "busy".to_string());
+        let replaced = coordinator.get_bounty(rerun.id.clone());
+        assert_eq!(replaced.elected_nodes, rerun.elected_nodes);
+        assert_eq!(replaced.replacements, 0);
+        assert!(replaced.elected_nodes.iter().all(|node_id| !bounty.elected_nodes.contains(node_id)));
+    }
+
+    #[test]
+    fn stalled_rerun_can_be_expired_after_the_timeout() {
+        let mut coordinator = Coordinator::default();
+        let (bounty, rerun) = create_disputed_bounty(&mut coordinator);
+        set_context_at(accounts(2), 0, RERUN_TIMEOUT_SECONDS * 1_000_000_000);
+        coordinator.expire_rerun(rerun.id.clone());
+
+        assert_eq!(coordinator.get_bounty(rerun.id.clone()).status, BountyStatus::Cancelled);
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(bounty.dispute_status, DisputeStatus::Upheld);
+        assert_eq!(bounty.amt_dispute_bond, 0);
+        assert!(!bounty.is_payout_locked());
+    }
+
+    #[test]
+    #[should_panic(expected = "to close")]
+    fn rerun_cannot_be_expired_early() {
+        let mut coordinator = Coordinator::default();
+        let (_, rerun) = create_disputed_bounty(&mut coordinator);
+        set_context_at(accounts(2), 0, (RERUN_TIMEOUT_SECONDS - 1) * 1_000_000_000);
+        coordinator.expire_rerun(rerun.id.clone());
+    }
+
+    // Coordinator that audits every successful bounty, with 6 nodes and enough in the pool for one audit
+    fn create_auditing_coordinator() -> Coordinator {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        coordinator.set_audit_rate_bps(10_000);
+        coordinator.audit_pool = parse_near!("2N");
+        register_nodes(&mut coordinator, accounts(1), 6);
+        return coordinator;
+    }
+
+    // Closes a successful bounty and returns it with the audit that spawned
+    fn create_audited_bounty(coordinator: &mut Coordinator) -> (Bounty, Bounty) {
+        let bounty = create_test_bounty(coordinator, accounts(0), 2, None);
+        let bounty = answer_all(coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+        assert_eq!(coordinator.get_bounty_count(), 2, "Closing a successful bounty should have spawned an audit");
+        let audit = coordinator.get_bounties().into_iter().find(|b| b.id != bounty.id).unwrap();
+        return (bounty, audit);
+    }
+
+    #[test]
+    fn audit_mismatch_is_recorded_against_original_nodes() {
+        let mut coordinator = create_auditing_coordinator();
+        let (bounty, audit) = create_audited_bounty(&mut coordinator);
+        assert_eq!(audit.owner_id, bounty.owner_id, "Audit should look like it came from the original owner");
+        assert!(audit.elected_nodes.iter().all(|node_id| !bounty.elected_nodes.contains(node_id)));
+        for node_id in audit.elected_nodes.iter().take(2) {
+            coordinator.post_answer(audit.id.clone(), node_id.clone(), "43".to_string(), "".to_string(), NodeResponseStatus::SUCCESS);
+        }
+
+        for node_id in bounty.successful_nodes.iter() {
+            assert_eq!(coordinator.get_node(node_id).audits_failed, 1);
+        }
+    }
+
+    #[test]
+    fn audits_are_listed_under_the_coordinator() {
+        let mut coordinator = create_auditing_coordinator();
+        let (bounty, audit) = create_audited_bounty(&mut coordinator);
+        let owner_bounties: Vec<AccountId> = coordinator.get_bounties_for_owner(accounts(0)).into_iter().map(|b| b.id).collect();
+        assert_eq!(owner_bounties, vec![bounty.id.clone()]);
+        assert_eq!(coordinator.get_active_bounty_count_for_owner(accounts(0)), 0);
+        assert_eq!(coordinator.get_active_bounty_count_for_owner(current_account_id()), 1);
+
+        set_context(accounts(0), 0);
+        coordinator.cancel_all_my_bounties();
+        assert_eq!(coordinator.get_bounty(audit.id.clone()).status, BountyStatus::Pending);
+    }
+
+    #[test]
+    fn audit_is_never_handed_back_to_the_audited_nodes() {
+        let mut coordinator = create_auditing_coordinator();
+        let (bounty, audit) = create_audited_bounty(&mut coordinator);
+        assert!(bounty.elected_nodes.iter().all(|node_id| audit.excluded_nodes.contains(node_id)));
+
+        // Only the audited nodes haven't been elected for the audit, so the rejection stands without a replacement
+        set_context(accounts(1), 0);
+        coordinator.reject_bounty(audit.id.clone(), audit.elected_nodes[0].clone(), "busy".to_string());
+        let replaced = coordinator.get_bounty(audit.id.clone());
+        assert_eq!(replaced.elected_nodes, audit.elected_nodes);
+        assert_eq!(replaced.replacements, 0);
+    }
+
+    #[test]
+    fn audit_rate_is_rolled_on_the_whole_seed() {
+        let mut coordinator = create_auditing_coordinator();
+        set_context("coordinator.near".parse().unwrap(), 0);
+        coordinator.set_audit_rate_bps(100);
+        // Multiplying this seed's bytes together rolls 0, which passes any audit rate
+        let mut seed = [1u8; 32];
+        seed[..8].copy_from_slice(&5_000u64.to_le_bytes());
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id("coordinator.near".parse().unwrap())
+            .signer_account_id(accounts(1))
+            .predecessor_account_id(accounts(1))
+            .random_seed(seed)
+            .build());
+        for node_id in bounty.elected_nodes.iter().take(2) {
+            coordinator.post_answer(bounty.id.clone(), node_id.clone(), "42".to_string(), "".to_string(), NodeResponseStatus::SUCCESS);
+        }
+        assert_eq!(coordinator.get_bounty(bounty.id.clone()).status, BountyStatus::Success);
+        assert_eq!(coordinator.get_bounty_count(), 1, "A roll of 5000 shouldn't pass a 100 bps audit rate");
+    }
+
+    #[test]
+    fn failed_audit_marks_nobody() {
+        let mut coordinator = create_auditing_coordinator();
+        let (bounty, audit) = create_audited_bounty(&mut coordinator);
+        answer_all(&mut coordinator, &audit, accounts(1), vec![NodeResponseStatus::FAILURE, NodeResponseStatus::FAILURE]);
+
+        for node_id in bounty.successful_nodes.iter() {
+            let node = coordinator.get_node(node_id);
+            assert_eq!((node.audits_passed, node.audits_failed), (0, 0));
+        }
+    }
+
+    #[test]
+    fn audit_is_skipped_when_its_nodes_cannot_be_elected() {
+        let mut coordinator = create_auditing_coordinator();
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        // Enough nodes are registered for a disjoint set, but none of the others take the owner's bounties
+        set_context(accounts(1), 0);
+        for node_id in coordinator.node_queue.clone().iter().filter(|node_id| !bounty.elected_nodes.contains(node_id)) {
+            coordinator.set_node_rules(node_id.clone(), AcceptanceRules { denied_owners: vec![accounts(0)], ..Default::default() });
+        }
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+
+        assert_eq!(bounty.status, BountyStatus::Success);
+        assert_eq!(coordinator.get_bounty_count(), 1);
+        assert_eq!(coordinator.get_audit_pool(), parse_near!("2N"));
+    }
+
+    #[test]
+    fn stalled_audit_can_be_expired_back_into_the_pool() {
+        let mut coordinator = create_auditing_coordinator();
+        let (bounty, audit) = create_audited_bounty(&mut coordinator);
+        let pool = coordinator.get_audit_pool();
+        set_context_at(accounts(2), 0, RERUN_TIMEOUT_SECONDS * 1_000_000_000);
+        coordinator.expire_rerun(audit.id.clone());
+
+        assert_eq!(coordinator.get_bounty(audit.id.clone()).status, BountyStatus::Cancelled);
+        assert!(coordinator.get_audit_pool() > pool, "The unused audit funds should go back to the pool");
+        for node_id in bounty.successful_nodes.iter() {
+            assert_eq!(coordinator.get_node(node_id).audits_failed, 0);
+        }
+    }
+
+    #[test]
+    fn nodes_priced_above_the_reward_per_node_are_not_elected() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 6);
+        set_context(accounts(1), 0);
+        for node_id in &node_ids[0..3] {
+            coordinator.set_node_price(node_id.clone(), parse_near!("0.1N").to_string(), parse_near!("0.01N").to_string());
+        }
+        // 3 nodes are elected for min_nodes = 2, so each is offered 0.33N. The priced nodes ask 0.4N for 30 seconds, 0.2N for 10
+        assert_eq!(coordinator.get_accepting_node_count(parse_near!("1N").to_string(), 2, 30, false, false), 3);
+        assert_eq!(coordinator.get_accepting_node_count(parse_near!("1N").to_string(), 2, 10, false, false), 6);
+
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        assert_eq!(bounty.elected_nodes.len(), 3);
+        assert!(bounty.elected_nodes.iter().all(|node_id| !node_ids[0..3].contains(node_id)), "Priced nodes should not be elected");
+    }
+
+    fn create_auction_bounty(coordinator: &mut Coordinator, bidding_window_seconds: u64) -> Bounty {
+        set_context(accounts(0), parse_near!("2N"));
+        return coordinator.create_bounty(
+            "https://github.com/ad0ll/docker-hello-world.git".to_string(),
+            SupportedDownloadProtocols::GIT,
+            2,
+            30,
+            false,
+            false,
+            parse_near!("1N").to_string(),
+            parse_near!("1N").to_string(),
+            Some(BountyOptions {
+                bidding_window_seconds: Some(bidding_window_seconds),
+                max_nodes_per_owner: Some(0),
+                ..Default::default()
+            }),
+        );
+    }
+
+    #[test]
+    fn auction_elects_the_cheapest_bids_and_refunds_the_rest() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 6);
+        let bounty = create_auction_bounty(&mut coordinator, 60);
+        assert!(bounty.elected_nodes.is_empty(), "Auctions shouldn't elect nodes until bidding closes");
+        assert_eq!(bounty.reward_policy, RewardPolicy::Bid);
+
+        set_context(accounts(1), 0);
+        let prices = [parse_near!("0.3N"), parse_near!("0.1N"), parse_near!("0.25N"), parse_near!("0.2N")];
+        for (node_id, price) in node_ids.iter().zip(prices) {
+            coordinator.bid_on_bounty(bounty.id.clone(), node_id.clone(), price.to_string());
+        }
+        // Bidding again replaces the earlier bid
+        coordinator.bid_on_bounty(bounty.id.clone(), node_ids[0].clone(), parse_near!("0.05N").to_string());
+
+        set_context_at(accounts(2), 0, 61_000_000_000);
+        coordinator.award_bounty(bounty.id.clone());
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(bounty.elected_nodes, vec![node_ids[0].clone(), node_ids[1].clone(), node_ids[3].clone()]);
+        let amt_node_reward = parse_near!("0.35N") + 3 * DEFAULT_GAS_REIMBURSEMENT;
+        assert_eq!(bounty.amt_node_reward, amt_node_reward);
+        assert_eq!(bounty.escrow.refunded, parse_near!("1N") - amt_node_reward);
+
+        let bounty = answer_all(&mut coordinator, &bounty, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+        assert_eq!(bounty.status, BountyStatus::Success);
+        assert_eq!(bounty.get_amt_payout_for_node(&node_ids[0]), DEFAULT_GAS_REIMBURSEMENT + parse_near!("0.05N"));
+        assert_eq!(bounty.get_amt_payout_for_node(&node_ids[1]), DEFAULT_GAS_REIMBURSEMENT + parse_near!("0.1N"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Bounty was auctioned, only nodes that bid can work on it")]
+    fn awarded_auction_is_not_reelected() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 6);
+        let bounty = create_auction_bounty(&mut coordinator, 60);
+        set_context(accounts(1), 0);
+        for node_id in node_ids[0..3].iter() {
+            coordinator.bid_on_bounty(bounty.id.clone(), node_id.clone(), parse_near!("0.1N").to_string());
+        }
+        set_context_at(accounts(2), 0, 61_000_000_000);
+        coordinator.award_bounty(bounty.id.clone());
+
+        // None of the winners answered within the 30 second timeout
+        set_context_at(accounts(0), 0, 100_000_000_000);
+        coordinator.reelect_unanswered_nodes(bounty.id.clone());
+    }
+
+    #[test]
+    fn auction_without_enough_bids_is_cancelled() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 6);
+        let bounty = create_auction_bounty(&mut coordinator, 60);
+        set_context(accounts(1), 0);
+        coordinator.bid_on_bounty(bounty.id.clone(), node_ids[0].clone(), parse_near!("0.1N").to_string());
+
+        set_context_at(accounts(2), 0, 61_000_000_000);
+        coordinator.award_bounty(bounty.id.clone());
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(bounty.status, BountyStatus::Cancelled);
+        assert!(bounty.elected_nodes.is_empty());
+        assert_eq!(bounty.escrow.remaining, bounty.get_amt_storage_used());
+    }
+
+    #[test]
+    #[should_panic(expected = "Bid cannot be more than")]
+    fn cannot_bid_more_than_the_reward_per_node() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 6);
+        let bounty = create_auction_bounty(&mut coordinator, 60);
+        set_context(accounts(1), 0);
+        coordinator.bid_on_bounty(bounty.id.clone(), node_ids[0].clone(), parse_near!("0.4N").to_string());
+    }
+
+    fn create_typed_bounty(coordinator: &mut Coordinator, result_type: ResultType, aggregation: AggregationFunction) -> Bounty {
+        set_context(accounts(0), parse_near!("2N"));
+        return coordinator.create_bounty(
+            "https://github.com/ad0ll/docker-hello-world.git".to_string(),
+            SupportedDownloadProtocols::GIT,
+            3,
+            30,
+            false,
+            false,
+            parse_near!("1N").to_string(),
+            parse_near!("1N").to_string(),
+            Some(BountyOptions {
+                result_type: Some(result_type),
+                aggregation: Some(aggregation),
+                max_nodes_per_owner: Some(0),
+                ..Default::default()
+            }),
+        );
+    }
+
+    #[test]
+    fn decimal_median_bounty_agrees_within_tolerance() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 6);
+        let bounty = create_typed_bounty(&mut coordinator, ResultType::Decimal { tolerance: "0.01".to_string() }, AggregationFunction::Median);
+        set_context(accounts(1), 0);
+        for (node_id, answer) in bounty.elected_nodes.iter().zip(["1.0", "1.005", "2.0"]) {
+            coordinator.post_answer(bounty.id.clone(), node_id.clone(), answer.to_string(), "".to_string(), NodeResponseStatus::SUCCESS);
+        }
+
+        let result = coordinator.get_bounty_aggregate_result(bounty.id.clone());
+        assert_eq!(result.canonical_result, Some("1.005".to_string()));
+        assert_eq!(result.agreeing_nodes, bounty.elected_nodes[0..2].to_vec());
+        assert_eq!(result.disagreeing_nodes, vec![bounty.elected_nodes[2].clone()]);
+    }
+
+    #[test]
+    fn disagreeing_node_is_not_rewarded() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 6);
+        let bounty = create_typed_bounty(&mut coordinator, ResultType::Decimal { tolerance: "0.01".to_string() }, AggregationFunction::Median);
+        set_context(accounts(1), 0);
+        for (node_id, answer) in bounty.elected_nodes.iter().zip(["1.0", "1.005", "2.0"]) {
+            coordinator.post_answer(bounty.id.clone(), node_id.clone(), answer.to_string(), "".to_string(), NodeResponseStatus::SUCCESS);
+        }
+        // Three successful answers, but only two of them agree, so min_nodes = 3 isn't met yet
+        assert_eq!(coordinator.get_bounty(bounty.id.clone()).status, BountyStatus::Pending);
+        coordinator.post_answer(bounty.id.clone(), bounty.elected_nodes[3].clone(), "1.0".to_string(), "".to_string(), NodeResponseStatus::SUCCESS);
+
+        let bounty = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(bounty.status, BountyStatus::Success);
+        let disagreeing = bounty.elected_nodes[2].clone();
+        assert!(!bounty.get_payout_recipient_ids().contains(&disagreeing));
+        assert_eq!(bounty.get_payout_recipient_ids().len(), 3);
+        assert_eq!(bounty.get_amt_reward_for_node(&disagreeing), 0);
+        assert_eq!(bounty.get_amt_payout_for_node(&disagreeing), bounty.amt_gas_reimbursement);
+        assert!(bounty.get_amt_reward_for_node(&bounty.elected_nodes[0]) > 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Aggregation Median can't be used with result type Json")]
+    fn cannot_create_bounty_with_unsupported_aggregation() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 6);
+        create_typed_bounty(&mut coordinator, ResultType::Json, AggregationFunction::Median);
+    }
+
+    #[test]
+    #[should_panic(expected = "bob already has the maximum of 2 nodes")]
+    fn cannot_register_more_nodes_than_the_owner_quota() {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        coordinator.set_owner_quotas(accounts(1), Some(Quotas { max_nodes_per_owner: 2, ..Quotas::default() }));
+        register_nodes(&mut coordinator, accounts(1), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "alice already has the maximum of 1 active bounties")]
+    fn cannot_create_more_active_bounties_than_the_quota() {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        coordinator.set_quotas(0, 1, 10_000);
+        register_nodes(&mut coordinator, accounts(1), 6);
+        create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        create_test_bounty(&mut coordinator, accounts(0), 2, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "alice already has the maximum of 1 active bounties")]
+    fn cannot_dispute_past_the_active_bounty_quota() {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        coordinator.set_quotas(0, 1, 10_000);
+        let bounty = create_disputable_bounty(&mut coordinator);
+        create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        // The re-run would be alice's second active bounty
+        set_context(accounts(0), parse_near!("2.5N"));
+        coordinator.dispute_bounty(bounty.id.clone(), parse_near!("1N").to_string(), parse_near!("1N").to_string());
+    }
+
+    #[test]
+    fn elected_share_limits_how_many_nodes_one_owner_gets() {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        // 3 nodes are elected for min_nodes = 2, so no owner can have more than 2 of them
+        coordinator.set_quotas(0, 0, 6_700);
+        let mut node_ids = register_nodes(&mut coordinator, accounts(1), 4);
+        node_ids.extend(register_nodes(&mut coordinator, accounts(2), 4));
+
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        assert_eq!(bounty.elected_nodes.len(), 3);
+        for owner_id in [accounts(1), accounts(2)] {
+            let owned = bounty.elected_nodes.iter().filter(|node_id| coordinator.get_node((*node_id).clone()).owner_id == owner_id).count();
+            assert!(owned <= 2, "{} has {} of the elected nodes", owner_id, owned);
+        }
+    }
+
+    #[test]
+    fn quota_usage_reports_nodes_and_active_bounties() {
+        set_context("coordinator.near".parse().unwrap(), 0);
+        let mut coordinator = Coordinator::default();
+        coordinator.set_owner_quotas(accounts(1), Some(Quotas { max_nodes_per_owner: 10, ..Quotas::default() }));
+        register_nodes(&mut coordinator, accounts(1), 6);
+        create_test_bounty(&mut coordinator, accounts(0), 2, None);
+
+        let usage = coordinator.get_quota_usage(accounts(1));
+        assert!(usage.overridden);
+        assert_eq!(usage.nodes, 6);
+        assert_eq!(usage.quotas.max_nodes_per_owner, 10);
+        let usage = coordinator.get_quota_usage(accounts(0));
+        assert!(!usage.overridden);
+        assert_eq!(usage.active_bounties, 1);
+    }
+
+    fn create_diverse_bounty(coordinator: &mut Coordinator, max_nodes_per_owner: Option<u64>) -> Bounty {
+        set_context(accounts(0), parse_near!("2N"));
+        return coordinator.create_bounty(
+            "https://github.com/ad0ll/docker-hello-world.git".to_string(),
+            SupportedDownloadProtocols::GIT,
+            2,
+            30,
+            false,
+            false,
+            parse_near!("1N").to_string(),
+            parse_near!("1N").to_string(),
+            Some(BountyOptions {
+                max_nodes_per_owner,
+                ..Default::default()
+            }),
+        );
+    }
+
+    fn owner_counts(coordinator: &Coordinator, bounty: &Bounty) -> HashMap<AccountId, u64> {
+        let mut counts: HashMap<AccountId, u64> = HashMap::new();
+        for node_id in bounty.elected_nodes.iter() {
+            *counts.entry(coordinator.get_node(node_id.clone()).owner_id).or_insert(0) += 1;
+        }
+        return counts;
+    }
+
+    #[test]
+    fn elected_nodes_have_different_owners_by_default() {
+        let mut coordinator = Coordinator::default();
+        for owner in 1..5 {
+            register_nodes(&mut coordinator, accounts(owner), 2);
+        }
+        let bounty = create_diverse_bounty(&mut coordinator, None);
+        assert_eq!(bounty.max_nodes_per_owner, 1);
+        assert_eq!(bounty.elected_nodes.len(), 3);
+        assert!(owner_counts(&coordinator, &bounty).values().all(|count| *count == 1));
+    }
+
+    #[test]
+    fn bounty_can_let_an_owner_have_several_elected_nodes() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 3);
+        register_nodes(&mut coordinator, accounts(2), 3);
+        let bounty = create_diverse_bounty(&mut coordinator, Some(2));
+        assert_eq!(bounty.elected_nodes.len(), 3);
+        assert!(owner_counts(&coordinator, &bounty).values().all(|count| *count <= 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough qualified nodes with different owners to fill bounty, at most 1 of its nodes can share an owner")]
+    fn cannot_fill_bounty_from_a_single_owner() {
+        let mut coordinator = Coordinator::default();
+        register_nodes(&mut coordinator, accounts(1), 6);
+        create_diverse_bounty(&mut coordinator, None);
+    }
+
+    fn create_pool_bounty(coordinator: &mut Coordinator, owner: AccountId, pool_id: &str) -> Bounty {
+        set_context(owner, parse_near!("2N"));
+        return coordinator.create_bounty(
+            "https://github.com/ad0ll/docker-hello-world.git".to_string(),
+            SupportedDownloadProtocols::GIT,
+            2,
+            30,
+            false,
+            false,
+            parse_near!("1N").to_string(),
+            parse_near!("1N").to_string(),
+            Some(BountyOptions {
+                pool_id: Some(pool_id.to_string()),
+                ..Default::default()
+            }),
+        );
+    }
+
+    // accounts(0) owns the pool, the first node of each of accounts(1) to accounts(member_owners) joins it
+    fn setup_pool(coordinator: &mut Coordinator, member_owners: usize) -> Vec<AccountId> {
+        let mut members = vec![];
+        for owner in 1..5 {
+            let node_ids = register_nodes(coordinator, accounts(owner), 2);
+            if owner <= member_owners {
+                members.push(node_ids[0].clone());
+            }
+        }
+        set_context(accounts(0), parse_near!("0.1N"));
+        coordinator.create_pool("trusted".to_string());
+        for node_id in &members {
+            set_context(accounts(0), parse_near!("0.1N"));
+            coordinator.invite_to_pool("trusted".to_string(), node_id.clone());
+            set_context(coordinator.get_node(node_id.clone()).owner_id, 0);
+            coordinator.join_pool("trusted".to_string(), node_id.clone());
+        }
+        return members;
+    }
+
+    #[test]
+    fn pool_bounty_only_elects_pool_members() {
+        let mut coordinator = Coordinator::default();
+        let members = setup_pool(&mut coordinator, 3);
+        set_context(accounts(0), parse_near!("0.1N"));
+        let pending = coordinator.get_nodes_for_owner(accounts(4))[0].id.clone();
+        coordinator.invite_to_pool("trusted".to_string(), pending.clone());
+
+        let bounty = create_pool_bounty(&mut coordinator, accounts(0), "trusted");
+        assert_eq!(bounty.elected_nodes.len(), 3);
+        assert!(bounty.elected_nodes.iter().all(|node_id| members.contains(node_id)));
+        assert_eq!(coordinator.get_pools_for_node(pending).len(), 1);
+
+        let stats = coordinator.get_pool_stats("trusted".to_string());
+        assert_eq!((stats.members, stats.online_members, stats.invited), (3, 3, 1));
+        assert_eq!((stats.total_bounties, stats.active_bounties), (1, 1));
+    }
+
+    #[test]
+    fn removed_node_leaves_its_pools() {
+        let mut coordinator = Coordinator::default();
+        let members = setup_pool(&mut coordinator, 4);
+        set_context(accounts(1), 0);
+        coordinator.remove_node(members[0].clone());
+        assert!(coordinator.get_pools_for_node(members[0].clone()).is_empty());
+        let stats = coordinator.get_pool_stats("trusted".to_string());
+        assert_eq!((stats.members, stats.online_members), (3, 3));
+
+        let bounty = create_pool_bounty(&mut coordinator, accounts(0), "trusted");
+        assert_eq!(bounty.elected_nodes.len(), 3);
+        assert!(!bounty.elected_nodes.contains(&members[0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough qualified nodes in pool trusted to fill bounty")]
+    fn pool_bounty_cannot_be_filled_from_outside_the_pool() {
+        let mut coordinator = Coordinator::default();
+        setup_pool(&mut coordinator, 2);
+        create_pool_bounty(&mut coordinator, accounts(0), "trusted");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner of pool trusted can create bounties against it")]
+    fn only_the_pool_owner_can_target_a_pool() {
+        let mut coordinator = Coordinator::default();
+        setup_pool(&mut coordinator, 3);
+        create_pool_bounty(&mut coordinator, accounts(5), "trusted");
+    }
+
+    fn create_excluding_bounty(coordinator: &mut Coordinator, excluded_nodes: Vec<AccountId>) -> Bounty {
+        set_context(accounts(0), parse_near!("2N"));
+        return coordinator.create_bounty(
+            "https://github.com/ad0ll/docker-hello-world.git".to_string(),
+            SupportedDownloadProtocols::GIT,
+            2,
+            30,
+            false,
+            false,
+            parse_near!("1N").to_string(),
+            parse_near!("1N").to_string(),
+            Some(BountyOptions {
+                excluded_nodes: Some(excluded_nodes),
+                ..Default::default()
+            }),
+        );
+    }
+
+    #[test]
+    fn blocked_and_excluded_nodes_are_not_elected() {
+        let mut coordinator = Coordinator::default();
+        let mut node_ids = vec![];
+        for owner in 1..6 {
+            node_ids.push(register_nodes(&mut coordinator, accounts(owner), 2));
+        }
+        set_context(accounts(0), parse_near!("0.1N"));
+        let blocklist = coordinator.add_to_blocklist(Some(vec![node_ids[1][0].clone()]), Some(vec![accounts(1)]));
+        assert_eq!(blocklist, coordinator.get_blocklist(accounts(0)));
+
+        let bounty = create_excluding_bounty(&mut coordinator, vec![node_ids[2][0].clone()]);
+        assert_eq!(bounty.elected_nodes.len(), 3);
+        for node_id in bounty.elected_nodes.iter() {
+            assert_ne!(coordinator.get_node(node_id.clone()).owner_id, accounts(1));
+            assert!(node_id != &node_ids[1][0] && node_id != &node_ids[2][0], "{} should not be elected", node_id);
+        }
+
+        set_context(accounts(0), 0);
+        let blocklist = coordinator.remove_from_blocklist(None, Some(vec![accounts(1)]));
+        assert_eq!(blocklist.node_ids, vec![node_ids[1][0].clone()]);
+        assert!(blocklist.owner_ids.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit must cover")]
+    fn blocklist_storage_is_paid_by_the_owner() {
+        let mut coordinator = Coordinator::default();
+        set_context(accounts(0), 0);
+        coordinator.add_to_blocklist(None, Some(vec![accounts(1)]));
+    }
+
+    #[test]
+    fn nodes_are_not_elected_for_bounties_their_rules_refuse() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 8);
+        let refusing = vec![
+            AcceptanceRules { denied_owners: vec![accounts(0)], ..Default::default() },
+            AcceptanceRules { allowed_owners: vec![accounts(2)], ..Default::default() },
+            AcceptanceRules { max_timeout_seconds: 10, ..Default::default() },
+            AcceptanceRules { require_pinned_package: true, ..Default::default() },
+            AcceptanceRules { allowed_protocols: vec![SupportedDownloadProtocols::IPFS], ..Default::default() },
+        ];
+        set_context(accounts(1), 0);
+        for (node_id, rules) in node_ids.iter().zip(refusing) {
+            assert_eq!(coordinator.set_node_rules(node_id.clone(), rules.clone()).acceptance_rules, rules);
+        }
+        // Rules that allow the bounty don't get in the way
+        coordinator.set_node_rules(
+            node_ids[5].clone(),
+            AcceptanceRules { allowed_owners: vec![accounts(0)], max_timeout_seconds: 30, ..Default::default() },
+        );
+
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        let mut elected = bounty.elected_nodes.clone();
+        elected.sort();
+        assert_eq!(elected, node_ids[5..].to_vec());
+    }
+
+    #[test]
+    fn rejecting_a_bounty_elects_a_replacement() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 4);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        let spare = node_ids.iter().find(|node_id| !bounty.elected_nodes.contains(node_id)).unwrap().clone();
+
+        set_context(accounts(1), 0);
+        coordinator.reject_bounty(bounty.id.clone(), bounty.elected_nodes[0].clone(), "busy".to_string());
+        let retry: serde_json::Value = serde_json::from_str(get_logs().last().unwrap().strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+        assert_eq!(retry["event"], "bounty_retry");
+        assert_eq!(retry["data"]["node_ids"], serde_json::json!([spare]));
+        assert_eq!(retry["data"]["replaced_node_ids"], serde_json::json!([bounty.elected_nodes[0]]));
+        let replaced = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(replaced.elected_nodes.len(), 3, "The replacement should take the rejecter's slot");
+        assert_eq!(replaced.elected_nodes[0], spare);
+        assert_eq!(replaced.replaced_nodes, vec![bounty.elected_nodes[0].clone()]);
+        assert_eq!(replaced.replacements, 1);
+        assert!(replaced.unanswered_nodes.contains(&spare));
+        assert!(!replaced.unanswered_nodes.contains(&bounty.elected_nodes[0]));
+        assert_eq!(replaced.get_reimbursed_node_ids(), vec![bounty.elected_nodes[0].clone()], "The swapped out rejecter is still reimbursed");
+
+        // Every node has been elected for the bounty now, so the rejection stands without a replacement
+        set_context(accounts(1), 0);
+        coordinator.reject_bounty(bounty.id.clone(), spare.clone(), "busy".to_string());
+        assert_eq!(event_names(), vec!["bounty_rejected"]);
+        let replaced = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(replaced.elected_nodes.len(), 3);
+        assert_eq!(replaced.get_reimbursed_node_ids(), vec![spare, bounty.elected_nodes[0].clone()]);
+        assert_eq!(replaced.replacements, 1);
+        assert_eq!(coordinator.node_queue.len(), 4, "A failed replacement leaves the queue as it was");
+    }
+
+    #[test]
+    fn rejecting_through_post_answer_elects_a_replacement() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 4);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        let spare = node_ids.iter().find(|node_id| !bounty.elected_nodes.contains(node_id)).unwrap().clone();
+        let rejecter = bounty.elected_nodes[0].clone();
+
+        set_context(accounts(1), 0);
+        coordinator.post_answer(bounty.id.clone(), rejecter.clone(), "".to_string(), "busy".to_string(), NodeResponseStatus::REJECT);
+        let replaced = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(replaced.elected_nodes[0], spare);
+        assert_eq!(replaced.replaced_nodes, vec![rejecter.clone()]);
+
+        let closed = answer_all(&mut coordinator, &replaced, accounts(1), vec![NodeResponseStatus::SUCCESS, NodeResponseStatus::SUCCESS]);
+        assert_eq!(closed.status, BountyStatus::Success);
+        assert_eq!(closed.get_amt_payout_for_node(&rejecter), closed.amt_gas_reimbursement);
+    }
+
+    #[test]
+    #[should_panic(expected = "You are not an elected node")]
+    fn only_elected_nodes_can_reject_a_bounty() {
+        let mut coordinator = Coordinator::default();
+        let node_ids = register_nodes(&mut coordinator, accounts(1), 4);
+        let bounty = create_test_bounty(&mut coordinator, accounts(0), 2, None);
+        let spare = node_ids.iter().find(|node_id| !bounty.elected_nodes.contains(node_id)).unwrap().clone();
+        set_context(accounts(1), 0);
+        coordinator.reject_bounty(bounty.id.clone(), spare, "busy".to_string());
+    }
+
+    // Bounty with three elected nodes out of n, where only the first answers
+    fn create_stalled_bounty(coordinator: &mut Coordinator, n: u64) -> Bounty {
+        register_nodes(coordinator, accounts(1), n);
+        let bounty = create_test_bounty(coordinator, accounts(0), 2, None);
+        set_context(accounts(1), 0);
+        coordinator.post_answer(bounty.id.clone(), bounty.elected_nodes[0].clone(), "42".to_string(), "".to_string(), NodeResponseStatus::SUCCESS);
+        return coordinator.get_bounty(bounty.id.clone());
+    }
+
+    #[test]
+    fn keepers_reelect_timed_out_nodes_after_the_deadline() {
+        let mut coordinator = Coordinator::default();
+        let bounty = create_stalled_bounty(&mut coordinator, 6);
+        let timed_out = bounty.elected_nodes[1..].to_vec();
+        set_context(coordinator.get_admin(), 0);
+        assert_eq!(coordinator.add_keeper(accounts(2)), vec![accounts(2)]);
+
+        set_context_at(accounts(2), 0, 30_000_000_000);
+        coordinator.reelect_unanswered_nodes(bounty.id.clone());
+        let reelected = coordinator.get_bounty(bounty.id.clone());
+        assert_eq!(reelected.elected_nodes.len(), 3);
+        assert_eq!(reelected.elected_nodes[0], bounty.elected_nodes[0]);
+        assert_eq!(reelected.replaced_nodes, timed_out);
+        assert_eq!(reelected.reelection_rounds, 1);
+        for node_id in timed_out.iter() {
+            assert!(!reelected.elected_nodes.contains(node_id), "{} timed out and should not be elected again", node_id);
+            assert_eq!(coordinator.get_node(node_id.clone()).unanswered_runs, 1);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Nodes have until 30000 to answer bounty")]
+    fn cannot_reelect_before_the_response_deadline() {
+        let mut coordinator = Coordinator::default();
+        let bounty = create_stalled_bounty(&mut coordinator, 6);
+        set_context_at(accounts(0), 0, 29_000_000_000);
+        coordinator.reelect_unanswered_nodes(bounty.id.clone());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the bounty owner or a keeper can reelect nodes")]
+    fn only_the_owner_or_a_keeper_can_reelect() {
+        let mut coordinator = Coordinator::default();
+        let bounty = create_stalled_bounty(&mut coordinator, 6);
+        set_context_at(accounts(2), 0, 30_000_000_000);
+        coordinator.reelect_unanswered_nodes(bounty.id.clone());
+    }
+
+    #[test]
+    #[should_panic(expected = "has already been reelected 3 times")]
+    fn bounty_can_only_be_reelected_a_few_times() {
+        let mut coordinator = Coordinator::default();
+        let bounty = create_stalled_bounty(&mut coordinator, 9);
+        for round in 1..=MAX_REELECTION_ROUNDS + 1 {
+            set_context_at(accounts(0), 0, round * 30_000_000_000);
+            coordinator.reelect_unanswered_nodes(bounty.id.clone());
+        }
+    }
+
     // #[test]
     // fn can_register_bounty() {
     //     let mut coordinator = Coordinator::default();
@@ -1,15 +1,18 @@
 use std::fmt;
 
 use near_sdk::{AccountId, serde_json};
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
-use crate::bounty::BountyStatus;
+use crate::bounty::{Bid, BountyStatus, NodeResponseStatus, RewardPolicy, SupportedDownloadProtocols};
 use crate::coordinator::PayoutStrategy;
+use crate::node::AcceptanceRules;
+use crate::pool::PoolMembershipChange;
 
 // This is heavily influenced by: https://github.com/near-examples/nft-tutorial/blob/7.events/nft-contract/src/events.rs#L1-L79
 
 
 /// Enum that represents the data type of the EventLog.
-/// The enum can either be an NftMint or an NftTransfer.
+/// There is one variant for every state transition of a node or a bounty.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "event", content = "data")]
 #[serde(rename_all = "snake_case")]
@@ -19,6 +22,22 @@ pub enum EventLogVariant {
     BountyCreated(BountyCreatedLog),
     BountyRetry(BountyRetryLog),
     BountyCompleted(BountyCompletedLog),
+    BountyDisputed(BountyDisputedLog),
+    BountyDisputeResolved(BountyDisputeResolvedLog),
+    BountyAuditMismatch(BountyAuditMismatchLog),
+    BountyRejected(BountyRejectedLog),
+    BountyDepositAdded(BountyDepositAddedLog),
+    BountyReelected(BountyReelectedLog),
+    BountyBidPlaced(BountyBidPlacedLog),
+    BountyAwarded(BountyAwardedLog),
+    AnswerPosted(AnswerPostedLog),
+    RewardCollected(RewardCollectedLog),
+    NodeRegistered(NodeRegisteredLog),
+    NodeUpdated(NodeUpdatedLog),
+    NodeRemoved(NodeRemovedLog),
+    NodeStatusChanged(NodeStatusChangedLog),
+    PoolCreated(PoolCreatedLog),
+    PoolMembershipChanged(PoolMembershipChangedLog),
 }
 
 /// Interface to capture data about an event
@@ -26,12 +45,17 @@ pub enum EventLogVariant {
 /// Arguments:
 /// * `standard`: name of standard e.g. nep171
 /// * `version`: e.g. 1.0.0
+/// * `event_nonce`: coordinator-wide counter, incremented for every event. A gap means an event was missed
+/// * `event_seq`: per-bounty counter, only present on events about a bounty
 /// * `event`: associate event data
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct EventLog {
     pub standard: String,
     pub version: String,
+    pub event_nonce: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_seq: Option<u64>,
     // `flatten` to not have "event": {<EventLogVariant>} in the JSON, just have the contents of {<EventLogVariant>}.
     #[serde(flatten)]
     pub event: EventLogVariant,
@@ -47,12 +71,23 @@ impl fmt::Display for EventLog {
 }
 
 /// An event log for when a bounty is created.
-/// Used to let nodes know that there is work to do
+/// Used to let nodes know that there is work to do, and whether they want to do it, without calling get_bounty
 ///
 /// Arguments
 /// * `coordinator_id`: the account id of the coordinator firing this event
 /// * `bounty_id`: "bounty.id.test.near"
 /// * `node_ids`: ["node.id.test.near", "node2.id.test.near"]
+/// * `owner_id`: the account that created the bounty
+/// * `file_location`: where to download the bounty's package from
+/// * `file_download_protocol`: how to download the bounty's package
+/// * `min_nodes`: how many agreeing answers are needed to close the bounty
+/// * `timeout_seconds`: how long nodes have to answer
+/// * `network_required`: whether the bounty needs network access
+/// * `gpu_required`: whether the bounty needs a gpu
+/// * `amt_storage`: storage deposit, as a string
+/// * `amt_node_reward`: reward pool shared by the nodes, as a string
+/// * `reward_policy`: how the reward is divided between recipients
+/// * `bidding_ends_at`: for auctions, when bidding closes (ms). node_ids is empty until the auction is awarded
 /// * `message`: optional message
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(crate = "near_sdk::serde")]
@@ -60,6 +95,18 @@ pub struct BountyCreatedLog {
     pub coordinator_id: AccountId,
     pub bounty_id: AccountId,
     pub node_ids: Vec<AccountId>,
+    pub owner_id: AccountId,
+    pub file_location: String,
+    pub file_download_protocol: SupportedDownloadProtocols,
+    pub min_nodes: u64,
+    pub timeout_seconds: u64,
+    pub network_required: bool,
+    pub gpu_required: bool,
+    pub amt_storage: U128,
+    pub amt_node_reward: U128,
+    pub reward_policy: RewardPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bidding_ends_at: Option<u64>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
@@ -71,6 +118,7 @@ pub struct BountyCreatedLog {
 /// * `coordinator_id`: the account id of the coordinator firing this event
 /// * `bounty_id`: "bounty.id.test.near"
 /// * `node_ids`: nodes elected for the bounty
+/// * `replaced_node_ids`: nodes that rejected the bounty and were swapped out for `node_ids`, empty for other retries
 /// * `message`: optional message
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(crate = "near_sdk::serde")]
@@ -78,6 +126,8 @@ pub struct BountyRetryLog {
     pub coordinator_id: AccountId,
     pub bounty_id: AccountId,
     pub node_ids: Vec<AccountId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replaced_node_ids: Vec<AccountId>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
@@ -92,7 +142,13 @@ pub struct BountyRetryLog {
 /// * `bounty_id`: id of the bounty that was closed
 /// * `node_ids`: All nodes that were elected for the bounty
 /// * `reward_recipients`: Elected nodes that are qualified to receive a reward
+/// * `reward_amounts`: The reward each recipient is owed, excluding gas reimbursement
 /// * `payout_strategy`: The payout strategy used to determine which nodes receive an award
+/// * `reward_policy`: The reward policy used to divide the reward between recipients
+/// * `protocol_fee_bps`: The protocol fee taken from each payout, in basis points
+/// * `fees`: Total protocol fees that will be credited to the treasury as payouts are collected
+/// * `canonical_result`: The result the bounty's aggregation function settled on, if any answer could be parsed
+/// * `agreeing_nodes`: Successful nodes whose answer agrees with the canonical result
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct BountyCompletedLog {
@@ -100,8 +156,384 @@ pub struct BountyCompletedLog {
     pub bounty_id: AccountId,
     pub node_ids: Vec<AccountId>,
     pub reward_recipients: Vec<AccountId>,
+    pub reward_amounts: Vec<RewardAmountLog>,
     pub outcome: BountyStatus,
     pub payout_strategy: PayoutStrategy,
+    pub reward_policy: RewardPolicy,
+    pub protocol_fee_bps: u16,
+    pub fees: U128,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_result: Option<String>,
+    #[serde(default)]
+    pub agreeing_nodes: Vec<AccountId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// The reward owed to a single recipient of a completed bounty
+///
+/// Arguments
+/// * `node_id`: the recipient
+/// * `amount`: yoctoNEAR owed to the recipient, as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RewardAmountLog {
+    pub node_id: AccountId,
+    pub amount: U128,
+}
+
+/// An event log for when a bounty owner disputes a successful bounty
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the disputed bounty
+/// * `rerun_bounty_id`: id of the bounty that re-runs the job on a fresh set of nodes
+/// * `bond`: the dispute bond escrowed by the owner, as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyDisputedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub rerun_bounty_id: AccountId,
+    pub bond: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a disputed bounty's re-run closes
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the disputed bounty
+/// * `rerun_bounty_id`: id of the bounty that re-ran the job on a fresh set of nodes
+/// * `upheld`: true if the re-run agreed with the original nodes
+/// * `recipients`: the original reward recipients if the re-run agreed with them, otherwise the bounty owner
+/// * `amount`: what the recipients get, as a string. The reward and their share of the bond if the re-run agreed, the bond
+///   if the re-run failed or expired, and the reward plus the bond if the bounty was overturned
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyDisputeResolvedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub rerun_bounty_id: AccountId,
+    pub upheld: bool,
+    pub recipients: Vec<AccountId>,
+    pub amount: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a spot-check audit disagrees with the bounty it re-ran
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the audited bounty
+/// * `audit_bounty_id`: id of the audit bounty that re-ran the job on disjoint nodes
+/// * `node_ids`: nodes whose successful answers disagreed with the audit
+/// * `expected`: the audited bounty's consensus solution
+/// * `actual`: the audit's consensus solution, if it had one
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyAuditMismatchLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub audit_bounty_id: AccountId,
+    pub node_ids: Vec<AccountId>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when an elected node rejects a bounty
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the rejected bounty
+/// * `node_id`: the node that rejected it
+/// * `message`: the reason given by the node
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyRejectedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub node_id: AccountId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Which of a bounty's deposits was topped up
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[serde(crate = "near_sdk::serde")]
+pub enum BountyDepositKind {
+    Storage,
+    NodeReward,
+}
+
+/// An event log for when the owner adds to a bounty's storage or reward deposit
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the bounty
+/// * `deposit`: which deposit was topped up
+/// * `amount`: the amount added, as a string
+/// * `total`: the deposit's new total, as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyDepositAddedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub deposit: BountyDepositKind,
+    pub amount: U128,
+    pub total: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when the unanswered nodes of an in-flight bounty are replaced
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the bounty
+/// * `timed_out_node_ids`: elected nodes that hadn't answered and lost their election
+/// * `node_ids`: the newly elected nodes
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyReelectedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub timed_out_node_ids: Vec<AccountId>,
+    pub node_ids: Vec<AccountId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a node bids on an auctioned bounty, or changes its bid
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the auctioned bounty
+/// * `node_id`: the bidding node
+/// * `owner_id`: the node's owner
+/// * `price`: what the node will run the bounty for, in yoctoNEAR
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyBidPlacedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub node_id: AccountId,
+    pub owner_id: AccountId,
+    pub price: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when an auction's bidding closes. The winning nodes should start work, like on bounty_created.
+/// If there weren't enough reputable bids, node_ids is empty and the bounty is cancelled.
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the auctioned bounty
+/// * `node_ids`: the elected nodes, cheapest first
+/// * `winning_bids`: the bids of the elected nodes, what each is paid if it's a reward recipient
+/// * `amt_node_reward`: the bounty's node reward after the award, the winning bids plus gas reimbursements
+/// * `amt_refund`: reward returned to the owner because the winning bids came in under it
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyAwardedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub node_ids: Vec<AccountId>,
+    pub winning_bids: Vec<Bid>,
+    pub amt_node_reward: U128,
+    pub amt_refund: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a node posts an answer.
+/// The answer itself is left out so other elected nodes can't copy it before the bounty closes
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the bounty
+/// * `node_id`: the node that answered
+/// * `status`: the status the node reported
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AnswerPostedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub node_id: AccountId,
+    pub status: NodeResponseStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a node collects its payout for a bounty
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `bounty_id`: id of the bounty
+/// * `node_id`: the node that collected
+/// * `owner_id`: the account the payout was sent to
+/// * `amount`: the amount sent to the owner, as a string
+/// * `fee`: the protocol fee credited to the treasury, as a string
+/// * `audit_levy`: the levy credited to the audit pool, as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RewardCollectedLog {
+    pub coordinator_id: AccountId,
+    pub bounty_id: AccountId,
+    pub node_id: AccountId,
+    pub owner_id: AccountId,
+    pub amount: U128,
+    pub fee: U128,
+    pub audit_levy: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a node is registered
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `node_id`: "node.owner.test.near"
+/// * `owner_id`: the account that registered the node
+/// * `allow_network`: whether the node accepts bounties that need network access
+/// * `allow_gpu`: whether the node accepts bounties that need a gpu
+/// * `absolute_timeout`: the longest the node will run a bounty for
+/// * `deposit`: the refundable registration deposit, as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeRegisteredLog {
+    pub coordinator_id: AccountId,
+    pub node_id: AccountId,
+    pub owner_id: AccountId,
+    pub allow_network: bool,
+    pub allow_gpu: bool,
+    pub absolute_timeout: u64,
+    pub deposit: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a node's settings are updated
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `node_id`: the updated node
+/// * `owner_id`: the node's owner
+/// * `allow_network`: the new network setting
+/// * `allow_gpu`: the new gpu setting
+/// * `absolute_timeout`: the new timeout
+/// * `min_reward`: the least the node will run a bounty for, in yoctoNEAR
+/// * `min_reward_per_second`: added to min_reward for every second of a bounty's timeout, in yoctoNEAR
+/// * `acceptance_rules`: the bounties the node takes on, see AcceptanceRules
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeUpdatedLog {
+    pub coordinator_id: AccountId,
+    pub node_id: AccountId,
+    pub owner_id: AccountId,
+    pub allow_network: bool,
+    pub allow_gpu: bool,
+    pub absolute_timeout: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_reward: Option<U128>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_reward_per_second: Option<U128>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acceptance_rules: Option<AcceptanceRules>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a node is removed
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `node_id`: the removed node
+/// * `owner_id`: the node's owner
+/// * `refunded`: the deposit refunded to the owner, as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeRemovedLog {
+    pub coordinator_id: AccountId,
+    pub node_id: AccountId,
+    pub owner_id: AccountId,
+    pub refunded: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a node is set offline or brought back online
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `node_id`: the node
+/// * `owner_id`: the node's owner
+/// * `online`: true if the node can be elected again, false if it was set offline
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeStatusChangedLog {
+    pub coordinator_id: AccountId,
+    pub node_id: AccountId,
+    pub owner_id: AccountId,
+    pub online: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when an account creates a node pool
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `pool_id`: name of the pool
+/// * `owner_id`: the account that curates the pool, only it can create bounties against the pool
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolCreatedLog {
+    pub coordinator_id: AccountId,
+    pub pool_id: String,
+    pub owner_id: AccountId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// An event log for when a node is invited to a pool, joins it, or leaves it
+///
+/// Arguments
+/// * `coordinator_id`: the account id of the coordinator firing this event
+/// * `pool_id`: name of the pool
+/// * `node_id`: the node
+/// * `owner_id`: the node's owner
+/// * `change`: what happened, Invited, Joined, Left or Removed
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolMembershipChangedLog {
+    pub coordinator_id: AccountId,
+    pub pool_id: String,
+    pub node_id: AccountId,
+    pub owner_id: AccountId,
+    pub change: PoolMembershipChange,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
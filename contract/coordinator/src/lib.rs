@@ -1,4 +1,6 @@
+pub mod aggregation;
 pub mod bounty;
 pub mod coordinator;
 pub mod events;
 pub mod node;
+pub mod pool;
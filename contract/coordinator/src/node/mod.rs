@@ -5,6 +5,8 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::env::signer_account_id;
 use near_sdk::serde::{Deserialize, Serialize};
 
+use crate::bounty::{Bounty, SupportedDownloadProtocols};
+
 //TODO This struct should be considered when calculating the storage fee.
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Debug)]
@@ -21,12 +23,56 @@ pub struct Node {
     pub failed_runs: u64,
     pub unanswered_runs: u64,
     pub rejected_runs: u64,
+    pub audits_passed: u64,
+    pub audits_failed: u64,
     pub allow_network: bool,
     pub allow_gpu: bool,
     pub absolute_timeout: u64,
     pub lifetime_earnings: u128,
     pub deposit: u128,
     pub registration_time: u64,
+    pub min_reward: u128,
+    pub min_reward_per_second: u128,
+    pub acceptance_rules: AcceptanceRules,
+}
+
+// Bounties a node will take on, set by its owner. The node is never elected for a bounty its rules don't allow,
+// so it doesn't have to reject it and count the rejection against its record.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AcceptanceRules {
+    //Only take bounties from these owners. Any owner if empty
+    pub allowed_owners: Vec<AccountId>,
+    //Never take bounties from these owners
+    pub denied_owners: Vec<AccountId>,
+    //Only take bounties downloaded with these protocols. Any protocol if empty
+    pub allowed_protocols: Vec<SupportedDownloadProtocols>,
+    //Longest timeout_seconds the node takes, 0 for no limit. Checked on top of absolute_timeout
+    pub max_timeout_seconds: u64,
+    //Only take bounties whose package is pinned to one version, see Bounty::is_package_pinned
+    pub require_pinned_package: bool,
+}
+
+impl AcceptanceRules {
+    // Why the rules turn the bounty down, None if they allow it
+    pub fn check(&self, bounty: &Bounty) -> Option<String> {
+        if !self.allowed_owners.is_empty() && !self.allowed_owners.contains(&bounty.owner_id) {
+            return Some(format!("only takes bounties from {:?}", self.allowed_owners));
+        }
+        if self.denied_owners.contains(&bounty.owner_id) {
+            return Some(format!("doesn't take bounties from {}", bounty.owner_id));
+        }
+        if !self.allowed_protocols.is_empty() && !self.allowed_protocols.contains(&bounty.file_download_protocol) {
+            return Some(format!("doesn't download packages over {}", bounty.file_download_protocol));
+        }
+        if self.max_timeout_seconds > 0 && bounty.timeout_seconds > self.max_timeout_seconds {
+            return Some(format!("doesn't take bounties longer than {} seconds", self.max_timeout_seconds));
+        }
+        if self.require_pinned_package && !bounty.is_package_pinned() {
+            return Some("only runs pinned packages".to_string());
+        }
+        return None;
+    }
 }
 
 #[near_bindgen]
@@ -47,16 +93,39 @@ impl Node {
             failed_runs: 0,
             unanswered_runs: 0,
             rejected_runs: 0,
+            audits_passed: 0,
+            audits_failed: 0,
             allow_network,
             allow_gpu,
             lifetime_earnings: 0,
             absolute_timeout,
             deposit: env::attached_deposit(),
             registration_time: env::block_timestamp(),
+            min_reward: 0,
+            min_reward_per_second: 0,
+            acceptance_rules: AcceptanceRules::default(),
         }
     }
 }
 
+impl Node {
+    // Share of the node's runs that succeeded, in basis points, or None if it hasn't run anything yet
+    pub fn get_success_rate_bps(&self) -> Option<u64> {
+        let runs = self.successful_runs + self.failed_runs + self.rejected_runs + self.unanswered_runs;
+        if runs == 0 {
+            return None;
+        }
+        return Some(self.successful_runs * 10_000 / runs);
+    }
+
+    // The least this node will run a bounty for, min_reward plus min_reward_per_second for every second of the bounty's timeout
+    pub fn get_min_price(&self, timeout_seconds: u64) -> u128 {
+        return self
+            .min_reward
+            .saturating_add(self.min_reward_per_second.saturating_mul(timeout_seconds as u128));
+    }
+}
+
 impl Default for Node {
     fn default() -> Self {
         Self {
@@ -71,12 +140,17 @@ impl Default for Node {
             failed_runs: 0,
             unanswered_runs: 0,
             rejected_runs: 0,
+            audits_passed: 0,
+            audits_failed: 0,
             allow_network: true,
             allow_gpu: false,
             absolute_timeout: 60000,
             lifetime_earnings: 0,
             deposit: 0,
             registration_time: env::block_timestamp(),
+            min_reward: 0,
+            min_reward_per_second: 0,
+            acceptance_rules: AcceptanceRules::default(),
         }
     }
 }
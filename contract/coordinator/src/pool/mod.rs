@@ -0,0 +1,85 @@
+use std::fmt::{Display, Formatter};
+
+use near_sdk::AccountId;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+//Longest pool id we accept, it's written into every bounty that targets the pool
+pub const MAX_POOL_ID_LEN: usize = 64;
+
+// A named set of nodes curated by its owner. Bounties the owner creates against the pool only elect its members.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Pool {
+    pub id: String,
+    pub owner_id: AccountId,
+    //Nodes that accepted an invite. A node can be a member of several pools and still be elected from the public queue
+    pub members: Vec<AccountId>,
+    //Nodes the owner invited that haven't accepted yet
+    pub invited: Vec<AccountId>,
+    //UTC timestamp for when the pool was created (ms)
+    pub created_at: u64,
+    //Bounties created against this pool, including re-runs and audits
+    pub total_bounties: u64,
+}
+
+impl Pool {
+    pub fn new_pool(id: String, owner_id: AccountId, created_at: u64) -> Self {
+        Self { id, owner_id, members: vec![], invited: vec![], created_at, total_bounties: 0 }
+    }
+
+    // Pool ids are short and limited to characters that read well in logs and urls
+    pub fn is_valid_id(id: &str) -> bool {
+        return !id.is_empty()
+            && id.len() <= MAX_POOL_ID_LEN
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    }
+
+    pub fn is_member(&self, node_id: &AccountId) -> bool {
+        return self.members.contains(node_id);
+    }
+
+    pub fn is_invited(&self, node_id: &AccountId) -> bool {
+        return self.invited.contains(node_id);
+    }
+}
+
+// How a node's place in a pool changed, carried by the pool_membership_changed event
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Eq, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PoolMembershipChange {
+    //The pool owner invited the node
+    Invited,
+    //The node's owner accepted the invite
+    Joined,
+    //The node's owner took the node out of the pool, or turned down the invite
+    Left,
+    //The pool owner took the node out of the pool, or withdrew the invite
+    Removed,
+}
+
+impl Display for PoolMembershipChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoolMembershipChange::Invited => write!(f, "Invited"),
+            PoolMembershipChange::Joined => write!(f, "Joined"),
+            PoolMembershipChange::Left => write!(f, "Left"),
+            PoolMembershipChange::Removed => write!(f, "Removed"),
+        }
+    }
+}
+
+// Summary of a pool for dashboards, see get_pool_stats
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolStats {
+    pub pool_id: String,
+    pub owner_id: AccountId,
+    pub members: u64,
+    //Members that are registered and online, only these can be elected
+    pub online_members: u64,
+    pub invited: u64,
+    pub total_bounties: u64,
+    //Bounties against the pool that haven't closed yet
+    pub active_bounties: u64,
+}
@@ -0,0 +1,32 @@
+pub mod source;
+pub mod store;
+
+use crate::source::BlockSource;
+use crate::store::{IndexStats, Store};
+
+// Indexes every block after the store's checkpoint (or from_height, whichever is later), up to to_height.
+// Each block is committed along with the checkpoint as soon as the source yields it, so an interrupted or failed run
+// resumes where it stopped.
+pub fn index(
+    source: &mut dyn BlockSource,
+    store: &mut Store,
+    coordinator_id: &str,
+    from_height: u64,
+    to_height: Option<u64>,
+) -> anyhow::Result<IndexStats> {
+    let start = match store.checkpoint()? {
+        Some(checkpoint) => std::cmp::max(from_height, checkpoint + 1),
+        None => from_height,
+    };
+    let mut stats = IndexStats::default();
+    for block in source.blocks(start, to_height)? {
+        let block = block?;
+        let block_stats = store.apply_block(&block, coordinator_id)?;
+        stats.blocks += block_stats.blocks;
+        stats.events += block_stats.events;
+        stats.duplicates += block_stats.duplicates;
+        stats.invalid += block_stats.invalid;
+        stats.nonce_gaps += block_stats.nonce_gaps;
+    }
+    return Ok(stats);
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use indexer::source::{BlockSource, DirectorySource, RpcSource};
+use indexer::store::Store;
+
+/// Projects the coordinator's events into a SQLite database
+#[derive(Parser)]
+struct Args {
+    /// SQLite database to write to, created if it doesn't exist
+    #[arg(long, default_value = "indexer.sqlite")]
+    db: PathBuf,
+    /// Account id of the coordinator contract whose events are indexed
+    #[arg(long)]
+    coordinator: String,
+    /// Directory of near-lake style block JSON files
+    #[arg(long, conflicts_with = "rpc_url", required_unless_present = "rpc_url")]
+    blocks_dir: Option<PathBuf>,
+    /// JSON RPC url of a node, e.g. a local sandbox at http://localhost:3030
+    #[arg(long)]
+    rpc_url: Option<String>,
+    /// First block to index. Ignored if the database has already indexed past it
+    #[arg(long, default_value_t = 0)]
+    from_height: u64,
+    /// Last block to index, defaults to the last available block
+    #[arg(long)]
+    to_height: Option<u64>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let mut store = Store::open(&args.db)?;
+    let mut source: Box<dyn BlockSource> = match (args.blocks_dir, args.rpc_url) {
+        (Some(dir), _) => Box::new(DirectorySource::new(dir)),
+        (None, Some(url)) => Box::new(RpcSource::new(url)),
+        (None, None) => unreachable!("clap requires one of --blocks-dir or --rpc-url"),
+    };
+    let stats = indexer::index(source.as_mut(), &mut store, &args.coordinator, args.from_height, args.to_height)?;
+    println!(
+        "Indexed {} blocks: {} new events, {} already indexed, {} undecodable, {} nonce gaps. Checkpoint: {:?}",
+        stats.blocks,
+        stats.events,
+        stats.duplicates,
+        stats.invalid,
+        stats.nonce_gaps,
+        store.checkpoint()?
+    );
+    return Ok(());
+}
@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+// A block, reduced to what the indexer needs: the logs of every receipt that executed in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexerBlock {
+    pub height: u64,
+    pub hash: String,
+    pub timestamp: u64,
+    pub outcomes: Vec<ReceiptOutcome>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceiptOutcome {
+    pub receipt_id: String,
+    pub executor_id: String,
+    pub logs: Vec<String>,
+    // Logs of failed receipts are still returned by NEAR, but their state changes were rolled back
+    pub succeeded: bool,
+}
+
+// Blocks are read one at a time as the iterator is advanced, so a long range never has to be held in memory
+pub type Blocks<'a> = Box<dyn Iterator<Item = anyhow::Result<IndexerBlock>> + 'a>;
+
+pub trait BlockSource {
+    // Yields every available block with a height in [from_height, to_height], in ascending order
+    fn blocks(&mut self, from_height: u64, to_height: Option<u64>) -> anyhow::Result<Blocks<'_>>;
+}
+
+// The subset of a near-lake StreamerMessage that we read. Unknown fields are ignored.
+#[derive(Deserialize)]
+struct StreamerMessage {
+    block: StreamerBlock,
+    shards: Vec<StreamerShard>,
+}
+
+#[derive(Deserialize)]
+struct StreamerBlock {
+    header: StreamerHeader,
+}
+
+#[derive(Deserialize)]
+struct StreamerHeader {
+    height: u64,
+    hash: String,
+    timestamp: u64,
+}
+
+#[derive(Deserialize)]
+struct StreamerShard {
+    #[serde(default)]
+    receipt_execution_outcomes: Vec<StreamerReceiptOutcome>,
+}
+
+#[derive(Deserialize)]
+struct StreamerReceiptOutcome {
+    execution_outcome: ExecutionOutcomeWithId,
+}
+
+#[derive(Deserialize)]
+struct ExecutionOutcomeWithId {
+    id: String,
+    outcome: ExecutionOutcome,
+}
+
+#[derive(Deserialize)]
+struct ExecutionOutcome {
+    logs: Vec<String>,
+    executor_id: String,
+    status: Value,
+}
+
+fn is_success(status: &Value) -> bool {
+    return status.get("Failure").is_none() && status.get("failure").is_none();
+}
+
+impl From<ExecutionOutcomeWithId> for ReceiptOutcome {
+    fn from(outcome: ExecutionOutcomeWithId) -> Self {
+        return ReceiptOutcome {
+            receipt_id: outcome.id,
+            executor_id: outcome.outcome.executor_id,
+            succeeded: is_success(&outcome.outcome.status),
+            logs: outcome.outcome.logs,
+        };
+    }
+}
+
+// Reads one near-lake style JSON file per block from a directory. Files are named after their zero padded height,
+// e.g. blocks/000000123.json, so reading them in file name order reads them in height order.
+pub struct DirectorySource {
+    pub dir: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn new(dir: PathBuf) -> Self {
+        return DirectorySource { dir };
+    }
+
+    fn read_block(path: &Path) -> anyhow::Result<IndexerBlock> {
+        let contents = fs::read_to_string(path)?;
+        let message: StreamerMessage =
+            serde_json::from_str(&contents).with_context(|| format!("Could not parse block file {}", path.display()))?;
+        return Ok(IndexerBlock {
+            height: message.block.header.height,
+            hash: message.block.header.hash,
+            timestamp: message.block.header.timestamp,
+            outcomes: message
+                .shards
+                .into_iter()
+                .flat_map(|shard| shard.receipt_execution_outcomes)
+                .map(|outcome| outcome.execution_outcome.into())
+                .collect(),
+        });
+    }
+}
+
+impl BlockSource for DirectorySource {
+    fn blocks(&mut self, from_height: u64, to_height: Option<u64>) -> anyhow::Result<Blocks<'_>> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(&self.dir).with_context(|| format!("Could not read block directory {}", self.dir.display()))? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        let mut last_height: Option<u64> = None;
+        let blocks = paths.into_iter().filter_map(move |path| {
+            let block = match DirectorySource::read_block(&path) {
+                Ok(block) => block,
+                Err(e) => return Some(Err(e)),
+            };
+            if last_height.is_some_and(|last_height| block.height <= last_height) {
+                return Some(Err(anyhow!("Block file {} is out of order, block files must be named after their zero padded height", path.display())));
+            }
+            last_height = Some(block.height);
+            if block.height < from_height || to_height.is_some_and(|to_height| block.height > to_height) {
+                return None;
+            }
+            return Some(Ok(block));
+        });
+        return Ok(Box::new(blocks));
+    }
+}
+
+// Reads blocks from a node's JSON RPC, e.g. a local sandbox.
+// RPC blocks only list transactions, so every receipt a transaction spawns is attributed to the block the transaction landed in.
+pub struct RpcSource {
+    pub url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl RpcSource {
+    pub fn new(url: String) -> Self {
+        return RpcSource { url, client: reqwest::blocking::Client::new() };
+    }
+
+    fn call(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&json!({"jsonrpc": "2.0", "id": "indexer", "method": method, "params": params}))
+            .send()?
+            .json()?;
+        if let Some(error) = response.get("error") {
+            return Err(anyhow!("{} failed: {}", method, error));
+        }
+        return response.get("result").cloned().ok_or_else(|| anyhow!("{} returned no result", method));
+    }
+
+    pub fn final_height(&self) -> anyhow::Result<u64> {
+        let block = self.call("block", json!({"finality": "final"}))?;
+        return block["header"]["height"].as_u64().ok_or_else(|| anyhow!("block has no height"));
+    }
+
+    fn block(&self, height: u64) -> anyhow::Result<Option<IndexerBlock>> {
+        let block = match self.call("block", json!({"block_id": height})) {
+            Ok(block) => block,
+            // Heights can be skipped, there's nothing to index for them
+            Err(e) if e.to_string().contains("UNKNOWN_BLOCK") => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut outcomes = vec![];
+        for chunk in block["chunks"].as_array().cloned().unwrap_or_default() {
+            let chunk = self.call("chunk", json!({"chunk_id": chunk["chunk_hash"]}))?;
+            for transaction in chunk["transactions"].as_array().cloned().unwrap_or_default() {
+                let status = self.call("tx", json!([transaction["hash"], transaction["signer_id"]]))?;
+                for receipt in status["receipts_outcome"].as_array().cloned().unwrap_or_default() {
+                    let outcome: ExecutionOutcomeWithId = serde_json::from_value(receipt)?;
+                    outcomes.push(outcome.into());
+                }
+            }
+        }
+        return Ok(Some(IndexerBlock {
+            height,
+            hash: block["header"]["hash"].as_str().unwrap_or_default().to_string(),
+            timestamp: block["header"]["timestamp"].as_u64().unwrap_or_default(),
+            outcomes,
+        }));
+    }
+}
+
+impl BlockSource for RpcSource {
+    fn blocks(&mut self, from_height: u64, to_height: Option<u64>) -> anyhow::Result<Blocks<'_>> {
+        let to_height = match to_height {
+            Some(to_height) => to_height,
+            None => self.final_height()?,
+        };
+        // Skipped heights yield nothing, every other height is fetched when the caller gets to it
+        return Ok(Box::new((from_height..=to_height).filter_map(move |height| self.block(height).transpose())));
+    }
+}
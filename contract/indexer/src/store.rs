@@ -0,0 +1,412 @@
+use std::path::Path;
+
+use anyhow::Context;
+use coordinator_events::{decode_log, DecodeError, DecodedEvent, EventLogVariant};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+
+use crate::source::IndexerBlock;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS checkpoint (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    height INTEGER NOT NULL,
+    event_nonce INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS events (
+    receipt_id TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    block_timestamp INTEGER NOT NULL,
+    event_nonce INTEGER,
+    event_seq INTEGER,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (receipt_id, log_index)
+);
+CREATE TABLE IF NOT EXISTS nodes (
+    node_id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    allow_network INTEGER NOT NULL,
+    allow_gpu INTEGER NOT NULL,
+    absolute_timeout INTEGER NOT NULL,
+    deposit TEXT NOT NULL,
+    online INTEGER NOT NULL,
+    removed INTEGER NOT NULL,
+    registered_height INTEGER NOT NULL,
+    updated_height INTEGER NOT NULL,
+    audits_failed INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS bounties (
+    bounty_id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    file_location TEXT NOT NULL,
+    file_download_protocol TEXT NOT NULL,
+    min_nodes INTEGER NOT NULL,
+    timeout_seconds INTEGER NOT NULL,
+    network_required INTEGER NOT NULL,
+    gpu_required INTEGER NOT NULL,
+    amt_storage TEXT NOT NULL,
+    amt_node_reward TEXT NOT NULL,
+    reward_policy TEXT NOT NULL,
+    elected_nodes TEXT NOT NULL,
+    status TEXT NOT NULL,
+    dispute_status TEXT NOT NULL DEFAULT 'Undisputed',
+    event_seq INTEGER NOT NULL,
+    created_height INTEGER NOT NULL,
+    closed_height INTEGER
+);
+CREATE TABLE IF NOT EXISTS answers (
+    bounty_id TEXT NOT NULL,
+    node_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    message TEXT,
+    block_height INTEGER NOT NULL,
+    PRIMARY KEY (bounty_id, node_id)
+);
+CREATE TABLE IF NOT EXISTS payouts (
+    bounty_id TEXT NOT NULL,
+    node_id TEXT NOT NULL,
+    owner_id TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    fee TEXT NOT NULL,
+    audit_levy TEXT NOT NULL,
+    block_height INTEGER NOT NULL,
+    PRIMARY KEY (bounty_id, node_id)
+);
+CREATE TABLE IF NOT EXISTS pools (
+    pool_id TEXT PRIMARY KEY,
+    owner_id TEXT NOT NULL,
+    created_height INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS pool_members (
+    pool_id TEXT NOT NULL,
+    node_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    updated_height INTEGER NOT NULL,
+    PRIMARY KEY (pool_id, node_id)
+);
+";
+
+// What happened while indexing a run of blocks
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IndexStats {
+    pub blocks: u64,
+    pub events: u64,
+    // Events that were already indexed, e.g. when replaying blocks
+    pub duplicates: u64,
+    // Event logs that couldn't be decoded, these are skipped
+    pub invalid: u64,
+    // Times the event nonce jumped, meaning events between the two were never seen
+    pub nonce_gaps: u64,
+}
+
+pub struct Store {
+    pub conn: Connection,
+}
+
+// Serializes enums like NodeResponseStatus and BountyStatus the same way the contract does
+fn json_string<T: Serialize>(value: &T) -> String {
+    return match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(value) => value.to_string(),
+        Err(_) => String::new(),
+    };
+}
+
+impl Store {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open(path).with_context(|| format!("Could not open database {}", path.display()))?;
+        return Store::with_connection(conn);
+    }
+
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        return Store::with_connection(Connection::open_in_memory()?);
+    }
+
+    fn with_connection(conn: Connection) -> anyhow::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        return Ok(Store { conn });
+    }
+
+    // Height of the last fully indexed block
+    pub fn checkpoint(&self) -> anyhow::Result<Option<u64>> {
+        return Ok(self
+            .conn
+            .query_row("SELECT height FROM checkpoint WHERE id = 1", [], |row| row.get(0))
+            .optional()?);
+    }
+
+    pub fn last_event_nonce(&self) -> anyhow::Result<u64> {
+        return Ok(self
+            .conn
+            .query_row("SELECT event_nonce FROM checkpoint WHERE id = 1", [], |row| row.get(0))
+            .optional()?
+            .unwrap_or(0));
+    }
+
+    // Indexes every coordinator event in the block and moves the checkpoint past it, all in one transaction.
+    // Events are keyed by (receipt_id, log_index), so replaying a block never applies an event twice.
+    pub fn apply_block(&mut self, block: &IndexerBlock, coordinator_id: &str) -> anyhow::Result<IndexStats> {
+        let mut stats = IndexStats { blocks: 1, ..IndexStats::default() };
+        let mut last_nonce = self.last_event_nonce()?;
+        let tx = self.conn.transaction()?;
+        for outcome in block.outcomes.iter() {
+            if outcome.executor_id != coordinator_id || !outcome.succeeded {
+                continue;
+            }
+            for (log_index, line) in outcome.logs.iter().enumerate() {
+                let event = match decode_log(line) {
+                    Ok(event) => event,
+                    Err(DecodeError::NotAnEvent) => continue,
+                    Err(e) => {
+                        eprintln!("Skipping undecodable event in receipt {}: {}", outcome.receipt_id, e);
+                        stats.invalid += 1;
+                        continue;
+                    }
+                };
+                let data = match &event {
+                    DecodedEvent::Known(log) => serde_json::to_value(&log.event)?["data"].clone(),
+                    DecodedEvent::Unknown(unknown) => unknown.data.clone(),
+                };
+                let inserted = tx.execute(
+                    "INSERT OR IGNORE INTO events (receipt_id, log_index, block_height, block_timestamp, event_nonce, event_seq, name, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        outcome.receipt_id,
+                        log_index as u64,
+                        block.height,
+                        block.timestamp,
+                        event.event_nonce(),
+                        event.event_seq(),
+                        event.name(),
+                        data.to_string()
+                    ],
+                )?;
+                if inserted == 0 {
+                    stats.duplicates += 1;
+                    continue;
+                }
+                stats.events += 1;
+                if let Some(nonce) = event.event_nonce() {
+                    if last_nonce != 0 && nonce > last_nonce + 1 {
+                        eprintln!("Missed events {} to {} before block {}", last_nonce + 1, nonce - 1, block.height);
+                        stats.nonce_gaps += 1;
+                    }
+                    last_nonce = std::cmp::max(last_nonce, nonce);
+                }
+                if let DecodedEvent::Known(log) = &event {
+                    Store::project(&tx, &log.event, log.event_seq, block.height)?;
+                }
+            }
+        }
+        tx.execute(
+            "INSERT INTO checkpoint (id, height, event_nonce) VALUES (1, ?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET height = MAX(height, excluded.height), event_nonce = excluded.event_nonce",
+            params![block.height, last_nonce],
+        )?;
+        tx.commit()?;
+        return Ok(stats);
+    }
+
+    // Applies a single event to the nodes, bounties, answers, payouts and pool tables
+    fn project(tx: &Transaction, event: &EventLogVariant, event_seq: Option<u64>, height: u64) -> anyhow::Result<()> {
+        match event {
+            EventLogVariant::NodeRegistered(e) => {
+                tx.execute(
+                    "INSERT OR REPLACE INTO nodes (node_id, owner_id, allow_network, allow_gpu, absolute_timeout, deposit, online, removed, registered_height, updated_height)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1, 0, ?7, ?7)",
+                    params![e.node_id.as_str(), e.owner_id.as_str(), e.allow_network, e.allow_gpu, e.absolute_timeout, e.deposit.0.to_string(), height],
+                )?;
+            }
+            EventLogVariant::NodeUpdated(e) => {
+                tx.execute(
+                    "UPDATE nodes SET allow_network = ?2, allow_gpu = ?3, absolute_timeout = ?4, updated_height = ?5 WHERE node_id = ?1",
+                    params![e.node_id.as_str(), e.allow_network, e.allow_gpu, e.absolute_timeout, height],
+                )?;
+            }
+            EventLogVariant::NodeStatusChanged(e) => {
+                tx.execute(
+                    "UPDATE nodes SET online = ?2, updated_height = ?3 WHERE node_id = ?1",
+                    params![e.node_id.as_str(), e.online, height],
+                )?;
+            }
+            EventLogVariant::NodeRemoved(e) => {
+                tx.execute(
+                    "UPDATE nodes SET removed = 1, online = 0, deposit = '0', updated_height = ?2 WHERE node_id = ?1",
+                    params![e.node_id.as_str(), height],
+                )?;
+            }
+            EventLogVariant::BountyCreated(e) => {
+                tx.execute(
+                    "INSERT OR REPLACE INTO bounties (bounty_id, owner_id, file_location, file_download_protocol, min_nodes, timeout_seconds, network_required,
+                        gpu_required, amt_storage, amt_node_reward, reward_policy, elected_nodes, status, event_seq, created_height, closed_height)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, 'Pending', ?13, ?14, NULL)",
+                    params![
+                        e.bounty_id.as_str(),
+                        e.owner_id.as_str(),
+                        e.file_location,
+                        json_string(&e.file_download_protocol),
+                        e.min_nodes,
+                        e.timeout_seconds,
+                        e.network_required,
+                        e.gpu_required,
+                        e.amt_storage.0.to_string(),
+                        e.amt_node_reward.0.to_string(),
+                        serde_json::to_string(&e.reward_policy)?,
+                        serde_json::to_string(&e.node_ids)?,
+                        event_seq.unwrap_or(0),
+                        height
+                    ],
+                )?;
+            }
+            EventLogVariant::BountyDepositAdded(e) => {
+                let column = if json_string(&e.deposit) == "storage" { "amt_storage" } else { "amt_node_reward" };
+                tx.execute(
+                    &format!("UPDATE bounties SET {} = ?2 WHERE bounty_id = ?1", column),
+                    params![e.bounty_id.as_str(), e.total.0.to_string()],
+                )?;
+            }
+            EventLogVariant::BountyReelected(e) => {
+                let elected: Option<String> = tx
+                    .query_row("SELECT elected_nodes FROM bounties WHERE bounty_id = ?1", params![e.bounty_id.as_str()], |row| row.get(0))
+                    .optional()?;
+                if let Some(elected) = elected {
+                    let mut elected: Vec<String> = serde_json::from_str(&elected)?;
+                    elected.retain(|node_id| !e.timed_out_node_ids.iter().any(|timed_out| timed_out.as_str() == node_id));
+                    elected.extend(e.node_ids.iter().map(|node_id| node_id.to_string()));
+                    tx.execute(
+                        "UPDATE bounties SET elected_nodes = ?2 WHERE bounty_id = ?1",
+                        params![e.bounty_id.as_str(), serde_json::to_string(&elected)?],
+                    )?;
+                }
+            }
+            EventLogVariant::BountyAwarded(e) => {
+                let elected: Vec<String> = e.node_ids.iter().map(|node_id| node_id.to_string()).collect();
+                tx.execute(
+                    "UPDATE bounties SET elected_nodes = ?2, amt_node_reward = ?3 WHERE bounty_id = ?1",
+                    params![e.bounty_id.as_str(), serde_json::to_string(&elected)?, e.amt_node_reward.0.to_string()],
+                )?;
+            }
+            // A rejected node's replacement takes its slot
+            EventLogVariant::BountyRetry(e) if !e.replaced_node_ids.is_empty() => {
+                let elected: Option<String> = tx
+                    .query_row("SELECT elected_nodes FROM bounties WHERE bounty_id = ?1", params![e.bounty_id.as_str()], |row| row.get(0))
+                    .optional()?;
+                if let Some(elected) = elected {
+                    let mut elected: Vec<String> = serde_json::from_str(&elected)?;
+                    for (replaced, replacement) in e.replaced_node_ids.iter().zip(e.node_ids.iter()) {
+                        if let Some(slot) = elected.iter_mut().find(|node_id| node_id.as_str() == replaced.as_str()) {
+                            *slot = replacement.to_string();
+                        }
+                    }
+                    tx.execute(
+                        "UPDATE bounties SET elected_nodes = ?2 WHERE bounty_id = ?1",
+                        params![e.bounty_id.as_str(), serde_json::to_string(&elected)?],
+                    )?;
+                }
+            }
+            // Other retries only name nodes that are already elected
+            EventLogVariant::BountyRetry(_) => {}
+            EventLogVariant::BountyRejected(e) => {
+                tx.execute(
+                    "INSERT OR REPLACE INTO answers (bounty_id, node_id, status, message, block_height) VALUES (?1, ?2, 'REJECT', ?3, ?4)",
+                    params![e.bounty_id.as_str(), e.node_id.as_str(), e.message, height],
+                )?;
+            }
+            EventLogVariant::AnswerPosted(e) => {
+                tx.execute(
+                    "INSERT OR REPLACE INTO answers (bounty_id, node_id, status, message, block_height) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![e.bounty_id.as_str(), e.node_id.as_str(), json_string(&e.status), e.message, height],
+                )?;
+            }
+            EventLogVariant::BountyCompleted(e) => {
+                tx.execute(
+                    "UPDATE bounties SET status = ?2, closed_height = ?3 WHERE bounty_id = ?1",
+                    params![e.bounty_id.as_str(), json_string(&e.outcome), height],
+                )?;
+            }
+            EventLogVariant::BountyDisputed(e) => {
+                tx.execute("UPDATE bounties SET dispute_status = 'Pending' WHERE bounty_id = ?1", params![e.bounty_id.as_str()])?;
+            }
+            // The bounty keeps the status it closed with, like in the contract. An overturned dispute shows up in dispute_status.
+            EventLogVariant::BountyDisputeResolved(e) => {
+                let dispute_status = if e.upheld { "Upheld" } else { "Overturned" };
+                tx.execute(
+                    "UPDATE bounties SET dispute_status = ?2 WHERE bounty_id = ?1",
+                    params![e.bounty_id.as_str(), dispute_status],
+                )?;
+            }
+            EventLogVariant::BountyAuditMismatch(e) => {
+                for node_id in e.node_ids.iter() {
+                    tx.execute(
+                        "UPDATE nodes SET audits_failed = audits_failed + 1, updated_height = ?2 WHERE node_id = ?1",
+                        params![node_id.as_str(), height],
+                    )?;
+                }
+            }
+            EventLogVariant::PoolCreated(e) => {
+                tx.execute(
+                    "INSERT OR REPLACE INTO pools (pool_id, owner_id, created_height) VALUES (?1, ?2, ?3)",
+                    params![e.pool_id, e.owner_id.as_str(), height],
+                )?;
+            }
+            EventLogVariant::PoolMembershipChanged(e) => {
+                let change = json_string(&e.change);
+                if change == "Invited" || change == "Joined" {
+                    tx.execute(
+                        "INSERT OR REPLACE INTO pool_members (pool_id, node_id, status, updated_height) VALUES (?1, ?2, ?3, ?4)",
+                        params![e.pool_id, e.node_id.as_str(), change, height],
+                    )?;
+                } else {
+                    // The node left, or was removed, or its invite was withdrawn
+                    tx.execute("DELETE FROM pool_members WHERE pool_id = ?1 AND node_id = ?2", params![e.pool_id, e.node_id.as_str()])?;
+                }
+            }
+            EventLogVariant::RewardCollected(e) => {
+                tx.execute(
+                    "INSERT OR REPLACE INTO payouts (bounty_id, node_id, owner_id, amount, fee, audit_levy, block_height) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        e.bounty_id.as_str(),
+                        e.node_id.as_str(),
+                        e.owner_id.as_str(),
+                        e.amount.0.to_string(),
+                        e.fee.0.to_string(),
+                        e.audit_levy.0.to_string(),
+                        height
+                    ],
+                )?;
+            }
+            // Bids only matter until the auction is awarded, and BountyAwarded names the winners. They're kept in the events table.
+            EventLogVariant::BountyBidPlaced(_) => {}
+            // Only reachable for variants added to the coordinator after this match, their events are still stored above
+            _ => {}
+        }
+        if let (Some(bounty_id), Some(event_seq)) = (bounty_id_of(event), event_seq) {
+            tx.execute(
+                "UPDATE bounties SET event_seq = MAX(event_seq, ?2) WHERE bounty_id = ?1",
+                params![bounty_id, event_seq],
+            )?;
+        }
+        return Ok(());
+    }
+}
+
+fn bounty_id_of(event: &EventLogVariant) -> Option<&str> {
+    return match event {
+        EventLogVariant::BountyCreated(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyRetry(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyCompleted(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyDisputed(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyDisputeResolved(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyAuditMismatch(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyRejected(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyDepositAdded(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyReelected(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyBidPlaced(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::BountyAwarded(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::AnswerPosted(e) => Some(e.bounty_id.as_str()),
+        EventLogVariant::RewardCollected(e) => Some(e.bounty_id.as_str()),
+        _ => None,
+    };
+}
@@ -0,0 +1,261 @@
+// Records tests/fixtures/history by driving the mock coordinator, which runs the contract's own code, through node,
+// pool, bounty, dispute, audit and auction lifecycles. Every call lands in its own block, with a failed call recorded
+// as a failed receipt. The mock has no block or receipt hashes, so they're derived from the block's contents.
+//
+// Run with RECORD_HISTORY=1 to rewrite the fixtures after the contract's events change.
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use mock_coordinator::{MockConfig, MockCoordinator, MockError};
+use near_sdk::{AccountId, Balance};
+use near_units::parse_near;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+const COORDINATOR: &str = "coordinator.near";
+const NODE_OWNERS: [&str; 7] = ["bob.near", "carol.near", "dave.near", "erin.near", "frank.near", "grace.near", "heidi.near"];
+// Sandbox nodes produce a block about every second
+const BLOCK_TIME: Duration = Duration::from_secs(1);
+
+fn fixtures_dir() -> PathBuf {
+    return PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/history");
+}
+
+fn hash(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    return bs58::encode(hasher.finalize()).into_string();
+}
+
+struct Recorder {
+    mock: MockCoordinator,
+    prev_height: u64,
+    prev_hash: String,
+    blocks: Vec<(u64, Value)>,
+}
+
+impl Recorder {
+    fn new() -> Self {
+        let mock = MockCoordinator::new(MockConfig {
+            coordinator_id: COORDINATOR.parse().unwrap(),
+            ..MockConfig::default()
+        });
+        let prev_height = mock.block_height();
+        return Recorder { mock, prev_height, prev_hash: hash(&["genesis"]), blocks: vec![] };
+    }
+
+    fn view(&mut self, method: &str, args: Value) -> Value {
+        return self.mock.view(method, args).unwrap().json().unwrap();
+    }
+
+    fn call(&mut self, signer_id: &str, method: &str, args: Value, deposit: Balance) -> Value {
+        return match self.execute(signer_id, method, args, deposit) {
+            Ok(value) => value,
+            Err(message) => panic!("{} failed: {}", method, message),
+        };
+    }
+
+    // Records a call the contract is expected to reject
+    fn call_failing(&mut self, signer_id: &str, method: &str, args: Value, deposit: Balance) -> String {
+        return match self.execute(signer_id, method, args, deposit) {
+            Ok(_) => panic!("{} should have failed", method),
+            Err(message) => message,
+        };
+    }
+
+    fn execute(&mut self, signer_id: &str, method: &str, args: Value, deposit: Balance) -> Result<Value, String> {
+        self.mock.advance_time(BLOCK_TIME);
+        let signer: AccountId = signer_id.parse().unwrap();
+        let receipt_id = hash(&[&self.prev_hash, signer_id, method, &args.to_string()]);
+        let (result, logs, status) = match self.mock.call(&signer, method, args, deposit) {
+            Ok(outcome) => {
+                let status = json!({ "SuccessValue": base64::encode(outcome.value.get()) });
+                (Ok(outcome.json().unwrap()), outcome.logs, status)
+            }
+            Err(MockError::Panic { message, logs }) => {
+                let status = json!({
+                    "Failure": {
+                        "ActionError": {
+                            "index": 0,
+                            "kind": { "FunctionCallError": { "ExecutionError": format!("Smart contract panicked: {}", message) } }
+                        }
+                    }
+                });
+                (Err(message), logs, status)
+            }
+            Err(e) => panic!("{} never reached the coordinator: {}", method, e),
+        };
+        let height = self.mock.block_height();
+        let timestamp = self.mock.block_timestamp();
+        let block_hash = hash(&[&self.prev_hash, &height.to_string(), &receipt_id]);
+        let block = json!({
+            "block": {
+                "header": {
+                    "height": height,
+                    "prev_height": self.prev_height,
+                    "hash": block_hash,
+                    "prev_hash": self.prev_hash,
+                    "timestamp": timestamp,
+                    "timestamp_nanosec": timestamp.to_string(),
+                }
+            },
+            "shards": [{
+                "shard_id": 0,
+                "receipt_execution_outcomes": [{
+                    "execution_outcome": {
+                        "id": receipt_id,
+                        "outcome": {
+                            "logs": logs,
+                            "receipt_ids": [],
+                            "executor_id": COORDINATOR,
+                            "status": status,
+                        }
+                    },
+                    "receipt": null
+                }]
+            }]
+        });
+        self.blocks.push((height, block));
+        self.prev_height = height;
+        self.prev_hash = block_hash;
+        return result;
+    }
+
+    fn node_owner(node_id: &str) -> String {
+        return node_id.split_once(".node.").unwrap().1.to_string();
+    }
+
+    fn post_answer(&mut self, bounty_id: &str, node_id: &str, answer: &str) -> Value {
+        let args = json!({ "bounty_id": bounty_id, "node_id": node_id, "answer": answer, "message": "", "status": "SUCCESS" });
+        return self.call(&Recorder::node_owner(node_id), "post_answer", args, 0);
+    }
+}
+
+fn bounty_args(amt_node_reward: Balance, options: Value) -> Value {
+    return json!({
+        "file_location": "https://github.com/ad0ll/docker-hello-world.git",
+        "file_download_protocol": "GIT",
+        "min_nodes": 2,
+        "timeout_seconds": 30,
+        "network_required": false,
+        "gpu_required": false,
+        "amt_storage": parse_near!("1N").to_string(),
+        "amt_node_reward": amt_node_reward.to_string(),
+        "options": options,
+    });
+}
+
+fn node_ids(bounty: &Value) -> Vec<String> {
+    return bounty["elected_nodes"].as_array().unwrap().iter().map(|node_id| node_id.as_str().unwrap().to_string()).collect();
+}
+
+fn record() -> Vec<(u64, Value)> {
+    let mut recorder = Recorder::new();
+    recorder.call(COORDINATOR, "set_audit_levy_bps", json!({ "levy_bps": 500 }), 0);
+
+    // Nodes, each with a different owner so any three can be elected together
+    let mut nodes = vec![];
+    for owner in NODE_OWNERS {
+        let args = json!({ "name": "worker", "absolute_timeout": 60000, "allow_network": true, "allow_gpu": false });
+        let node = recorder.call(owner, "register_node", args, parse_near!("1N"));
+        nodes.push(node["id"].as_str().unwrap().to_string());
+    }
+    let args = json!({ "node_id": nodes[0], "allow_network": true, "allow_gpu": true, "absolute_timeout": 90000 });
+    recorder.call(NODE_OWNERS[0], "update_node", args, 0);
+    recorder.call(NODE_OWNERS[0], "set_node_offline", json!({ "node_id": nodes[0], "offline": true }), 0);
+    recorder.call(NODE_OWNERS[0], "set_node_offline", json!({ "node_id": nodes[0], "offline": false }), 0);
+
+    // A pool that one node joins and leaves again
+    recorder.call("alice.near", "create_pool", json!({ "pool_id": "lab" }), parse_near!("0.1N"));
+    recorder.call("alice.near", "invite_to_pool", json!({ "pool_id": "lab", "node_id": nodes[1] }), parse_near!("0.1N"));
+    recorder.call(NODE_OWNERS[1], "join_pool", json!({ "pool_id": "lab", "node_id": nodes[1] }), 0);
+    recorder.call(NODE_OWNERS[1], "leave_pool", json!({ "pool_id": "lab", "node_id": nodes[1] }), 0);
+
+    // A bounty that loses a node to a rejection, succeeds, and is overturned by a dispute
+    let args = bounty_args(parse_near!("2N"), json!({ "dispute_window_seconds": 3600 }));
+    let bounty = recorder.call("alice.near", "create_bounty", args, parse_near!("3N"));
+    let bounty_id = bounty["id"].as_str().unwrap().to_string();
+    recorder.call("alice.near", "add_node_reward_deposit", json!({ "bounty_id": bounty_id }), parse_near!("1N"));
+    let rejecter = node_ids(&bounty)[0].clone();
+    let args = json!({ "bounty_id": bounty_id, "node_id": rejecter, "message": "busy" });
+    recorder.call(&Recorder::node_owner(&rejecter), "reject_bounty", args, 0);
+    let elected = node_ids(&recorder.view("get_bounty", json!({ "bounty_id": bounty_id })));
+    assert!(!elected.contains(&rejecter), "The rejecter should have been replaced");
+    recorder.post_answer(&bounty_id, &elected[0], "42");
+    recorder.post_answer(&bounty_id, &elected[1], "42");
+    let args = json!({ "bounty_id": bounty_id, "node_id": elected[2], "answer": "42", "message": "", "status": "SUCCESS" });
+    recorder.call_failing(&Recorder::node_owner(&elected[2]), "post_answer", args, 0);
+
+    let args = json!({ "bounty_id": bounty_id, "amt_storage": parse_near!("1N").to_string(), "amt_node_reward": parse_near!("5N").to_string() });
+    let rerun = recorder.call("alice.near", "dispute_bounty", args, parse_near!("6.5N"));
+    let rerun_id = rerun["id"].as_str().unwrap().to_string();
+    let rerun_nodes = node_ids(&rerun);
+    recorder.post_answer(&rerun_id, &rerun_nodes[0], "43");
+    recorder.post_answer(&rerun_id, &rerun_nodes[1], "43");
+    // The audit levy on these payouts funds the audit below
+    for node_id in rerun_nodes.iter().take(2) {
+        let args = json!({ "node_id": node_id, "bounty_id": rerun_id });
+        recorder.call(&Recorder::node_owner(node_id), "collect_reward", args, 0);
+    }
+
+    // A bounty whose audit disagrees with it
+    recorder.call(COORDINATOR, "set_audit_rate_bps", json!({ "rate_bps": 10000 }), 0);
+    let bounty = recorder.call("alice.near", "create_bounty", bounty_args(parse_near!("1N"), json!({})), parse_near!("2N"));
+    let audited_id = bounty["id"].as_str().unwrap().to_string();
+    let audited_nodes = node_ids(&bounty);
+    recorder.post_answer(&audited_id, &audited_nodes[0], "42");
+    recorder.post_answer(&audited_id, &audited_nodes[1], "42");
+    let audits: Vec<Value> = serde_json::from_value(recorder.view("get_bounties_for_owner", json!({ "owner_id": COORDINATOR }))).unwrap();
+    let audit = audits.into_iter().find(|audit| audit["status"] == "Pending").expect("The bounty should have been audited");
+    let audit_id = audit["id"].as_str().unwrap().to_string();
+    let audit_nodes = node_ids(&audit);
+    recorder.post_answer(&audit_id, &audit_nodes[0], "43");
+    recorder.post_answer(&audit_id, &audit_nodes[1], "43");
+    recorder.call(COORDINATOR, "set_audit_rate_bps", json!({ "rate_bps": 0 }), 0);
+    let args = json!({ "node_id": audited_nodes[0], "bounty_id": audited_id });
+    recorder.call(&Recorder::node_owner(&audited_nodes[0]), "collect_reward", args, 0);
+
+    // An auctioned bounty, awarded to the three cheapest bids
+    let args = bounty_args(parse_near!("1N"), json!({ "bidding_window_seconds": 60 }));
+    let auction = recorder.call("alice.near", "create_bounty", args, parse_near!("2N"));
+    let auction_id = auction["id"].as_str().unwrap().to_string();
+    let bidders: Vec<String> = nodes.iter().filter(|node_id| **node_id != rejecter).take(4).cloned().collect();
+    for (i, node_id) in bidders.iter().enumerate() {
+        let args = json!({ "bounty_id": auction_id, "node_id": node_id, "price": parse_near!("0.1N").saturating_add(i as u128 * parse_near!("0.05N")).to_string() });
+        recorder.call(&Recorder::node_owner(node_id), "bid_on_bounty", args, 0);
+    }
+    recorder.mock.advance_time(Duration::from_secs(60));
+    recorder.call("alice.near", "award_bounty", json!({ "bounty_id": auction_id }), 0);
+
+    let removed = bidders.last().unwrap().clone();
+    recorder.call(&Recorder::node_owner(&removed), "remove_node", json!({ "node_id": removed }), 0);
+    return recorder.blocks;
+}
+
+#[test]
+fn checked_in_history_matches_a_recorded_run() {
+    let blocks = record();
+    if std::env::var("RECORD_HISTORY").is_ok() {
+        for entry in fs::read_dir(fixtures_dir()).unwrap() {
+            fs::remove_file(entry.unwrap().path()).unwrap();
+        }
+        for (height, block) in blocks.iter() {
+            let path = fixtures_dir().join(format!("{:09}.json", height));
+            fs::write(path, serde_json::to_string_pretty(block).unwrap() + "\n").unwrap();
+        }
+    }
+
+    let mut checked_in: Vec<String> = fs::read_dir(fixtures_dir()).unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect();
+    checked_in.sort();
+    let recorded: Vec<String> = blocks.iter().map(|(height, _)| format!("{:09}.json", height)).collect();
+    assert_eq!(checked_in, recorded, "Re-record the history with RECORD_HISTORY=1");
+    for (height, block) in blocks.iter() {
+        let contents = fs::read_to_string(fixtures_dir().join(format!("{:09}.json", height))).unwrap();
+        let checked_in: Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(&checked_in, block, "Block {} differs from a fresh run, re-record the history with RECORD_HISTORY=1", height);
+    }
+}
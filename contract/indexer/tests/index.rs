@@ -0,0 +1,184 @@
+// tests/fixtures/history holds one near-lake style block file per height, recorded by tests/history.rs from a run of the
+// contract's own code. It covers node, pool, bounty, dispute, audit and auction lifecycles, and block 22 has a failed receipt.
+use std::path::PathBuf;
+use std::process::Command;
+
+use indexer::source::DirectorySource;
+use indexer::store::Store;
+
+const COORDINATOR: &str = "coordinator.near";
+
+fn history() -> DirectorySource {
+    return DirectorySource::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/history"));
+}
+
+fn count(store: &Store, table: &str) -> u64 {
+    return store.conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap();
+}
+
+fn assert_full_history(store: &Store) {
+    assert_eq!(store.checkpoint().unwrap(), Some(42));
+    assert_eq!(store.last_event_nonce().unwrap(), 47);
+    assert_eq!(count(store, "events"), 47);
+    assert_eq!(count(store, "nodes"), 7);
+    assert_eq!(count(store, "bounties"), 5);
+    assert_eq!(count(store, "answers"), 9);
+    assert_eq!(count(store, "payouts"), 3);
+
+    let (online, timeout): (bool, u64) = store
+        .conn
+        .query_row("SELECT online, absolute_timeout FROM nodes WHERE node_id = 'worker.node.bob.near'", [], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap();
+    assert!(online);
+    assert_eq!(timeout, 90000);
+    let (removed, deposit): (bool, String) = store
+        .conn
+        .query_row("SELECT removed, deposit FROM nodes WHERE node_id = 'worker.node.frank.near'", [], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap();
+    assert!(removed);
+    assert_eq!(deposit, "0");
+
+    let (status, reward, event_seq, closed_height): (String, String, u64, u64) = store
+        .conn
+        .query_row(
+            "SELECT status, amt_node_reward, event_seq, closed_height FROM bounties WHERE bounty_id = '0-0.bounty.alice.near'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(status, "Success");
+    assert_eq!(reward, "3000000000000000000000000");
+    assert_eq!(event_seq, 10);
+    assert_eq!(closed_height, 21);
+
+    let rejected: u64 = store.conn.query_row("SELECT COUNT(*) FROM answers WHERE status = 'REJECT'", [], |row| row.get(0)).unwrap();
+    assert_eq!(rejected, 1);
+    let (elected, dispute_status): (String, String) = store
+        .conn
+        .query_row("SELECT elected_nodes, dispute_status FROM bounties WHERE bounty_id = '0-0.bounty.alice.near'", [], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap();
+    assert_eq!(elected, r#"["worker.node.carol.near","worker.node.heidi.near","worker.node.bob.near"]"#, "The rejecter's replacement should take its slot");
+    assert_eq!(dispute_status, "Overturned");
+
+    let audits_failed: Vec<(String, u64)> = store
+        .conn
+        .prepare("SELECT node_id, audits_failed FROM nodes WHERE audits_failed > 0 ORDER BY node_id")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect();
+    assert_eq!(audits_failed, vec![("worker.node.bob.near".to_string(), 1), ("worker.node.dave.near".to_string(), 1)]);
+
+    assert_eq!(count(store, "pools"), 1);
+    assert_eq!(count(store, "pool_members"), 0, "The only node that joined the pool left it again");
+    let awarded: String = store
+        .conn
+        .query_row("SELECT elected_nodes FROM bounties WHERE bounty_id = '4-0.bounty.alice.near'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(awarded, r#"["worker.node.bob.near","worker.node.carol.near","worker.node.erin.near"]"#);
+}
+
+#[test]
+fn indexes_a_full_history() {
+    let mut store = Store::open_in_memory().unwrap();
+    let stats = indexer::index(&mut history(), &mut store, COORDINATOR, 0, None).unwrap();
+    assert_eq!(stats.blocks, 41);
+    assert_eq!(stats.events, 47);
+    assert_eq!(stats.duplicates, 0);
+    assert_eq!(stats.invalid, 0);
+    assert_eq!(stats.nonce_gaps, 0);
+    assert_full_history(&store);
+}
+
+#[test]
+fn skips_receipts_from_other_contracts() {
+    let mut store = Store::open_in_memory().unwrap();
+    let stats = indexer::index(&mut history(), &mut store, "other.near", 0, None).unwrap();
+    assert_eq!(stats.blocks, 41);
+    assert_eq!(stats.events, 0);
+    assert_eq!(count(&store, "events"), 0);
+    assert_eq!(store.checkpoint().unwrap(), Some(42));
+}
+
+#[test]
+fn replaying_blocks_is_idempotent() {
+    let mut store = Store::open_in_memory().unwrap();
+    indexer::index(&mut history(), &mut store, COORDINATOR, 0, None).unwrap();
+    // Forget the checkpoint so every block is read again
+    store.conn.execute("DELETE FROM checkpoint", []).unwrap();
+    let stats = indexer::index(&mut history(), &mut store, COORDINATOR, 0, None).unwrap();
+    assert_eq!(stats.events, 0);
+    assert_eq!(stats.duplicates, 47);
+    assert_eq!(count(&store, "events"), 47);
+    assert_eq!(count(&store, "answers"), 9);
+    assert_eq!(count(&store, "payouts"), 3);
+}
+
+#[test]
+fn resumes_from_the_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("indexer.sqlite");
+    {
+        let mut store = Store::open(&db).unwrap();
+        let stats = indexer::index(&mut history(), &mut store, COORDINATOR, 0, Some(15)).unwrap();
+        assert_eq!(stats.events, 13);
+        let member: String = store.conn.query_row("SELECT status FROM pool_members", [], |row| row.get(0)).unwrap();
+        assert_eq!(member, "Joined");
+        let stats = indexer::index(&mut history(), &mut store, COORDINATOR, 0, Some(20)).unwrap();
+        assert_eq!(stats.events, 7);
+        assert_eq!(store.checkpoint().unwrap(), Some(20));
+        let status: String = store.conn.query_row("SELECT status FROM bounties", [], |row| row.get(0)).unwrap();
+        assert_eq!(status, "Pending");
+    }
+    let mut store = Store::open(&db).unwrap();
+    let stats = indexer::index(&mut history(), &mut store, COORDINATOR, 0, None).unwrap();
+    assert_eq!(stats.blocks, 22);
+    assert_eq!(stats.events, 27);
+    assert_eq!(stats.duplicates, 0);
+    assert_full_history(&store);
+}
+
+#[test]
+fn commits_each_block_before_reading_the_next() {
+    let dir = tempfile::tempdir().unwrap();
+    let blocks_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/history");
+    for entry in std::fs::read_dir(&blocks_dir).unwrap() {
+        let path = entry.unwrap().path();
+        std::fs::copy(&path, dir.path().join(path.file_name().unwrap())).unwrap();
+    }
+    let broken = dir.path().join("000000021.json");
+    let contents = std::fs::read_to_string(&broken).unwrap();
+    std::fs::write(&broken, "not a block").unwrap();
+
+    let mut store = Store::open_in_memory().unwrap();
+    let mut source = DirectorySource::new(dir.path().to_path_buf());
+    let error = indexer::index(&mut source, &mut store, COORDINATOR, 0, None).unwrap_err();
+    assert!(error.to_string().contains("000000021.json"), "{}", error);
+    // Everything before the broken block was committed as it was read
+    assert_eq!(store.checkpoint().unwrap(), Some(20));
+    assert_eq!(count(&store, "events"), 20);
+
+    std::fs::write(&broken, contents).unwrap();
+    let stats = indexer::index(&mut source, &mut store, COORDINATOR, 0, None).unwrap();
+    assert_eq!(stats.blocks, 22);
+    assert_full_history(&store);
+}
+
+#[test]
+fn binary_indexes_a_block_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("indexer.sqlite");
+    let blocks_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/history");
+    for _ in 0..2 {
+        let output = Command::new(env!("CARGO_BIN_EXE_indexer"))
+            .args(["--coordinator", COORDINATOR, "--db"])
+            .arg(&db)
+            .arg("--blocks-dir")
+            .arg(&blocks_dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert_full_history(&Store::open(&db).unwrap());
+}
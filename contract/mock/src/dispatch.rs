@@ -0,0 +1,283 @@
+// Maps the contract's public methods to the Coordinator functions near_bindgen would call, with the same JSON arguments.
+// Every public method of the contract must be listed in exactly one of VIEW_METHODS, CALL_METHODS or UNSUPPORTED_METHODS,
+// tests/surface.rs fails otherwise.
+use coordinator::coordinator::Coordinator;
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde::Serialize;
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+// Methods that only read state, callable with view or call
+pub const VIEW_METHODS: &[&str] = &[
+    "get_node_count",
+    "get_bounty_count",
+    "get_offline_node_count",
+    "get_total_completed_bounties",
+    "get_nodes_for_owner_count",
+    "get_lifetime_earnings_for_owner",
+    "get_bounties_for_owner_count",
+    "get_total_payouts",
+    "get_gas_reimbursement",
+    "get_total_fees",
+    "get_treasury_balance",
+    "get_treasury_account",
+    "get_protocol_fee_bps",
+    "get_admin",
+    "get_keepers",
+    "get_audit_rate_bps",
+    "get_audit_levy_bps",
+    "get_audit_pool",
+    "get_quotas",
+    "get_active_bounty_count_for_owner",
+    "get_quota_usage",
+    "get_event_nonce",
+    "get_total_escrowed",
+    "get_solvency_report",
+    "get_node",
+    "get_bounty",
+    "get_nodes",
+    "get_nodes2",
+    "get_nodes_for_owner",
+    "get_offline_nodes_for_owner",
+    "get_bounties",
+    "get_bounty_elected_nodes",
+    "get_unanswered_nodes",
+    "get_successful_nodes",
+    "get_failed_nodes",
+    "get_bounty_or_panic",
+    "get_bounties_for_owner",
+    "get_bounty_answer_counts",
+    "get_answer",
+    "should_post_answer",
+    "should_collect_reward",
+    "get_pending_payout",
+    "get_bounty_result",
+    "get_bounty_aggregate_result",
+    "get_accepting_node_count",
+    "get_blocklist",
+    "get_pool",
+    "get_pools_for_node",
+    "get_pool_stats",
+];
+
+// Methods that need a signer, and can only be sent as a transaction
+pub const CALL_METHODS: &[&str] = &[
+    "set_gas_reimbursement",
+    "set_admin",
+    "add_keeper",
+    "remove_keeper",
+    "set_protocol_fee_bps",
+    "set_treasury_account",
+    "withdraw_treasury",
+    "set_audit_rate_bps",
+    "set_audit_levy_bps",
+    "set_quotas",
+    "set_owner_quotas",
+    "register_node",
+    "update_node",
+    "remove_node",
+    "remove_all_nodes",
+    "set_node_offline",
+    "set_node_price",
+    "set_node_rules",
+    "get_node_padding",
+    "create_bounty",
+    "call_get_answer",
+    "cancel_bounty",
+    "cancel_all_my_bounties",
+    "reject_bounty",
+    "post_answer",
+    "collect_reward",
+    "dispute_bounty",
+    "expire_rerun",
+    "reclaim_reward_from_dropped_nodes",
+    "add_storage_deposit",
+    "add_node_reward_deposit",
+    "reelect_unanswered_nodes",
+    "bid_on_bounty",
+    "award_bounty",
+    "add_to_blocklist",
+    "remove_from_blocklist",
+    "create_pool",
+    "invite_to_pool",
+    "join_pool",
+    "leave_pool",
+];
+
+// Call methods marked #[payable], every other method panics if a deposit is attached
+pub const PAYABLE_METHODS: &[&str] = &[
+    "register_node",
+    "create_bounty",
+    "dispute_bounty",
+    "add_storage_deposit",
+    "add_node_reward_deposit",
+    "add_to_blocklist",
+    "create_pool",
+    "invite_to_pool",
+];
+
+// Call methods marked #[private], only the coordinator itself can call them
+pub const PRIVATE_METHODS: &[&str] = &["get_node_padding"];
+
+// Public in Rust but not reachable by clients: init runs when the mock starts, the rest take arguments that can't come from JSON
+pub const UNSUPPORTED_METHODS: &[&str] = &["init", "elect_nodes", "close_bounty"];
+
+// Reads a named argument the way near_bindgen does, missing arguments are null so Option arguments can be left out
+fn arg<T: DeserializeOwned>(args: &Value, name: &str) -> T {
+    let value = args.get(name).cloned().unwrap_or(Value::Null);
+    return serde_json::from_value(value)
+        .unwrap_or_else(|e| panic!("Failed to deserialize input from JSON.: {} {}", name, e));
+}
+
+// Serialized the way near_bindgen does it, going through Value would lose u128s
+fn ret<T: Serialize>(value: T) -> Box<RawValue> {
+    return serde_json::value::to_raw_value(&value).expect("contract return values always serialize");
+}
+
+macro_rules! invoke {
+    ($coordinator:expr, $method:ident, $args:expr $(, $name:literal)*) => {
+        ret($coordinator.$method($(arg($args, $name)),*))
+    };
+}
+
+// Promises are scheduled when they're dropped, the caller sees null just like a transfer on chain
+macro_rules! invoke_promise {
+    ($coordinator:expr, $method:ident, $args:expr $(, $name:literal)*) => {{
+        drop($coordinator.$method($(arg($args, $name)),*));
+        ret(())
+    }};
+}
+
+/// Runs a view method, None if there is no such view
+pub fn view(c: &Coordinator, method: &str, args: &Value) -> Option<Box<RawValue>> {
+    let value = match method {
+        "get_node_count" => invoke!(c, get_node_count, args),
+        "get_bounty_count" => invoke!(c, get_bounty_count, args),
+        "get_offline_node_count" => invoke!(c, get_offline_node_count, args),
+        "get_total_completed_bounties" => invoke!(c, get_total_completed_bounties, args),
+        "get_nodes_for_owner_count" => invoke!(c, get_nodes_for_owner_count, args, "owner_id"),
+        "get_lifetime_earnings_for_owner" => invoke!(c, get_lifetime_earnings_for_owner, args, "owner_id"),
+        "get_bounties_for_owner_count" => invoke!(c, get_bounties_for_owner_count, args, "owner_id"),
+        "get_total_payouts" => invoke!(c, get_total_payouts, args),
+        "get_gas_reimbursement" => invoke!(c, get_gas_reimbursement, args),
+        "get_total_fees" => invoke!(c, get_total_fees, args),
+        "get_treasury_balance" => invoke!(c, get_treasury_balance, args),
+        "get_treasury_account" => invoke!(c, get_treasury_account, args),
+        "get_protocol_fee_bps" => invoke!(c, get_protocol_fee_bps, args),
+        "get_admin" => invoke!(c, get_admin, args),
+        "get_keepers" => invoke!(c, get_keepers, args),
+        "get_audit_rate_bps" => invoke!(c, get_audit_rate_bps, args),
+        "get_audit_levy_bps" => invoke!(c, get_audit_levy_bps, args),
+        "get_audit_pool" => invoke!(c, get_audit_pool, args),
+        "get_quotas" => invoke!(c, get_quotas, args, "account_id"),
+        "get_active_bounty_count_for_owner" => invoke!(c, get_active_bounty_count_for_owner, args, "owner_id"),
+        "get_quota_usage" => invoke!(c, get_quota_usage, args, "account_id"),
+        "get_event_nonce" => invoke!(c, get_event_nonce, args),
+        "get_total_escrowed" => invoke!(c, get_total_escrowed, args),
+        "get_solvency_report" => invoke!(c, get_solvency_report, args),
+        "get_node" => invoke!(c, get_node, args, "node_id"),
+        "get_bounty" => invoke!(c, get_bounty, args, "bounty_id"),
+        "get_nodes" => invoke!(c, get_nodes, args),
+        "get_nodes2" => invoke!(c, get_nodes2, args),
+        "get_nodes_for_owner" => invoke!(c, get_nodes_for_owner, args, "owner_id"),
+        "get_offline_nodes_for_owner" => invoke!(c, get_offline_nodes_for_owner, args, "owner_id"),
+        "get_bounties" => invoke!(c, get_bounties, args),
+        "get_bounty_elected_nodes" => invoke!(c, get_bounty_elected_nodes, args, "bounty_id"),
+        "get_unanswered_nodes" => invoke!(c, get_unanswered_nodes, args, "bounty_id"),
+        "get_successful_nodes" => invoke!(c, get_successful_nodes, args, "bounty_id"),
+        "get_failed_nodes" => invoke!(c, get_failed_nodes, args, "bounty_id"),
+        "get_bounty_or_panic" => invoke!(c, get_bounty_or_panic, args, "bounty_id"),
+        "get_bounties_for_owner" => invoke!(c, get_bounties_for_owner, args, "owner_id"),
+        "get_bounty_answer_counts" => invoke!(c, get_bounty_answer_counts, args, "bounty_id"),
+        "get_answer" => invoke!(c, get_answer, args, "bounty_id", "node_id"),
+        "should_post_answer" => invoke!(c, should_post_answer, args, "bounty_id", "node_id"),
+        "should_collect_reward" => invoke!(c, should_collect_reward, args, "node_id", "bounty_id"),
+        "get_pending_payout" => invoke!(c, get_pending_payout, args, "node_id", "bounty_id"),
+        "get_bounty_result" => invoke!(c, get_bounty_result, args, "bounty_id"),
+        "get_bounty_aggregate_result" => invoke!(c, get_bounty_aggregate_result, args, "bounty_id"),
+        "get_accepting_node_count" => invoke!(
+            c,
+            get_accepting_node_count,
+            args,
+            "amt_node_reward",
+            "min_nodes",
+            "timeout_seconds",
+            "network_required",
+            "gpu_required"
+        ),
+        "get_blocklist" => invoke!(c, get_blocklist, args, "account_id"),
+        "get_pool" => invoke!(c, get_pool, args, "pool_id"),
+        "get_pools_for_node" => invoke!(c, get_pools_for_node, args, "node_id"),
+        "get_pool_stats" => invoke!(c, get_pool_stats, args, "pool_id"),
+        _ => return None,
+    };
+    return Some(value);
+}
+
+/// Runs a call method, falling back to views like the chain does. None if there is no such method.
+pub fn call(c: &mut Coordinator, method: &str, args: &Value) -> Option<Box<RawValue>> {
+    let value = match method {
+        "set_gas_reimbursement" => invoke!(c, set_gas_reimbursement, args, "amount"),
+        "set_admin" => invoke!(c, set_admin, args, "admin_id"),
+        "add_keeper" => invoke!(c, add_keeper, args, "account_id"),
+        "remove_keeper" => invoke!(c, remove_keeper, args, "account_id"),
+        "set_protocol_fee_bps" => invoke!(c, set_protocol_fee_bps, args, "fee_bps"),
+        "set_treasury_account" => invoke!(c, set_treasury_account, args, "treasury_account_id"),
+        "withdraw_treasury" => invoke_promise!(c, withdraw_treasury, args, "amount"),
+        "set_audit_rate_bps" => invoke!(c, set_audit_rate_bps, args, "rate_bps"),
+        "set_audit_levy_bps" => invoke!(c, set_audit_levy_bps, args, "levy_bps"),
+        "set_quotas" => invoke!(
+            c,
+            set_quotas,
+            args,
+            "max_nodes_per_owner",
+            "max_active_bounties_per_owner",
+            "max_elected_share_bps"
+        ),
+        "set_owner_quotas" => invoke!(c, set_owner_quotas, args, "account_id", "quotas"),
+        "register_node" => invoke!(c, register_node, args, "name", "absolute_timeout", "allow_network", "allow_gpu"),
+        "update_node" => invoke!(c, update_node, args, "node_id", "allow_network", "allow_gpu", "absolute_timeout"),
+        "remove_node" => invoke_promise!(c, remove_node, args, "node_id"),
+        "remove_all_nodes" => invoke!(c, remove_all_nodes, args),
+        "set_node_offline" => invoke!(c, set_node_offline, args, "node_id", "offline"),
+        "set_node_price" => invoke!(c, set_node_price, args, "node_id", "min_reward", "min_reward_per_second"),
+        "set_node_rules" => invoke!(c, set_node_rules, args, "node_id", "rules"),
+        "get_node_padding" => invoke!(c, get_node_padding, args, "min_nodes"),
+        "create_bounty" => invoke!(
+            c,
+            create_bounty,
+            args,
+            "file_location",
+            "file_download_protocol",
+            "min_nodes",
+            "timeout_seconds",
+            "network_required",
+            "gpu_required",
+            "amt_storage",
+            "amt_node_reward",
+            "options"
+        ),
+        "call_get_answer" => invoke!(c, call_get_answer, args, "bounty_id", "node_id"),
+        "cancel_bounty" => invoke!(c, cancel_bounty, args, "bounty_id"),
+        "cancel_all_my_bounties" => invoke!(c, cancel_all_my_bounties, args),
+        "reject_bounty" => invoke!(c, reject_bounty, args, "bounty_id", "node_id", "message"),
+        "post_answer" => invoke!(c, post_answer, args, "bounty_id", "node_id", "answer", "message", "status"),
+        "collect_reward" => invoke_promise!(c, collect_reward, args, "node_id", "bounty_id"),
+        "dispute_bounty" => invoke!(c, dispute_bounty, args, "bounty_id", "amt_storage", "amt_node_reward"),
+        "expire_rerun" => invoke!(c, expire_rerun, args, "bounty_id"),
+        "reclaim_reward_from_dropped_nodes" => invoke_promise!(c, reclaim_reward_from_dropped_nodes, args, "bounty_id"),
+        "add_storage_deposit" => invoke_promise!(c, add_storage_deposit, args, "bounty_id"),
+        "add_node_reward_deposit" => invoke_promise!(c, add_node_reward_deposit, args, "bounty_id"),
+        "reelect_unanswered_nodes" => invoke!(c, reelect_unanswered_nodes, args, "bounty_id"),
+        "bid_on_bounty" => invoke!(c, bid_on_bounty, args, "bounty_id", "node_id", "price"),
+        "award_bounty" => invoke_promise!(c, award_bounty, args, "bounty_id"),
+        "add_to_blocklist" => invoke!(c, add_to_blocklist, args, "node_ids", "owner_ids"),
+        "remove_from_blocklist" => invoke!(c, remove_from_blocklist, args, "node_ids", "owner_ids"),
+        "create_pool" => invoke!(c, create_pool, args, "pool_id"),
+        "invite_to_pool" => invoke!(c, invite_to_pool, args, "pool_id", "node_id"),
+        "join_pool" => invoke!(c, join_pool, args, "pool_id", "node_id"),
+        "leave_pool" => invoke!(c, leave_pool, args, "pool_id", "node_id"),
+        _ => return view(c, method, args),
+    };
+    return Some(value);
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+// What goes wrong when a fault fires
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FaultKind {
+    // The method isn't run, the caller gets a contract panic with this message
+    Panic { message: String },
+    // The method isn't run, the caller gets an RPC error as if the node was down
+    Unavailable,
+    // The method runs and its changes are kept, but the caller gets a timeout instead of the result
+    Timeout,
+    // The method runs, the response is held back this long
+    Delay { ms: u64 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Fault {
+    // Only fire on this method, fires on every method if not set
+    pub method: Option<String>,
+    // Fire this many times then disarm, fires forever if not set
+    pub times: Option<u32>,
+    #[serde(flatten)]
+    pub kind: FaultKind,
+}
+
+impl Fault {
+    pub fn new(method: Option<&str>, kind: FaultKind) -> Self {
+        return Self { method: method.map(|m| m.to_string()), times: None, kind };
+    }
+
+    pub fn times(mut self, times: u32) -> Self {
+        self.times = Some(times);
+        return self;
+    }
+
+    fn matches(&self, method: &str) -> bool {
+        return self.times != Some(0) && self.method.as_deref().is_none_or(|m| m == method);
+    }
+}
+
+// Armed faults, first match wins
+#[derive(Default, Debug, Clone)]
+pub struct Faults {
+    armed: Vec<Fault>,
+}
+
+impl Faults {
+    pub fn inject(&mut self, fault: Fault) {
+        self.armed.push(fault);
+    }
+
+    pub fn clear(&mut self) {
+        self.armed.clear();
+    }
+
+    pub fn armed(&self) -> &[Fault] {
+        return &self.armed;
+    }
+
+    // Returns the fault that fires for this method, if any, using up one of its times
+    pub fn fire(&mut self, method: &str) -> Option<FaultKind> {
+        let fault = self.armed.iter_mut().find(|fault| fault.matches(method))?;
+        if let Some(times) = fault.times.as_mut() {
+            *times -= 1;
+        }
+        let kind = fault.kind.clone();
+        self.armed.retain(|fault| fault.times != Some(0));
+        return Some(kind);
+    }
+}
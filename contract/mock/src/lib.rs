@@ -0,0 +1,409 @@
+// An in-memory coordinator for testing clients without a sandbox or a compiled wasm.
+// It runs the coordinator crate's own code against near-sdk's mocked blockchain, so bounty rules, events and panics
+// are the contract's, not a reimplementation. State is kept under the same STATE key near_bindgen uses, and a call
+// that panics leaves it untouched, like a failed transaction.
+//
+// What's simulated: attached deposits, account balances and transfers, storage staking, block height and time, logs.
+// What isn't: gas, access keys, and cross-contract calls other than plain transfers.
+// Contract panics are caught with catch_unwind, so this only works in debug builds, see MockCoordinator::new.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use coordinator::coordinator::Coordinator;
+use near_sdk::json_types::U128;
+use near_sdk::mock::{with_mocked_blockchain, VmAction};
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::{env, AccountId, Balance, MockedBlockchain, RuntimeFeesConfig, VMConfig, VMContext};
+use near_units::parse_near;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+pub mod dispatch;
+pub mod faults;
+pub mod rpc;
+
+use crate::faults::{Fault, FaultKind, Faults};
+
+// Accounts the mock hasn't seen yet start with this balance, so tests don't need to fund anyone
+pub const DEFAULT_ACCOUNT_BALANCE: Balance = parse_near!("100 N");
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MockConfig {
+    pub coordinator_id: AccountId,
+    // Block timestamp of the first block, in nanoseconds
+    pub start_timestamp: u64,
+    // Seeds env::random_seed, the same seed and calls give the same elections
+    pub seed: u64,
+    // Balance of the coordinator account before init
+    pub coordinator_balance: Balance,
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        Self {
+            coordinator_id: "coordinator.test.near".parse().unwrap(),
+            start_timestamp: 1_700_000_000_000_000_000,
+            seed: 0,
+            coordinator_balance: DEFAULT_ACCOUNT_BALANCE,
+        }
+    }
+}
+
+// The result of a successful call
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Outcome {
+    // The method's return value, exactly as the contract serialized it
+    pub value: Box<RawValue>,
+    pub logs: Vec<String>,
+    pub block_height: u64,
+    pub block_timestamp: u64,
+    // Set by a delay fault, the RPC server holds the response back this long
+    #[serde(skip)]
+    pub delay: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MockError {
+    // There's no such method, or it can't be called this way
+    MethodNotFound { method: String },
+    // A call method was sent as a view
+    ProhibitedInView { method: String },
+    // The contract panicked, nothing it did was kept
+    Panic { message: String, logs: Vec<String> },
+    // The signer can't cover the attached deposit
+    NotEnoughBalance { account_id: AccountId, balance: U128, deposit: U128 },
+    // The call left the coordinator without enough balance to stake its storage
+    LackBalanceForState { amount: U128 },
+    // Injected, the call never reached the coordinator
+    Unavailable,
+    // Injected, the call was executed but the caller never heard back
+    Timeout,
+}
+
+impl fmt::Display for MockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MockError::MethodNotFound { method } => write!(f, "MethodNotFound: {}", method),
+            MockError::ProhibitedInView { method } => write!(f, "ProhibitedInView: {}", method),
+            MockError::Panic { message, .. } => write!(f, "Smart contract panicked: {}", message),
+            MockError::NotEnoughBalance { account_id, balance, deposit } => {
+                write!(f, "{} has {} but attached {}", account_id, balance.0, deposit.0)
+            }
+            MockError::LackBalanceForState { amount } => write!(f, "LackBalanceForState: needs {} more", amount.0),
+            MockError::Unavailable => write!(f, "coordinator unavailable"),
+            MockError::Timeout => write!(f, "timed out waiting for the transaction"),
+        }
+    }
+}
+
+impl std::error::Error for MockError {}
+
+impl MockError {
+    // The contract's panic message, for matching against what the chain reports
+    pub fn panic_message(&self) -> Option<&str> {
+        return match self {
+            MockError::Panic { message, .. } => Some(message),
+            _ => None,
+        };
+    }
+}
+
+impl Outcome {
+    pub fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        return serde_json::from_str(self.value.get());
+    }
+}
+
+// What a successful execution changed, applied to the mock only once the whole call succeeded
+struct Effects {
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+    storage_usage: u64,
+    account_balance: Balance,
+    transfers: Vec<(AccountId, Balance)>,
+    logs: Vec<String>,
+}
+
+pub struct MockCoordinator {
+    config: MockConfig,
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+    storage_usage: u64,
+    balances: HashMap<AccountId, Balance>,
+    block_height: u64,
+    block_timestamp: u64,
+    // Every log the coordinator emitted, in order
+    logs: Vec<String>,
+    faults: Faults,
+}
+
+impl MockCoordinator {
+    pub fn new(config: MockConfig) -> Self {
+        // In release, require! goes through env::panic_str which aborts natively, and the workspace profile aborts on panic
+        if !cfg!(debug_assertions) || !cfg!(panic = "unwind") {
+            panic!("The mock coordinator has to be built without --release, contract panics would abort the process");
+        }
+        let mut mock = Self {
+            storage: HashMap::new(),
+            storage_usage: 0,
+            balances: HashMap::from([(config.coordinator_id.clone(), config.coordinator_balance)]),
+            block_height: 1,
+            block_timestamp: config.start_timestamp,
+            logs: vec![],
+            faults: Faults::default(),
+            config,
+        };
+        let context = mock.context(&mock.config.coordinator_id.clone(), 0, false);
+        let (_, effects) = mock
+            .execute(context, || env::state_write(&Coordinator::init()))
+            .unwrap_or_else(|(message, _)| panic!("Coordinator init failed: {}", message));
+        mock.apply(effects);
+        return mock;
+    }
+
+    pub fn coordinator_id(&self) -> &AccountId {
+        return &self.config.coordinator_id;
+    }
+
+    pub fn block_height(&self) -> u64 {
+        return self.block_height;
+    }
+
+    pub fn block_timestamp(&self) -> u64 {
+        return self.block_timestamp;
+    }
+
+    // Moves the clock forward, the next call sees the new time
+    pub fn advance_time(&mut self, by: Duration) {
+        self.block_timestamp += by.as_nanos() as u64;
+    }
+
+    // Sets the block timestamp in nanoseconds, time can't go backwards
+    pub fn set_time(&mut self, timestamp: u64) -> anyhow::Result<()> {
+        anyhow::ensure!(timestamp >= self.block_timestamp, "Time can't go backwards, it's already {}", self.block_timestamp);
+        self.block_timestamp = timestamp;
+        return Ok(());
+    }
+
+    pub fn inject_fault(&mut self, fault: Fault) {
+        self.faults.inject(fault);
+    }
+
+    pub fn clear_faults(&mut self) {
+        self.faults.clear();
+    }
+
+    pub fn faults(&self) -> &[Fault] {
+        return self.faults.armed();
+    }
+
+    pub fn balance_of(&self, account_id: &AccountId) -> Balance {
+        return self.balances.get(account_id).copied().unwrap_or(DEFAULT_ACCOUNT_BALANCE);
+    }
+
+    pub fn storage_usage(&self) -> u64 {
+        return self.storage_usage;
+    }
+
+    pub fn logs(&self) -> &[String] {
+        return &self.logs;
+    }
+
+    // Runs a view method against the current state, views never change it
+    pub fn view(&mut self, method: &str, args: Value) -> Result<Outcome, MockError> {
+        if !dispatch::VIEW_METHODS.contains(&method) {
+            if dispatch::CALL_METHODS.contains(&method) {
+                return Err(MockError::ProhibitedInView { method: method.to_string() });
+            }
+            return Err(MockError::MethodNotFound { method: method.to_string() });
+        }
+        let fault = self.fire_fault(method, false)?;
+        let context = self.context(&self.config.coordinator_id.clone(), 0, true);
+        let result = self.execute(context, || {
+            let coordinator: Coordinator = env::state_read().expect("Coordinator is not initialized");
+            return dispatch::view(&coordinator, method, &args).expect("method is dispatched");
+        });
+        let (value, effects) = result.map_err(|(message, logs)| MockError::Panic { message, logs })?;
+        return Ok(self.outcome(value, effects.logs, fault));
+    }
+
+    // Sends a transaction from signer_id, attaching deposit yoctoNEAR
+    pub fn call(&mut self, signer_id: &AccountId, method: &str, args: Value, deposit: Balance) -> Result<Outcome, MockError> {
+        if !dispatch::CALL_METHODS.contains(&method) && !dispatch::VIEW_METHODS.contains(&method) {
+            return Err(MockError::MethodNotFound { method: method.to_string() });
+        }
+        let fault = self.fire_fault(method, true)?;
+        let balance = self.balance_of(signer_id);
+        if deposit > balance {
+            return Err(MockError::NotEnoughBalance {
+                account_id: signer_id.clone(),
+                balance: balance.into(),
+                deposit: deposit.into(),
+            });
+        }
+        self.block_height += 1;
+        let context = self.context(signer_id, deposit, false);
+        let payable = dispatch::PAYABLE_METHODS.contains(&method);
+        let private = dispatch::PRIVATE_METHODS.contains(&method);
+        let coordinator_id = self.config.coordinator_id.clone();
+        let result = self.execute(context, || {
+            // The same checks near_bindgen generates in front of every method
+            if deposit > 0 && !payable {
+                panic!("Method {} doesn't accept deposit", method);
+            }
+            if private && env::predecessor_account_id() != coordinator_id {
+                panic!("Method {} is private", method);
+            }
+            let mut coordinator: Coordinator = env::state_read().expect("Coordinator is not initialized");
+            let value = dispatch::call(&mut coordinator, method, &args).expect("method is dispatched");
+            env::state_write(&coordinator);
+            return value;
+        });
+        let (value, effects) = match result {
+            Ok(result) => result,
+            Err((message, logs)) => {
+                self.logs.extend(logs.iter().cloned());
+                return Err(MockError::Panic { message, logs });
+            }
+        };
+        let staked = effects.storage_usage as u128 * env::STORAGE_PRICE_PER_BYTE;
+        if staked > effects.account_balance {
+            return Err(MockError::LackBalanceForState { amount: (staked - effects.account_balance).into() });
+        }
+        let logs = effects.logs.clone();
+        *self.balances.entry(signer_id.clone()).or_insert(DEFAULT_ACCOUNT_BALANCE) -= deposit;
+        self.apply(effects);
+        if fault == Some(FaultKind::Timeout) {
+            return Err(MockError::Timeout);
+        }
+        return Ok(self.outcome(value, logs, fault));
+    }
+
+    fn outcome(&self, value: Box<RawValue>, logs: Vec<String>, fault: Option<FaultKind>) -> Outcome {
+        return Outcome {
+            value,
+            logs,
+            block_height: self.block_height,
+            block_timestamp: self.block_timestamp,
+            delay: match fault {
+                Some(FaultKind::Delay { ms }) => Some(Duration::from_millis(ms)),
+                _ => None,
+            },
+        };
+    }
+
+    // Fires the armed fault for this method. Faults that stop the method return an error, the rest are handed back.
+    fn fire_fault(&mut self, method: &str, is_call: bool) -> Result<Option<FaultKind>, MockError> {
+        return match self.faults.fire(method) {
+            Some(FaultKind::Panic { message }) => Err(MockError::Panic { message, logs: vec![] }),
+            Some(FaultKind::Unavailable) => Err(MockError::Unavailable),
+            // A view that times out has nothing to keep
+            Some(FaultKind::Timeout) if !is_call => Err(MockError::Timeout),
+            other => Ok(other),
+        };
+    }
+
+    // Runs f against a fresh mocked blockchain over a copy of the storage, catching contract panics
+    #[allow(clippy::type_complexity)]
+    fn execute<R>(&self, context: VMContext, f: impl FnOnce() -> R) -> Result<(R, Effects), (String, Vec<String>)> {
+        env::set_blockchain_interface(MockedBlockchain::new(
+            context,
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            vec![],
+            self.storage.clone(),
+            HashMap::new(),
+            None,
+        ));
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let logs = with_mocked_blockchain(|blockchain| blockchain.logs());
+        let value = result.map_err(|payload| (panic_message(payload), logs.clone()))?;
+        let mut transfers = vec![];
+        for receipt in with_mocked_blockchain(|blockchain| blockchain.created_receipts()) {
+            for action in receipt.actions {
+                if let VmAction::Transfer { deposit } = action {
+                    transfers.push((receipt.receiver_id.clone(), deposit));
+                }
+            }
+        }
+        let effects = Effects {
+            storage_usage: env::storage_usage(),
+            account_balance: env::account_balance(),
+            storage: with_mocked_blockchain(|blockchain| blockchain.take_storage()),
+            transfers,
+            logs,
+        };
+        return Ok((value, effects));
+    }
+
+    fn apply(&mut self, effects: Effects) {
+        self.storage = effects.storage;
+        self.storage_usage = effects.storage_usage;
+        self.balances.insert(self.config.coordinator_id.clone(), effects.account_balance);
+        for (receiver_id, amount) in effects.transfers {
+            *self.balances.entry(receiver_id).or_insert(DEFAULT_ACCOUNT_BALANCE) += amount;
+        }
+        self.logs.extend(effects.logs);
+    }
+
+    fn context(&self, signer_id: &AccountId, deposit: Balance, is_view: bool) -> VMContext {
+        return VMContextBuilder::new()
+            .current_account_id(self.config.coordinator_id.clone())
+            .signer_account_id(signer_id.clone())
+            .predecessor_account_id(signer_id.clone())
+            .block_index(self.block_height)
+            .block_timestamp(self.block_timestamp)
+            .account_balance(self.balance_of(&self.config.coordinator_id) + deposit)
+            .attached_deposit(deposit)
+            .storage_usage(self.storage_usage)
+            .random_seed(self.random_seed())
+            .is_view(is_view)
+            .build();
+    }
+
+    // Deterministic per block, so a seed replays the same elections
+    fn random_seed(&self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        for (i, chunk) in seed.chunks_mut(8).enumerate() {
+            let mut hasher = DefaultHasher::new();
+            (self.config.seed, self.block_height, i).hash(&mut hasher);
+            chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+        }
+        return seed;
+    }
+}
+
+// near-sdk's mocked env::panic_str unwraps a HostError, pull the contract's message back out of it
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    let raw = match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => return "unknown panic".to_string(),
+        },
+    };
+    const MARKER: &str = "panic_msg: \"";
+    let Some(start) = raw.find(MARKER) else {
+        return raw;
+    };
+    let mut message = String::new();
+    let mut chars = raw[start + MARKER.len()..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => message.push('\n'),
+                Some('t') => message.push('\t'),
+                Some(other) => message.push(other),
+                None => break,
+            },
+            c => message.push(c),
+        }
+    }
+    return message;
+}
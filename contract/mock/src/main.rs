@@ -0,0 +1,35 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+use mock_coordinator::{rpc, MockConfig, MockCoordinator};
+use near_sdk::AccountId;
+
+/// Serve an in-memory coordinator over JSON-RPC, see src/rpc.rs for the methods
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1:3031")]
+    listen: SocketAddr,
+    #[arg(long, default_value = "coordinator.test.near")]
+    coordinator_id: AccountId,
+    /// Block timestamp to start at, in milliseconds since the epoch
+    #[arg(long)]
+    start_time_ms: Option<u64>,
+    /// Seeds the coordinator's randomness, the same seed replays the same elections
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let mut config = MockConfig { coordinator_id: args.coordinator_id, seed: args.seed, ..Default::default() };
+    if let Some(start_time_ms) = args.start_time_ms {
+        config.start_timestamp = start_time_ms * 1_000_000;
+    }
+    // Contract panics are expected and reported back to the caller, don't print a backtrace for each one
+    std::panic::set_hook(Box::new(|_| {}));
+    let (addr, _, server) = rpc::spawn(MockCoordinator::new(config), args.listen).await?;
+    println!("Mock coordinator listening on http://{}", addr);
+    server.await?;
+    return Ok(());
+}
@@ -0,0 +1,204 @@
+// A JSON-RPC 2.0 endpoint over a MockCoordinator, every request is a POST to /
+//
+// view              {method_name, args}                         -> Outcome
+// call              {signer_id, method_name, args, deposit?}    -> Outcome, deposit is a yoctoNEAR string
+// mock_advance_time {ms}                                        -> MockStatus
+// mock_set_time     {timestamp_ms}                              -> MockStatus
+// mock_inject_fault Fault                                       -> the armed faults
+// mock_clear_faults                                             -> null
+// mock_view_account {account_id}                                -> {account_id, balance}
+// mock_logs         {from?}                                     -> every log from index `from` on
+// mock_status                                                   -> MockStatus
+//
+// Contract errors come back with code -32000 and the MockError as data.
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+
+use crate::faults::Fault;
+use crate::{MockCoordinator, MockError};
+
+pub const CONTRACT_ERROR: i64 = -32000;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+
+pub type SharedMock = Arc<Mutex<MockCoordinator>>;
+
+#[derive(Deserialize, Debug)]
+pub struct Request {
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MockStatus {
+    pub coordinator_id: AccountId,
+    pub block_height: u64,
+    pub block_timestamp: u64,
+}
+
+#[derive(Deserialize)]
+struct ViewParams {
+    method_name: String,
+    #[serde(default)]
+    args: Value,
+}
+
+#[derive(Deserialize)]
+struct CallParams {
+    signer_id: AccountId,
+    method_name: String,
+    #[serde(default)]
+    args: Value,
+    deposit: Option<U128>,
+}
+
+#[derive(Deserialize)]
+struct AdvanceTimeParams {
+    ms: u64,
+}
+
+#[derive(Deserialize)]
+struct SetTimeParams {
+    timestamp_ms: u64,
+}
+
+#[derive(Deserialize)]
+struct AccountParams {
+    account_id: AccountId,
+}
+
+#[derive(Deserialize)]
+struct LogsParams {
+    from: Option<usize>,
+}
+
+pub fn router(mock: SharedMock) -> Router {
+    return Router::new().route("/", post(handle)).with_state(mock);
+}
+
+// Serves the mock on addr until the task is aborted, returns the address actually bound so port 0 works
+pub async fn spawn(mock: MockCoordinator, addr: SocketAddr) -> anyhow::Result<(SocketAddr, SharedMock, JoinHandle<()>)> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let addr = listener.local_addr()?;
+    let mock = Arc::new(Mutex::new(mock));
+    let app = router(mock.clone());
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("mock coordinator server failed");
+    });
+    return Ok((addr, mock, server));
+}
+
+async fn handle(State(mock): State<SharedMock>, Json(request): Json<Request>) -> Json<Value> {
+    let (result, delay) = dispatch(&mock, &request.method, request.params);
+    // The lock is released by now, a delayed response doesn't hold up other clients
+    if let Some(delay) = delay {
+        tokio::time::sleep(delay).await;
+    }
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": request.id, "error": error }),
+    };
+    return Json(response);
+}
+
+fn dispatch(mock: &SharedMock, method: &str, params: Value) -> (Result<Value, RpcError>, Option<Duration>) {
+    // A contract panic never happens while the lock is held, near-sdk's mock is caught inside MockCoordinator
+    let mut mock = mock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let result = match method {
+        "view" => params_of::<ViewParams>(params)
+            .and_then(|p| mock.view(&p.method_name, p.args).map_err(contract_error)),
+        "call" => params_of::<CallParams>(params).and_then(|p| {
+            let deposit = p.deposit.map(|d| d.0).unwrap_or(0);
+            return mock.call(&p.signer_id, &p.method_name, p.args, deposit).map_err(contract_error);
+        }),
+        _ => return (admin(&mut mock, method, params), None),
+    };
+    return match result {
+        Ok(outcome) => {
+            let delay = outcome.delay;
+            (Ok(serde_json::to_value(outcome).expect("outcomes always serialize")), delay)
+        }
+        Err(error) => (Err(error), None),
+    };
+}
+
+// The mock_ methods, for tests to drive the mock itself
+fn admin(mock: &mut MockCoordinator, method: &str, params: Value) -> Result<Value, RpcError> {
+    return match method {
+        "mock_advance_time" => {
+            let p: AdvanceTimeParams = params_of(params)?;
+            mock.advance_time(Duration::from_millis(p.ms));
+            Ok(status(mock))
+        }
+        "mock_set_time" => {
+            let p: SetTimeParams = params_of(params)?;
+            mock.set_time(p.timestamp_ms * 1_000_000).map_err(|e| invalid_params(e.to_string()))?;
+            Ok(status(mock))
+        }
+        "mock_inject_fault" => {
+            mock.inject_fault(params_of::<Fault>(params)?);
+            Ok(json!(mock.faults()))
+        }
+        "mock_clear_faults" => {
+            mock.clear_faults();
+            Ok(Value::Null)
+        }
+        "mock_view_account" => {
+            let p: AccountParams = params_of(params)?;
+            Ok(json!({ "account_id": p.account_id, "balance": U128(mock.balance_of(&p.account_id)) }))
+        }
+        "mock_logs" => {
+            let p: LogsParams = params_of(params)?;
+            Ok(json!(mock.logs().iter().skip(p.from.unwrap_or(0)).collect::<Vec<_>>()))
+        }
+        "mock_status" => Ok(status(mock)),
+        _ => Err(RpcError { code: METHOD_NOT_FOUND, message: format!("Unknown method {}", method), data: None }),
+    };
+}
+
+fn status(mock: &MockCoordinator) -> Value {
+    return json!(MockStatus {
+        coordinator_id: mock.coordinator_id().clone(),
+        block_height: mock.block_height(),
+        block_timestamp: mock.block_timestamp(),
+    });
+}
+
+fn params_of<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // Methods without params may leave them out entirely
+    let params = if params.is_null() { json!({}) } else { params };
+    return serde_json::from_value(params).map_err(|e| invalid_params(e.to_string()));
+}
+
+fn invalid_params(message: String) -> RpcError {
+    return RpcError { code: INVALID_PARAMS, message, data: None };
+}
+
+fn contract_error(error: MockError) -> RpcError {
+    return RpcError {
+        code: CONTRACT_ERROR,
+        message: error.to_string(),
+        data: Some(serde_json::to_value(&error).expect("errors always serialize")),
+    };
+}
@@ -0,0 +1,234 @@
+// Runs the same scenario against the mock and the real contract, and checks they agree. The sandbox half needs a local
+// sandbox and the release wasm, build it with ./build.sh first, then run cargo test -p mock-coordinator -- --ignored
+use std::sync::Mutex;
+
+use coordinator_client::ClientError;
+use mock_coordinator::{MockConfig, MockCoordinator, MockError};
+use near_units::parse_near;
+use near_workspaces::network::Sandbox;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Worker};
+use serde_json::{json, Value};
+
+const COORDINATOR_WASM_FILEPATH: &str = "../target/wasm32-unknown-unknown/release/coordinator.wasm";
+const ACCOUNTS: usize = 3;
+
+// Where the scenario runs. Errors are the contract's panic message when there is one.
+trait Backend {
+    fn account_id(&self, account: usize) -> String;
+    fn coordinator_id(&self) -> String;
+    async fn call(&self, account: usize, method: &str, args: Value, deposit: u128) -> Result<Value, String>;
+    async fn view(&self, method: &str, args: Value) -> Result<Value, String>;
+}
+
+struct Mock(Mutex<MockCoordinator>);
+
+impl Mock {
+    fn new() -> Self {
+        return Self(Mutex::new(MockCoordinator::new(MockConfig::default())));
+    }
+}
+
+fn mock_error(error: MockError) -> String {
+    return error.panic_message().map(|m| m.to_string()).unwrap_or_else(|| error.to_string());
+}
+
+impl Backend for Mock {
+    fn account_id(&self, account: usize) -> String {
+        return format!("account{}.test.near", account);
+    }
+
+    fn coordinator_id(&self) -> String {
+        return self.0.lock().unwrap().coordinator_id().to_string();
+    }
+
+    async fn call(&self, account: usize, method: &str, args: Value, deposit: u128) -> Result<Value, String> {
+        let signer_id = self.account_id(account).parse().unwrap();
+        let outcome = self.0.lock().unwrap().call(&signer_id, method, args, deposit).map_err(mock_error)?;
+        return Ok(outcome.json().unwrap());
+    }
+
+    async fn view(&self, method: &str, args: Value) -> Result<Value, String> {
+        let outcome = self.0.lock().unwrap().view(method, args).map_err(mock_error)?;
+        return Ok(outcome.json().unwrap());
+    }
+}
+
+struct Chain {
+    accounts: Vec<Account>,
+    coordinator_id: near_workspaces::AccountId,
+}
+
+impl Chain {
+    async fn new(worker: &Worker<Sandbox>) -> anyhow::Result<Self> {
+        let coordinator_wasm = std::fs::read(COORDINATOR_WASM_FILEPATH)?;
+        let contract = worker.dev_deploy(&coordinator_wasm).await?;
+        contract.call("init").max_gas().args_json(json!({})).transact().await?.into_result()?;
+        let mut accounts = vec![];
+        for _ in 0..ACCOUNTS {
+            accounts.push(worker.dev_create_account().await?);
+        }
+        return Ok(Self { accounts, coordinator_id: contract.id().clone() });
+    }
+}
+
+fn chain_error(description: String) -> String {
+    let error = ClientError::from_failure(&description);
+    return error.panic_message().map(|m| m.to_string()).unwrap_or(description);
+}
+
+impl Backend for Chain {
+    fn account_id(&self, account: usize) -> String {
+        return self.accounts[account].id().to_string();
+    }
+
+    fn coordinator_id(&self) -> String {
+        return self.coordinator_id.to_string();
+    }
+
+    async fn call(&self, account: usize, method: &str, args: Value, deposit: u128) -> Result<Value, String> {
+        let outcome = self.accounts[account]
+            .call(&self.coordinator_id, method)
+            .args_json(args)
+            .deposit(NearToken::from_yoctonear(deposit))
+            .max_gas()
+            .transact()
+            .await
+            .map_err(|e| e.to_string())?
+            .into_result()
+            .map_err(|failure| chain_error(failure.to_string()))?;
+        // Methods returning nothing or a Promise have no return value on chain, the mock returns null for them
+        return Ok(outcome.json().unwrap_or(Value::Null));
+    }
+
+    async fn view(&self, method: &str, args: Value) -> Result<Value, String> {
+        let result = self.accounts[0]
+            .view(&self.coordinator_id, method)
+            .args_json(args)
+            .await
+            .map_err(|e| chain_error(e.to_string()))?;
+        return Ok(result.json().unwrap());
+    }
+}
+
+// Everything the scenario observed, with account ids replaced so runs on different accounts compare equal
+struct Transcript<'a, B: Backend> {
+    backend: &'a B,
+    lines: Vec<String>,
+}
+
+impl<'a, B: Backend> Transcript<'a, B> {
+    fn record(&mut self, label: &str, observed: impl std::fmt::Debug) {
+        let mut line = format!("{}: {:?}", label, observed);
+        line = line.replace(&self.backend.coordinator_id(), "<coordinator>");
+        for account in 0..ACCOUNTS {
+            line = line.replace(&self.backend.account_id(account), &format!("<account{}>", account));
+        }
+        self.lines.push(line);
+    }
+}
+
+fn bounty_args(min_nodes: u64) -> Value {
+    return json!({
+        "file_location": "https://github.com/ad0ll/docker-hello-world.git",
+        "file_download_protocol": "GIT",
+        "min_nodes": min_nodes,
+        "timeout_seconds": 60,
+        "network_required": false,
+        "gpu_required": false,
+        "amt_storage": parse_near!("1N").to_string(),
+        "amt_node_reward": parse_near!("1N").to_string(),
+        "options": { "max_nodes_per_owner": 2 },
+    });
+}
+
+// Account 0 owns the bounty, accounts 1 and 2 run two nodes each, so the bounty lets an owner have two elected nodes
+async fn run_scenario<B: Backend>(backend: &B) -> Vec<String> {
+    let mut t = Transcript { backend, lines: vec![] };
+    for operator in 1..ACCOUNTS {
+        for i in 0..2 {
+            let args = json!({ "name": format!("node{}", i), "absolute_timeout": 600000, "allow_network": true, "allow_gpu": false });
+            let node = backend.call(operator, "register_node", args, parse_near!("1N")).await;
+            t.record("register_node", node.map(|node| node["id"].clone()));
+        }
+    }
+    let args = json!({ "name": "node0", "absolute_timeout": 600000, "allow_network": true, "allow_gpu": false });
+    t.record("register_node again", backend.call(1, "register_node", args, parse_near!("1N")).await);
+    t.record("get_node_count", backend.view("get_node_count", json!({})).await);
+    t.record("create_bounty too many nodes", backend.call(0, "create_bounty", bounty_args(50), parse_near!("2N")).await);
+
+    let bounty = backend.call(0, "create_bounty", bounty_args(2), parse_near!("2N")).await.unwrap();
+    let bounty_id = bounty["id"].clone();
+    t.record("create_bounty", (&bounty["status"], &bounty["min_nodes"], &bounty["amt_node_reward"]));
+    let elected: Vec<String> = serde_json::from_value(backend.view("get_bounty_elected_nodes", json!({ "bounty_id": bounty_id })).await.unwrap()).unwrap();
+    t.record("elected", elected.len());
+
+    let mut owners = vec![];
+    for node_id in &elected {
+        let node = backend.view("get_node", json!({ "node_id": node_id })).await.unwrap();
+        let owner = (1..ACCOUNTS).find(|a| backend.account_id(*a) == node["owner_id"].as_str().unwrap()).unwrap();
+        owners.push(owner);
+        let args = json!({ "bounty_id": bounty_id, "node_id": node_id });
+        t.record("should_post_answer", backend.view("should_post_answer", args).await);
+        let args = json!({ "bounty_id": bounty_id, "node_id": node_id, "answer": "hello world", "message": "", "status": "SUCCESS" });
+        let response = backend.call(owner, "post_answer", args, 0).await;
+        t.record("post_answer", response.map(|r| r["status"].clone()));
+    }
+    let args = json!({ "bounty_id": bounty_id, "node_id": elected[0], "answer": "hello world", "message": "", "status": "SUCCESS" });
+    t.record("post_answer after close", backend.call(owners[0], "post_answer", args, 0).await);
+    let bounty = backend.view("get_bounty", json!({ "bounty_id": bounty_id })).await.unwrap();
+    t.record("bounty", (&bounty["status"], &bounty["dispute_status"]));
+    t.record("get_bounty_result", backend.view("get_bounty_result", json!({ "bounty_id": bounty_id })).await);
+    t.record("get_bounty_aggregate_result", backend.view("get_bounty_aggregate_result", json!({ "bounty_id": bounty_id })).await);
+
+    for (node_id, owner) in elected.iter().zip(&owners) {
+        let args = json!({ "node_id": node_id, "bounty_id": bounty_id });
+        t.record("should_collect_reward", backend.view("should_collect_reward", args.clone()).await);
+        t.record("get_pending_payout", backend.view("get_pending_payout", args.clone()).await.map(|p| p["net"].clone()));
+        t.record("collect_reward", backend.call(*owner, "collect_reward", args.clone(), 0).await);
+        t.record("collect_reward again", backend.call(*owner, "collect_reward", args, 0).await);
+    }
+    let report = backend.view("get_solvency_report", json!({})).await.unwrap();
+    t.record("solvency", (&report["solvent"], &report["ledger_consistent"], &report["total_escrowed"]));
+    t.record("get_total_payouts", backend.view("get_total_payouts", json!({})).await);
+
+    let node_id = elected[0].clone();
+    t.record("deposit on non-payable", backend.call(owners[0], "set_node_offline", json!({ "node_id": node_id, "offline": true }), 1).await);
+    t.record("private method", backend.call(owners[0], "get_node_padding", json!({ "min_nodes": 2 }), 0).await);
+    t.record("not the owner", backend.call(0, "set_node_offline", json!({ "node_id": node_id, "offline": true }), 0).await);
+    return t.lines;
+}
+
+#[tokio::test]
+async fn test_mock_is_deterministic() {
+    assert_eq!(run_scenario(&Mock::new()).await, run_scenario(&Mock::new()).await);
+}
+
+#[tokio::test]
+async fn test_mock_panics_map_to_client_errors() {
+    let mock = Mock::new();
+    let args = json!({ "name": "node0", "absolute_timeout": 600000, "allow_network": true, "allow_gpu": false });
+    mock.call(1, "register_node", args.clone(), parse_near!("1N")).await.unwrap();
+    let error = ClientError::from_panic_message(&mock.call(1, "register_node", args, parse_near!("1N")).await.unwrap_err());
+    assert!(matches!(error, ClientError::NodeAlreadyRegistered(_)), "got {:?}", error);
+
+    let error = mock.view("get_bounty_or_panic", json!({ "bounty_id": "missing.coordinator.test.near" })).await.unwrap_err();
+    let error = ClientError::from_panic_message(&error);
+    assert!(matches!(error, ClientError::BountyNotFound(_)), "got {:?}", error);
+
+    let error = ClientError::from_panic_message(&mock.call(0, "create_bounty", bounty_args(5), parse_near!("2N")).await.unwrap_err());
+    assert!(matches!(error, ClientError::NotEnoughNodes(_)), "got {:?}", error);
+}
+
+#[tokio::test]
+#[ignore = "needs a local sandbox and the release wasm"]
+async fn test_mock_matches_sandbox() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let chain = run_scenario(&Chain::new(&worker).await?).await;
+    let mock = run_scenario(&Mock::new()).await;
+    for (chain, mock) in chain.iter().zip(&mock) {
+        assert_eq!(chain, mock);
+    }
+    assert_eq!(chain.len(), mock.len());
+    Ok(())
+}
@@ -0,0 +1,236 @@
+// Drives the mock over HTTP, the way a client under test would
+use std::time::{Duration, Instant};
+
+use mock_coordinator::rpc::{self, RpcError};
+use mock_coordinator::{MockConfig, MockCoordinator};
+use near_units::parse_near;
+use serde_json::{json, Value};
+
+struct MockRpc {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl MockRpc {
+    async fn start() -> anyhow::Result<Self> {
+        let (addr, _, _) = rpc::spawn(MockCoordinator::new(MockConfig::default()), "127.0.0.1:0".parse()?).await?;
+        return Ok(Self { http: reqwest::Client::new(), url: format!("http://{}/", addr) });
+    }
+
+    async fn request(&self, method: &str, params: Value) -> anyhow::Result<Result<Value, RpcError>> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = self.http.post(&self.url).json(&request).send().await?.json().await?;
+        if let Some(error) = response.get("error") {
+            return Ok(Err(serde_json::from_value(error.clone())?));
+        }
+        return Ok(Ok(response["result"].take()));
+    }
+
+    async fn view(&self, method_name: &str, args: Value) -> anyhow::Result<Value> {
+        let outcome = self.request("view", json!({ "method_name": method_name, "args": args })).await?;
+        return Ok(outcome.map_err(|e| anyhow::anyhow!("{}", e.message))?["value"].take());
+    }
+
+    async fn call(&self, signer_id: &str, method_name: &str, args: Value, deposit: u128) -> anyhow::Result<Result<Value, RpcError>> {
+        let params = json!({
+            "signer_id": signer_id,
+            "method_name": method_name,
+            "args": args,
+            "deposit": deposit.to_string(),
+        });
+        return Ok(self.request("call", params).await?.map(|mut outcome| outcome["value"].take()));
+    }
+
+    async fn call_ok(&self, signer_id: &str, method_name: &str, args: Value, deposit: u128) -> anyhow::Result<Value> {
+        return self.call(signer_id, method_name, args, deposit).await?.map_err(|e| anyhow::anyhow!("{}", e.message));
+    }
+
+    async fn balance(&self, account_id: &str) -> anyhow::Result<u128> {
+        let account = self.request("mock_view_account", json!({ "account_id": account_id })).await?.unwrap();
+        return Ok(account["balance"].as_str().unwrap().parse()?);
+    }
+
+    async fn register_node(&self, owner: &str, name: &str) -> anyhow::Result<String> {
+        let args = json!({ "name": name, "absolute_timeout": 600000, "allow_network": true, "allow_gpu": false });
+        let node = self.call_ok(owner, "register_node", args, parse_near!("1N")).await?;
+        return Ok(node["id"].as_str().unwrap().to_string());
+    }
+}
+
+fn panic_message(error: &RpcError) -> &str {
+    let data = error.data.as_ref().expect("contract errors carry data");
+    assert_eq!(data["kind"], "panic", "expected a contract panic, got {:?}", error);
+    return data["message"].as_str().unwrap();
+}
+
+fn bounty_args(min_nodes: u64, dispute_window_seconds: u64) -> Value {
+    return json!({
+        "file_location": "https://github.com/ad0ll/docker-hello-world.git",
+        "file_download_protocol": "GIT",
+        "min_nodes": min_nodes,
+        "timeout_seconds": 60,
+        "network_required": false,
+        "gpu_required": false,
+        "amt_storage": parse_near!("1N").to_string(),
+        "amt_node_reward": parse_near!("1N").to_string(),
+        "options": { "dispute_window_seconds": dispute_window_seconds },
+    });
+}
+
+#[tokio::test]
+async fn test_bounty_lifecycle_over_http() -> anyhow::Result<()> {
+    let mock = MockRpc::start().await?;
+    // Each elected node needs a different owner
+    let operators = ["alice.test.near", "bob.test.near", "carol.test.near"];
+    for operator in operators {
+        for i in 0..2 {
+            mock.register_node(operator, &format!("node{}", i)).await?;
+        }
+    }
+    assert_eq!(mock.view("get_node_count", json!({})).await?, 6);
+
+    let owner = "owner.test.near";
+    let bounty = mock.call_ok(owner, "create_bounty", bounty_args(2, 3600), parse_near!("2N")).await?;
+    let bounty_id = bounty["id"].as_str().unwrap().to_string();
+    assert_eq!(mock.balance(owner).await?, parse_near!("98N"));
+
+    let elected: Vec<String> = serde_json::from_value(mock.view("get_bounty_elected_nodes", json!({ "bounty_id": bounty_id })).await?)?;
+    assert!(elected.len() >= 2, "expected at least min_nodes to be elected");
+    for node_id in &elected {
+        let node = mock.view("get_node", json!({ "node_id": node_id })).await?;
+        let operator = node["owner_id"].as_str().unwrap();
+        let args = json!({ "bounty_id": bounty_id, "node_id": node_id });
+        if mock.view("should_post_answer", args.clone()).await? != json!(true) {
+            continue;
+        }
+        let args = json!({ "bounty_id": bounty_id, "node_id": node_id, "answer": "hello world", "message": "", "status": "SUCCESS" });
+        mock.call_ok(operator, "post_answer", args, 0).await?;
+    }
+    let bounty = mock.view("get_bounty", json!({ "bounty_id": bounty_id })).await?;
+    assert_eq!(bounty["status"], "Success");
+
+    // Payouts are locked for the dispute window, the clock only moves when the test says so
+    let node_id = &elected[0];
+    let operator = mock.view("get_node", json!({ "node_id": node_id })).await?["owner_id"].as_str().unwrap().to_string();
+    let args = json!({ "node_id": node_id, "bounty_id": bounty_id });
+    let error = mock.call(&operator, "collect_reward", args.clone(), 0).await?.unwrap_err();
+    assert!(panic_message(&error).starts_with("Payouts for this bounty are locked"), "got {:?}", error);
+
+    mock.request("mock_advance_time", json!({ "ms": 3_601_000 })).await?.unwrap();
+    assert_eq!(mock.view("should_collect_reward", args.clone()).await?, json!(true));
+    let net: u128 = mock.view("get_pending_payout", args.clone()).await?["net"].as_str().unwrap().parse()?;
+    assert!(net > 0);
+    let before = mock.balance(&operator).await?;
+    mock.call_ok(&operator, "collect_reward", args.clone(), 0).await?;
+    assert_eq!(mock.balance(&operator).await?, before + net);
+
+    let error = mock.call(&operator, "collect_reward", args, 0).await?.unwrap_err();
+    assert_eq!(panic_message(&error), "You have already claimed your payout");
+    let report = mock.view("get_solvency_report", json!({})).await?;
+    assert_eq!(report["solvent"], true);
+    assert_eq!(report["ledger_consistent"], true);
+
+    let logs: Vec<String> = serde_json::from_value(mock.request("mock_logs", json!({})).await?.unwrap())?;
+    assert!(logs.iter().any(|log| log.starts_with("EVENT_JSON:") && log.contains("\"bounty_completed\"")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_calls_are_rolled_back() -> anyhow::Result<()> {
+    let mock = MockRpc::start().await?;
+    let args = json!({ "name": "node0", "absolute_timeout": 600000, "allow_network": true, "allow_gpu": false });
+    let error = mock.call("alice.test.near", "register_node", args, 0).await?.unwrap_err();
+    assert_eq!(panic_message(&error), "Must include a refundable deposit of 1 NEAR to register a node");
+    assert_eq!(mock.view("get_node_count", json!({})).await?, 0);
+    assert_eq!(mock.balance("alice.test.near").await?, parse_near!("100N"));
+
+    // A panic after the node was inserted still leaves nothing behind
+    mock.register_node("alice.test.near", "node0").await?;
+    let error = mock.call("owner.test.near", "create_bounty", bounty_args(5, 0), parse_near!("2N")).await?.unwrap_err();
+    assert!(panic_message(&error).contains("nodes"), "got {:?}", error);
+    assert_eq!(mock.view("get_bounty_count", json!({})).await?, 0);
+    assert_eq!(mock.balance("owner.test.near").await?, parse_near!("100N"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bindgen_checks_are_enforced() -> anyhow::Result<()> {
+    let mock = MockRpc::start().await?;
+    let node_id = mock.register_node("alice.test.near", "node0").await?;
+
+    let args = json!({ "node_id": node_id, "offline": true });
+    let error = mock.call("alice.test.near", "set_node_offline", args, 1).await?.unwrap_err();
+    assert_eq!(panic_message(&error), "Method set_node_offline doesn't accept deposit");
+
+    let error = mock.call("alice.test.near", "get_node_padding", json!({ "min_nodes": 1 }), 0).await?.unwrap_err();
+    assert_eq!(panic_message(&error), "Method get_node_padding is private");
+
+    let error = mock.request("view", json!({ "method_name": "remove_all_nodes" })).await?.unwrap_err();
+    assert_eq!(error.data.unwrap()["kind"], "prohibited_in_view");
+    let error = mock.request("view", json!({ "method_name": "no_such_method" })).await?.unwrap_err();
+    assert_eq!(error.data.unwrap()["kind"], "method_not_found");
+    let error = mock.request("view", json!({ "method_name": "get_node", "args": { "node_id": 5 } })).await?.unwrap_err();
+    assert!(panic_message(&error).starts_with("Failed to deserialize input from JSON."), "got {:?}", error);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_injected_faults() -> anyhow::Result<()> {
+    let mock = MockRpc::start().await?;
+
+    // A panic fault fires the given number of times, the method never runs
+    let fault = json!({ "method": "register_node", "times": 1, "kind": "panic", "message": "Exceeded the prepaid gas" });
+    mock.request("mock_inject_fault", fault).await?.unwrap();
+    let args = json!({ "name": "node0", "absolute_timeout": 600000, "allow_network": true, "allow_gpu": false });
+    let error = mock.call("alice.test.near", "register_node", args, parse_near!("1N")).await?.unwrap_err();
+    assert_eq!(panic_message(&error), "Exceeded the prepaid gas");
+    assert_eq!(mock.view("get_node_count", json!({})).await?, 0);
+    mock.register_node("alice.test.near", "node0").await?;
+
+    // Unavailable never reaches the coordinator, timeout runs the call but loses the response
+    mock.request("mock_inject_fault", json!({ "method": "register_node", "times": 1, "kind": "unavailable" })).await?.unwrap();
+    let args = json!({ "name": "node1", "absolute_timeout": 600000, "allow_network": true, "allow_gpu": false });
+    let error = mock.call("alice.test.near", "register_node", args.clone(), parse_near!("1N")).await?.unwrap_err();
+    assert_eq!(error.data.unwrap()["kind"], "unavailable");
+    assert_eq!(mock.view("get_node_count", json!({})).await?, 1);
+    mock.request("mock_inject_fault", json!({ "method": "register_node", "times": 1, "kind": "timeout" })).await?.unwrap();
+    let error = mock.call("alice.test.near", "register_node", args, parse_near!("1N")).await?.unwrap_err();
+    assert_eq!(error.data.unwrap()["kind"], "timeout");
+    assert_eq!(mock.view("get_node_count", json!({})).await?, 2);
+
+    // Delays hold the response back without blocking other requests
+    mock.request("mock_inject_fault", json!({ "method": "get_node_count", "kind": "delay", "ms": 300 })).await?.unwrap();
+    let started = Instant::now();
+    let (delayed, other) = tokio::join!(mock.view("get_node_count", json!({})), async {
+        let value = mock.view("get_bounty_count", json!({})).await;
+        (value, started.elapsed())
+    });
+    assert_eq!(delayed?, 2);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    assert_eq!(other.0?, 0);
+    assert!(other.1 < Duration::from_millis(300));
+
+    mock.request("mock_clear_faults", json!({})).await?.unwrap();
+    let started = Instant::now();
+    mock.view("get_node_count", json!({})).await?;
+    assert!(started.elapsed() < Duration::from_millis(300));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_time_control() -> anyhow::Result<()> {
+    let mock = MockRpc::start().await?;
+    let status = mock.request("mock_status", json!({})).await?.unwrap();
+    let start = status["block_timestamp"].as_u64().unwrap();
+
+    let status = mock.request("mock_advance_time", json!({ "ms": 1500 })).await?.unwrap();
+    assert_eq!(status["block_timestamp"].as_u64().unwrap(), start + 1_500_000_000);
+    let status = mock.request("mock_set_time", json!({ "timestamp_ms": start / 1_000_000 + 60_000 })).await?.unwrap();
+    assert_eq!(status["block_timestamp"].as_u64().unwrap(), start + 60_000_000_000);
+
+    let error = mock.request("mock_set_time", json!({ "timestamp_ms": 0 })).await?.unwrap_err();
+    assert_eq!(error.code, rpc::INVALID_PARAMS);
+    let error = mock.request("mock_rewind", json!({})).await?.unwrap_err();
+    assert_eq!(error.code, rpc::METHOD_NOT_FOUND);
+    Ok(())
+}
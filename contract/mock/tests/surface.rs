@@ -0,0 +1,78 @@
+// Keeps the dispatch table in step with the contract, a method added to the coordinator without a mock entry fails here
+use mock_coordinator::dispatch::{CALL_METHODS, PAYABLE_METHODS, PRIVATE_METHODS, UNSUPPORTED_METHODS, VIEW_METHODS};
+
+const COORDINATOR_SRC: &str = include_str!("../../coordinator/src/coordinator/mod.rs");
+
+struct ContractMethod {
+    name: String,
+    payable: bool,
+    private: bool,
+    mutates: bool,
+}
+
+// Every pub fn in the #[near_bindgen] impl Coordinator block, with the attributes that matter to the mock
+fn contract_methods() -> Vec<ContractMethod> {
+    let start = COORDINATOR_SRC.find("#[near_bindgen]\nimpl Coordinator {").expect("coordinator impl block");
+    let mut methods = vec![];
+    let mut attributes: Vec<&str> = vec![];
+    let mut lines = COORDINATOR_SRC[start..].lines().skip(2);
+    while let Some(line) = lines.next() {
+        // The impl block is the only thing closed at column 0
+        if line == "}" {
+            break;
+        }
+        let line = line.trim();
+        if line.starts_with("#[") {
+            attributes.push(line);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("pub fn ") {
+            let name = rest.split(['(', '<']).next().unwrap().to_string();
+            // Signatures can wrap, read up to the opening brace to see the receiver
+            let mut signature = line.to_string();
+            while !signature.contains('{') {
+                signature.push_str(lines.next().unwrap_or("{").trim());
+            }
+            methods.push(ContractMethod {
+                name,
+                payable: attributes.iter().any(|a| a.starts_with("#[payable]")),
+                private: attributes.iter().any(|a| a.starts_with("#[private]")),
+                mutates: signature.contains("&mut self"),
+            });
+        }
+        if !line.is_empty() && !line.starts_with("//") {
+            attributes.clear();
+        }
+    }
+    return methods;
+}
+
+#[test]
+fn test_every_contract_method_is_dispatched() {
+    let methods = contract_methods();
+    assert!(methods.len() > 50, "only found {} methods, is the parser still right?", methods.len());
+    for method in &methods {
+        let listed = [VIEW_METHODS, CALL_METHODS, UNSUPPORTED_METHODS]
+            .iter()
+            .filter(|list| list.contains(&method.name.as_str()))
+            .count();
+        assert_eq!(listed, 1, "{} must be in exactly one of the mock's method lists", method.name);
+    }
+    for name in VIEW_METHODS.iter().chain(CALL_METHODS).chain(UNSUPPORTED_METHODS) {
+        assert!(methods.iter().any(|m| &m.name == name), "the mock lists {} but the contract has no such method", name);
+    }
+}
+
+#[test]
+fn test_method_attributes_match_the_contract() {
+    for method in contract_methods() {
+        if UNSUPPORTED_METHODS.contains(&method.name.as_str()) {
+            continue;
+        }
+        assert_eq!(PAYABLE_METHODS.contains(&method.name.as_str()), method.payable, "{} payable", method.name);
+        assert_eq!(PRIVATE_METHODS.contains(&method.name.as_str()), method.private, "{} private", method.name);
+        if method.mutates || method.payable || method.private {
+            assert!(CALL_METHODS.contains(&method.name.as_str()), "{} can't be a view", method.name);
+        }
+    }
+}
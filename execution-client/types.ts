@@ -153,6 +153,8 @@ export type ClientExecutionResult = {
 export type EventWrapper<EventData> = {
   standard: string;
   version: string;
+  event_nonce: number;
+  event_seq?: number;
   event: string;
   data: EventData;
 };
@@ -177,6 +179,15 @@ type WSEvent<EventData> = {
 type BountyCreatedEventData = {
   bounty_id: string;
   node_ids: string[];
+  owner_id?: string;
+  file_location?: string;
+  file_download_protocol?: SupportedFileDownloadProtocols;
+  min_nodes?: number;
+  timeout_seconds?: number;
+  network_required?: boolean;
+  gpu_required?: boolean;
+  amt_storage?: string;
+  amt_node_reward?: string;
 };
 
 export type CreateBountyArgs = {